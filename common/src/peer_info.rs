@@ -1,4 +1,9 @@
 //! Defines a common struct for describing a peer.
+use std::io::Read;
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -37,6 +42,27 @@ impl Peer {
             Peer::Ipfs(p) => p.p2p_addrs.as_slice(),
         }
     }
+    /// Report the name of the ceramic spec the peer was created from, if any.
+    pub fn spec_name(&self) -> Option<&str> {
+        match self {
+            Peer::Ceramic(p) => p.spec_name.as_deref(),
+            Peer::Ipfs(_) => None,
+        }
+    }
+    /// Report the number of peers the peer is connected to, if known.
+    pub fn connected_peers(&self) -> Option<i32> {
+        match self {
+            Peer::Ceramic(p) => p.connected_peers,
+            Peer::Ipfs(p) => p.connected_peers,
+        }
+    }
+    /// Record the number of peers the peer is connected to.
+    pub fn set_connected_peers(&mut self, connected_peers: Option<i32>) {
+        match self {
+            Peer::Ceramic(p) => p.connected_peers = connected_peers,
+            Peer::Ipfs(p) => p.connected_peers = connected_peers,
+        }
+    }
 }
 
 /// Describes a peer that participates via Ceramic protocols.
@@ -52,6 +78,12 @@ pub struct CeramicPeerInfo {
     /// Set of p2p addresses of the peer.
     /// Each address contains the /p2p/<peer_id> protocol.
     pub p2p_addrs: Vec<String>,
+    /// Name of the ceramic spec the peer was created from, i.e. the name of its stateful set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spec_name: Option<String>,
+    /// Number of peers the peer is connected to, unset when its status is unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connected_peers: Option<i32>,
 }
 /// Describes a peer that only participates using IPFS protocols.
 #[derive(Default, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
@@ -64,4 +96,21 @@ pub struct IpfsPeerInfo {
     /// Set of p2p addresses of the peer.
     /// Each address contains the /p2p/<peer_id> protocol.
     pub p2p_addrs: Vec<String>,
+    /// Number of peers the peer is connected to, unset when its status is unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connected_peers: Option<i32>,
+}
+
+/// Encode the peers as gzipped JSON in base64, so they can be stored as text in a config map.
+pub fn compress_peers(peers: &[Peer]) -> Result<String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    serde_json::to_writer(&mut encoder, peers)?;
+    Ok(STANDARD.encode(encoder.finish()?))
+}
+
+/// Decode peers encoded by [`compress_peers`].
+pub fn decompress_peers(data: &str) -> Result<Vec<Peer>> {
+    let mut json = String::new();
+    GzDecoder::new(STANDARD.decode(data.trim())?.as_slice()).read_to_string(&mut json)?;
+    Ok(serde_json::from_str(&json)?)
 }
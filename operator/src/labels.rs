@@ -1,5 +1,7 @@
 use std::collections::BTreeMap;
 
+use crate::utils::ConfigError;
+
 /// Create lables that can be used as a unique selector for a given app name.
 pub fn selector_labels(app: &str) -> Option<BTreeMap<String, String>> {
     Some(BTreeMap::from_iter(vec![(
@@ -18,3 +20,32 @@ pub fn managed_labels() -> Option<BTreeMap<String, String>> {
         "keramik".to_owned(),
     )]))
 }
+
+/// Label of the revision of the code under test, so results can be correlated with builds.
+pub const REVISION_LABEL: &str = "keramik.3box.io/revision";
+
+/// Add the revision label to the labels when a revision is set.
+pub fn insert_revision_label(labels: &mut BTreeMap<String, String>, revision: Option<&str>) {
+    if let Some(revision) = revision {
+        labels.insert(REVISION_LABEL.to_owned(), revision.to_owned());
+    }
+}
+
+/// Check the revision is a valid label value, at most 63 alphanumeric characters, `-`, `_`
+/// or `.` that start and end with an alphanumeric character.
+pub fn validate_revision(revision: &str) -> Result<(), ConfigError> {
+    let valid = revision.len() <= 63
+        && revision.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && revision.ends_with(|c: char| c.is_ascii_alphanumeric())
+        && revision
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(ConfigError::new(
+            "revision",
+            format!("{revision} is not a valid label value"),
+        ))
+    }
+}
@@ -3,6 +3,7 @@
 use anyhow::Result;
 use clap::{command, Parser, Subcommand};
 use opentelemetry::{global::shutdown_tracer_provider, Context};
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -16,6 +17,10 @@ struct Cli {
         default_value = "http://localhost:4317"
     )]
     otlp_endpoint: String,
+
+    /// Seconds after which a request to the IPFS RPC API of a peer times out.
+    #[arg(long, env = "OPERATOR_IPFS_RPC_TIMEOUT_SECONDS", default_value_t = 10)]
+    ipfs_rpc_timeout_seconds: u64,
 }
 
 /// Available Subcommands
@@ -34,9 +39,10 @@ async fn main() -> Result<()> {
 
     match args.command {
         Command::Daemon => {
+            let rpc_timeout = Duration::from_secs(args.ipfs_rpc_timeout_seconds);
             tokio::join!(
-                keramik_operator::network::run(),
-                // keramik_operator::simulation::run()
+                keramik_operator::network::run(rpc_timeout),
+                // keramik_operator::simulation::run(rpc_timeout)
             );
         }
     };
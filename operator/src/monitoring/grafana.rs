@@ -0,0 +1,243 @@
+use std::collections::BTreeMap;
+
+use k8s_openapi::{
+    api::{
+        apps::v1::StatefulSetSpec,
+        core::v1::{
+            ConfigMapVolumeSource, Container, ContainerPort, EnvVar, KeyToPath, PodSpec,
+            PodTemplateSpec, ResourceRequirements, ServicePort, ServiceSpec, Volume, VolumeMount,
+        },
+    },
+    apimachinery::pkg::{
+        api::resource::Quantity, apis::meta::v1::LabelSelector, apis::meta::v1::ObjectMeta,
+        util::intstr::IntOrString,
+    },
+};
+
+use crate::labels::selector_labels;
+use crate::simulation::{
+    controller::{GRAFANA_CONFIG_MAP_NAME, GRAFANA_DASHBOARDS_CONFIG_MAP_NAME, PROM_SERVICE_NAME},
+    GrafanaRole, GrafanaSpec,
+};
+use crate::utils::ConfigError;
+
+pub const GRAFANA_APP: &str = "grafana";
+/// Label the Grafana sidecar uses to discover dashboard config maps.
+pub const GRAFANA_DASHBOARD_LABEL: &str = "grafana_dashboard";
+
+/// Configuration of the Grafana instance deployed alongside a simulation.
+pub struct GrafanaConfig {
+    pub image: String,
+    pub image_pull_policy: String,
+    pub anonymous_role: GrafanaRole,
+}
+
+impl Default for GrafanaConfig {
+    fn default() -> Self {
+        Self {
+            image: "grafana/grafana:10.1.5".to_owned(),
+            image_pull_policy: "IfNotPresent".to_owned(),
+            anonymous_role: GrafanaRole::default(),
+        }
+    }
+}
+
+impl TryFrom<&GrafanaSpec> for GrafanaConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: &GrafanaSpec) -> Result<Self, Self::Error> {
+        let default = Self::default();
+        Ok(Self {
+            image: value.image.to_owned().unwrap_or(default.image),
+            image_pull_policy: value
+                .image_pull_policy
+                .to_owned()
+                .unwrap_or(default.image_pull_policy),
+            anonymous_role: value.anonymous_role.unwrap_or(default.anonymous_role),
+        })
+    }
+}
+
+/// Name of the role as spelled by Grafana.
+fn role_name(role: GrafanaRole) -> &'static str {
+    match role {
+        GrafanaRole::Viewer => "Viewer",
+        GrafanaRole::Editor => "Editor",
+        GrafanaRole::Admin => "Admin",
+    }
+}
+
+pub fn service_spec() -> ServiceSpec {
+    ServiceSpec {
+        ports: Some(vec![ServicePort {
+            name: Some("webui".to_owned()),
+            port: 3000,
+            protocol: Some("TCP".to_owned()),
+            target_port: Some(IntOrString::Int(3000)),
+            ..Default::default()
+        }]),
+        selector: selector_labels(GRAFANA_APP),
+        type_: Some("ClusterIP".to_owned()),
+        ..Default::default()
+    }
+}
+
+pub fn stateful_set_spec(config: &GrafanaConfig) -> StatefulSetSpec {
+    StatefulSetSpec {
+        replicas: Some(1),
+        selector: LabelSelector {
+            match_labels: selector_labels(GRAFANA_APP),
+            ..Default::default()
+        },
+        template: PodTemplateSpec {
+            metadata: Some(ObjectMeta {
+                labels: selector_labels(GRAFANA_APP),
+                ..Default::default()
+            }),
+            spec: Some(PodSpec {
+                containers: vec![Container {
+                    name: "grafana".to_owned(),
+                    image: Some(config.image.to_owned()),
+                    image_pull_policy: Some(config.image_pull_policy.to_owned()),
+                    ports: Some(vec![ContainerPort {
+                        container_port: 3000,
+                        name: Some("webui".to_owned()),
+                        ..Default::default()
+                    }]),
+                    env: Some(vec![
+                        EnvVar {
+                            name: "GF_AUTH_ANONYMOUS_ENABLED".to_owned(),
+                            value: Some("true".to_owned()),
+                            ..Default::default()
+                        },
+                        EnvVar {
+                            name: "GF_AUTH_ANONYMOUS_ORG_ROLE".to_owned(),
+                            value: Some(role_name(config.anonymous_role).to_owned()),
+                            ..Default::default()
+                        },
+                    ]),
+                    resources: Some(ResourceRequirements {
+                        limits: Some(BTreeMap::from_iter(vec![
+                            ("cpu".to_owned(), Quantity("250m".to_owned())),
+                            ("ephemeral-storage".to_owned(), Quantity("1Gi".to_owned())),
+                            ("memory".to_owned(), Quantity("512Mi".to_owned())),
+                        ])),
+                        requests: Some(BTreeMap::from_iter(vec![
+                            ("cpu".to_owned(), Quantity("250m".to_owned())),
+                            ("ephemeral-storage".to_owned(), Quantity("1Gi".to_owned())),
+                            ("memory".to_owned(), Quantity("512Mi".to_owned())),
+                        ])),
+                        ..Default::default()
+                    }),
+                    volume_mounts: Some(vec![
+                        VolumeMount {
+                            mount_path: "/etc/grafana/provisioning/datasources".to_owned(),
+                            name: "config".to_owned(),
+                            read_only: Some(true),
+                            sub_path: Some("datasources".to_owned()),
+                            ..Default::default()
+                        },
+                        VolumeMount {
+                            mount_path: "/etc/grafana/provisioning/dashboards".to_owned(),
+                            name: "config".to_owned(),
+                            read_only: Some(true),
+                            sub_path: Some("dashboards".to_owned()),
+                            ..Default::default()
+                        },
+                        VolumeMount {
+                            mount_path: "/var/lib/grafana/dashboards".to_owned(),
+                            name: "dashboards".to_owned(),
+                            read_only: Some(true),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                }],
+                volumes: Some(vec![
+                    Volume {
+                        config_map: Some(ConfigMapVolumeSource {
+                            default_mode: Some(0o755),
+                            name: Some(GRAFANA_CONFIG_MAP_NAME.to_owned()),
+                            items: Some(vec![
+                                KeyToPath {
+                                    key: "datasources.yaml".to_owned(),
+                                    path: "datasources/datasources.yaml".to_owned(),
+                                    ..Default::default()
+                                },
+                                KeyToPath {
+                                    key: "dashboards.yaml".to_owned(),
+                                    path: "dashboards/dashboards.yaml".to_owned(),
+                                    ..Default::default()
+                                },
+                            ]),
+                            ..Default::default()
+                        }),
+                        name: "config".to_owned(),
+                        ..Default::default()
+                    },
+                    Volume {
+                        config_map: Some(ConfigMapVolumeSource {
+                            default_mode: Some(0o755),
+                            name: Some(GRAFANA_DASHBOARDS_CONFIG_MAP_NAME.to_owned()),
+                            ..Default::default()
+                        }),
+                        name: "dashboards".to_owned(),
+                        ..Default::default()
+                    },
+                ]),
+                ..Default::default()
+            }),
+        },
+        ..Default::default()
+    }
+}
+
+/// Provisioning config wiring Grafana to the simulation Prometheus and the bundled dashboards.
+pub fn config_map_data() -> BTreeMap<String, String> {
+    BTreeMap::from_iter(vec![
+        (
+            "datasources.yaml".to_owned(),
+            format!(
+                r#"apiVersion: 1
+datasources:
+  - name: Prometheus
+    uid: prometheus
+    type: prometheus
+    access: proxy
+    url: http://{PROM_SERVICE_NAME}:9090
+    isDefault: true
+"#
+            ),
+        ),
+        (
+            "dashboards.yaml".to_owned(),
+            r#"apiVersion: 1
+providers:
+  - name: keramik
+    type: file
+    options:
+      path: /var/lib/grafana/dashboards
+"#
+            .to_owned(),
+        ),
+    ])
+}
+
+/// Dashboards preloaded into Grafana.
+pub fn dashboards_config_map_data() -> BTreeMap<String, String> {
+    BTreeMap::from_iter(vec![
+        (
+            "ceramic.json".to_owned(),
+            include_str!("dashboards/ceramic.json").to_owned(),
+        ),
+        (
+            "ipfs.json".to_owned(),
+            include_str!("dashboards/ipfs.json").to_owned(),
+        ),
+    ])
+}
+
+/// Labels marking a config map as holding dashboards for the Grafana sidecar.
+pub fn dashboard_labels() -> BTreeMap<String, String> {
+    BTreeMap::from_iter(vec![(GRAFANA_DASHBOARD_LABEL.to_owned(), "1".to_owned())])
+}
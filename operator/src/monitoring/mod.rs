@@ -1,4 +1,5 @@
 //! OTEL Resources
+pub(crate) mod grafana;
 pub(crate) mod jaeger;
 pub(crate) mod opentelemetry;
 pub(crate) mod prometheus;
@@ -200,10 +200,13 @@ pub fn cluster_role_binding(ns: &str) -> ClusterRoleBinding {
     }
 }
 
-pub fn config_map_data() -> BTreeMap<String, String> {
+/// Data of the OpenTelemetry collector config map.
+/// When open_metrics is set the collector exposes the scraped metrics with their exemplars.
+pub fn config_map_data(open_metrics: bool) -> BTreeMap<String, String> {
     BTreeMap::from_iter(vec![(
         "otel-config.yaml".to_owned(),
-        r#"
+        format!(
+            r#"
     receivers:
       # Push based metrics
       otlp:
@@ -269,6 +272,7 @@ pub fn config_map_data() -> BTreeMap<String, String> {
         metric_expiration: 1h
         resource_to_telemetry_conversion: 
           enabled: true
+        enable_open_metrics: {open_metrics}
       parquet:
         path: /data/
       # Grafana Cloud export
@@ -307,6 +311,6 @@ pub fn config_map_data() -> BTreeMap<String, String> {
         metrics:
           level: detailed
           address: 0.0.0.0:8888"#
-            .to_owned(),
+        ),
     )])
 }
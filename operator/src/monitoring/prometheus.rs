@@ -5,21 +5,109 @@ use k8s_openapi::{
         apps::v1::StatefulSetSpec,
         core::v1::{
             ConfigMapVolumeSource, Container, ContainerPort, PodSpec, PodTemplateSpec,
-            ResourceRequirements, Volume, VolumeMount,
+            ResourceRequirements, ServicePort, ServiceSpec, Volume, VolumeMount,
         },
     },
     apimachinery::pkg::{
         api::resource::Quantity, apis::meta::v1::LabelSelector, apis::meta::v1::ObjectMeta,
+        util::intstr::IntOrString,
     },
 };
 
-use crate::simulation::controller::PROM_CONFIG_MAP_NAME;
+use crate::simulation::{controller::PROM_CONFIG_MAP_NAME, SimulationSpec};
 
 use crate::labels::selector_labels;
+use crate::utils::ConfigError;
 
 pub const PROM_APP: &str = "prometheus";
 
-pub fn stateful_set_spec() -> StatefulSetSpec {
+/// Configuration of the Prometheus instance deployed alongside a simulation.
+#[derive(Default)]
+pub struct PrometheusConfig {
+    /// Scrape and store exemplars in the OpenMetrics format.
+    pub open_metrics: bool,
+    /// Interval between scrapes, every 10s with a 5s timeout when unset.
+    pub scrape_interval: Option<String>,
+    /// How long samples are kept, the 15d default of Prometheus when unset.
+    pub retention: Option<String>,
+}
+
+impl TryFrom<&SimulationSpec> for PrometheusConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: &SimulationSpec) -> Result<Self, Self::Error> {
+        let monitoring = value.monitoring.as_ref();
+        let scrape_interval = monitoring.and_then(|monitoring| monitoring.scrape_interval.clone());
+        let retention = monitoring.and_then(|monitoring| monitoring.retention.clone());
+        if let Some(scrape_interval) = &scrape_interval {
+            validate_duration("monitoring.scrapeInterval", scrape_interval)?;
+        }
+        if let Some(retention) = &retention {
+            validate_duration("monitoring.retention", retention)?;
+        }
+        Ok(Self {
+            open_metrics: value.open_metrics.unwrap_or(false),
+            scrape_interval,
+            retention,
+        })
+    }
+}
+
+/// Check the value is a Prometheus duration, e.g. 30s or 1h30m, which Prometheus would
+/// otherwise reject at startup.
+fn validate_duration(field: &'static str, value: &str) -> Result<(), ConfigError> {
+    if value.is_empty() {
+        return Err(ConfigError::new(field, "must not be empty"));
+    }
+    let mut rest = value;
+    while !rest.is_empty() {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let unit = ["ms", "s", "m", "h", "d", "w", "y"]
+            .into_iter()
+            .find(|unit| rest[digits..].starts_with(unit));
+        match unit {
+            Some(unit) if digits > 0 => rest = &rest[digits + unit.len()..],
+            _ => {
+                return Err(ConfigError::new(
+                    field,
+                    format!("{value} is not a duration, e.g. 30s or 1h30m"),
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn service_spec() -> ServiceSpec {
+    ServiceSpec {
+        ports: Some(vec![ServicePort {
+            name: Some("webui".to_owned()),
+            port: 9090,
+            protocol: Some("TCP".to_owned()),
+            target_port: Some(IntOrString::Int(9090)),
+            ..Default::default()
+        }]),
+        selector: selector_labels(PROM_APP),
+        type_: Some("ClusterIP".to_owned()),
+        ..Default::default()
+    }
+}
+
+/// Spec of the Prometheus stateful set.
+/// When open_metrics is set exemplars scraped in the OpenMetrics format are stored.
+pub fn stateful_set_spec(config: &PrometheusConfig) -> StatefulSetSpec {
+    let mut command = vec![
+        "/bin/prometheus".to_owned(),
+        "--web.enable-lifecycle".to_owned(),
+        "--config.file=/config/prom-config.yaml".to_owned(),
+    ];
+    if config.open_metrics {
+        command.push("--enable-feature=exemplar-storage".to_owned());
+    }
+    if let Some(retention) = &config.retention {
+        command.push(format!("--storage.tsdb.retention.time={retention}"));
+    }
+
     StatefulSetSpec {
         replicas: Some(1),
         selector: LabelSelector {
@@ -34,12 +122,8 @@ pub fn stateful_set_spec() -> StatefulSetSpec {
             spec: Some(PodSpec {
                 containers: vec![Container {
                     name: "prometheus".to_owned(),
-                    image: Some("prom/prometheus:v2.42.0".to_owned()),
-                    command: Some(vec![
-                        "/bin/prometheus".to_owned(),
-                        "--web.enable-lifecycle".to_owned(),
-                        "--config.file=/config/prom-config.yaml".to_owned(),
-                    ]),
+                    image: Some("prom/prometheus:v2.49.1".to_owned()),
+                    command: Some(command),
                     ports: Some(vec![ContainerPort {
                         container_port: 9090,
                         name: Some("webui".to_owned()),
@@ -83,13 +167,31 @@ pub fn stateful_set_spec() -> StatefulSetSpec {
     }
 }
 
-pub fn config_map_data() -> BTreeMap<String, String> {
+/// Data of the Prometheus config map.
+/// When open_metrics is set targets are asked for the OpenMetrics format first,
+/// which is the only format carrying exemplars.
+pub fn config_map_data(config: &PrometheusConfig) -> BTreeMap<String, String> {
+    // Prometheus bounds its default timeout by the interval, so a custom interval may be shorter
+    // than the 5s timeout used by default
+    let scrape_interval = match &config.scrape_interval {
+        Some(scrape_interval) => format!("scrape_interval: {scrape_interval}"),
+        None => "scrape_interval: 10s\n          scrape_timeout: 5s".to_owned(),
+    };
+    let scrape_protocols = if config.open_metrics {
+        r#"
+          scrape_protocols:
+            - OpenMetricsText1.0.0
+            - OpenMetricsText0.0.1
+            - PrometheusText0.0.4"#
+    } else {
+        ""
+    };
     BTreeMap::from_iter(vec![(
         "prom-config.yaml".to_owned(),
-        r#"
+        format!(
+            r#"
         global:
-          scrape_interval: 10s
-          scrape_timeout: 5s
+          {scrape_interval}{scrape_protocols}
         
         scrape_configs:
           - job_name: services
@@ -100,6 +202,6 @@ pub fn config_map_data() -> BTreeMap<String, String> {
                 - 'localhost:9090'
                 - 'otel:9090'
                 - 'otel:8888'"#
-            .to_owned(),
+        ),
     )])
 }
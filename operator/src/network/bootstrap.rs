@@ -1,11 +1,13 @@
 use k8s_openapi::api::{
     batch::v1::JobSpec,
-    core::v1::{
-        ConfigMapVolumeSource, Container, EnvVar, PodSpec, PodTemplateSpec, Volume, VolumeMount,
-    },
+    core::v1::{Container, EnvVar, PodSpec, PodTemplateSpec, VolumeMount},
 };
 
-use crate::network::{BootstrapSpec, PEERS_CONFIG_MAP_NAME};
+use crate::network::{peers::peers_volume, BootstrapSpec};
+use crate::utils::ConfigError;
+
+/// Bootstrap method that leaves the peers to discover each other.
+pub const BOOTSTRAP_METHOD_NONE: &str = "none";
 
 // BootstrapConfig defines which properties of the JobSpec can be customized.
 pub struct BootstrapConfig {
@@ -13,6 +15,8 @@ pub struct BootstrapConfig {
     pub image_pull_policy: String,
     pub method: String,
     pub n: i32,
+    pub center: Option<i32>,
+    pub target_peer_types: Option<Vec<String>>,
 }
 
 // Define clear defaults for this config
@@ -23,33 +27,107 @@ impl Default for BootstrapConfig {
             image_pull_policy: "Always".to_owned(),
             method: "sentinel".to_owned(),
             n: 3,
+            center: None,
+            target_peer_types: None,
         }
     }
 }
 
-impl From<Option<BootstrapSpec>> for BootstrapConfig {
-    fn from(value: Option<BootstrapSpec>) -> Self {
+impl BootstrapConfig {
+    /// Reports if a bootstrap job connects the peers.
+    pub fn is_enabled(&self) -> bool {
+        self.method != BOOTSTRAP_METHOD_NONE
+    }
+}
+
+impl TryFrom<Option<BootstrapSpec>> for BootstrapConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: Option<BootstrapSpec>) -> Result<Self, Self::Error> {
         match value {
-            Some(spec) => spec.into(),
-            None => BootstrapConfig::default(),
+            Some(spec) => spec.try_into(),
+            None => Ok(BootstrapConfig::default()),
         }
     }
 }
 
-impl From<BootstrapSpec> for BootstrapConfig {
-    fn from(value: BootstrapSpec) -> Self {
+impl TryFrom<BootstrapSpec> for BootstrapConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: BootstrapSpec) -> Result<Self, Self::Error> {
+        if let Some(center) = value.center {
+            if center < 0 {
+                return Err(ConfigError::new(
+                    "bootstrap.center",
+                    format!("{center} must not be negative"),
+                ));
+            }
+        }
+        if let Some(peer_type) = value
+            .target_peer_types
+            .iter()
+            .flatten()
+            .find(|peer_type| !["ceramic", "ipfs"].contains(&peer_type.as_str()))
+        {
+            return Err(ConfigError::new(
+                "bootstrap.targetPeerTypes",
+                format!("unknown peer type {peer_type}, expected ceramic or ipfs"),
+            ));
+        }
         let default = Self::default();
-        Self {
+        Ok(Self {
             image: value.image.unwrap_or(default.image),
             image_pull_policy: value.image_pull_policy.unwrap_or(default.image_pull_policy),
             method: value.method.unwrap_or(default.method),
             n: value.n.unwrap_or(default.n),
-        }
+            center: value.center,
+            target_peer_types: value.target_peer_types,
+        })
     }
 }
 
-pub fn bootstrap_job_spec(config: impl Into<BootstrapConfig>) -> JobSpec {
-    let config = config.into();
+pub fn bootstrap_job_spec(config: BootstrapConfig) -> JobSpec {
+    let mut env = vec![
+        EnvVar {
+            name: "RUNNER_OTLP_ENDPOINT".to_owned(),
+            value: Some("http://otel:4317".to_owned()),
+            ..Default::default()
+        },
+        EnvVar {
+            name: "RUST_LOG".to_owned(),
+            value: Some("info,keramik_runner=debug".to_owned()),
+            ..Default::default()
+        },
+        EnvVar {
+            name: "BOOTSTRAP_METHOD".to_owned(),
+            value: Some(config.method.to_owned()),
+            ..Default::default()
+        },
+        EnvVar {
+            name: "BOOTSTRAP_N".to_owned(),
+            value: Some(config.n.to_string()),
+            ..Default::default()
+        },
+    ];
+    if let Some(center) = config.center {
+        env.push(EnvVar {
+            name: "BOOTSTRAP_CENTER".to_owned(),
+            value: Some(center.to_string()),
+            ..Default::default()
+        });
+    }
+    if let Some(target_peer_types) = &config.target_peer_types {
+        env.push(EnvVar {
+            name: "BOOTSTRAP_TARGET_PEER_TYPES".to_owned(),
+            value: Some(target_peer_types.join(",")),
+            ..Default::default()
+        });
+    }
+    env.push(EnvVar {
+        name: "BOOTSTRAP_PEERS_PATH".to_owned(),
+        value: Some("/keramik-peers/peers.json".to_owned()),
+        ..Default::default()
+    });
     JobSpec {
         backoff_limit: Some(4),
         template: PodTemplateSpec {
@@ -62,33 +140,7 @@ pub fn bootstrap_job_spec(config: impl Into<BootstrapConfig>) -> JobSpec {
                         "/usr/bin/keramik-runner".to_owned(),
                         "bootstrap".to_owned(),
                     ]),
-                    env: Some(vec![
-                        EnvVar {
-                            name: "RUNNER_OTLP_ENDPOINT".to_owned(),
-                            value: Some("http://otel:4317".to_owned()),
-                            ..Default::default()
-                        },
-                        EnvVar {
-                            name: "RUST_LOG".to_owned(),
-                            value: Some("info,keramik_runner=debug".to_owned()),
-                            ..Default::default()
-                        },
-                        EnvVar {
-                            name: "BOOTSTRAP_METHOD".to_owned(),
-                            value: Some(config.method.to_owned()),
-                            ..Default::default()
-                        },
-                        EnvVar {
-                            name: "BOOTSTRAP_N".to_owned(),
-                            value: Some(config.n.to_string()),
-                            ..Default::default()
-                        },
-                        EnvVar {
-                            name: "BOOTSTRAP_PEERS_PATH".to_owned(),
-                            value: Some("/keramik-peers/peers.json".to_owned()),
-                            ..Default::default()
-                        },
-                    ]),
+                    env: Some(env),
                     volume_mounts: Some(vec![VolumeMount {
                         mount_path: "/keramik-peers".to_owned(),
                         name: "keramik-peers".to_owned(),
@@ -96,15 +148,7 @@ pub fn bootstrap_job_spec(config: impl Into<BootstrapConfig>) -> JobSpec {
                     }]),
                     ..Default::default()
                 }],
-                volumes: Some(vec![Volume {
-                    config_map: Some(ConfigMapVolumeSource {
-                        default_mode: Some(0o755),
-                        name: Some(PEERS_CONFIG_MAP_NAME.to_owned()),
-                        ..Default::default()
-                    }),
-                    name: "keramik-peers".to_owned(),
-                    ..Default::default()
-                }]),
+                volumes: Some(vec![peers_volume()]),
                 restart_policy: Some("Never".to_owned()),
                 ..Default::default()
             }),
@@ -113,3 +157,59 @@ pub fn bootstrap_job_spec(config: impl Into<BootstrapConfig>) -> JobSpec {
         ..Default::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_value(spec: &JobSpec, name: &str) -> Option<String> {
+        spec.template.spec.as_ref().unwrap().containers[0]
+            .env
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|env| env.name == name)
+            .and_then(|env| env.value.clone())
+    }
+
+    #[test]
+    fn target_peer_types_env() {
+        let spec = bootstrap_job_spec(
+            BootstrapSpec {
+                target_peer_types: Some(vec!["ceramic".to_owned(), "ipfs".to_owned()]),
+                ..Default::default()
+            }
+            .try_into()
+            .unwrap(),
+        );
+        assert_eq!(
+            Some("ceramic,ipfs".to_owned()),
+            env_value(&spec, "BOOTSTRAP_TARGET_PEER_TYPES")
+        );
+    }
+
+    #[test]
+    fn all_peer_types_by_default() {
+        let spec = bootstrap_job_spec(BootstrapSpec::default().try_into().unwrap());
+        assert_eq!(None, env_value(&spec, "BOOTSTRAP_TARGET_PEER_TYPES"));
+    }
+
+    #[test]
+    fn invalid_bootstrap_specs() {
+        let error = |spec: BootstrapSpec| BootstrapConfig::try_from(spec).err().unwrap().field;
+        assert_eq!(
+            "bootstrap.center",
+            error(BootstrapSpec {
+                center: Some(-1),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            "bootstrap.targetPeerTypes",
+            error(BootstrapSpec {
+                target_peer_types: Some(vec!["ceramic".to_owned(), "cas".to_owned()]),
+                ..Default::default()
+            })
+        );
+    }
+}
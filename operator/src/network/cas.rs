@@ -17,7 +17,8 @@ use k8s_openapi::{
 use kube::core::ObjectMeta;
 
 use crate::labels::{managed_labels, selector_labels};
-use crate::network::{resource_limits::ResourceLimitsConfig, CasSpec};
+use crate::network::{resource_limits::ResourceLimitsConfig, CasAwsSpec, CasQueueBackend, CasSpec};
+use crate::utils::ConfigError;
 
 use crate::network::{
     controller::{
@@ -33,9 +34,52 @@ pub struct CasConfig {
     pub image_pull_policy: String,
     pub cas_resource_limits: ResourceLimitsConfig,
     pub ipfs_resource_limits: ResourceLimitsConfig,
+    pub ipfs_storage: Quantity,
     pub ganache_resource_limits: ResourceLimitsConfig,
     pub postgres_resource_limits: ResourceLimitsConfig,
     pub localstack_resource_limits: ResourceLimitsConfig,
+    pub queue_backend: CasQueueBackend,
+    pub worker_count: u32,
+    pub deploy_localstack: bool,
+    pub aws: CasAwsConfig,
+}
+
+/// AWS services used by CAS when LocalStack is not deployed.
+pub struct CasAwsConfig {
+    pub region: String,
+    pub credentials_secret: String,
+    pub sqs_queue_url: String,
+    pub s3_endpoint: Option<String>,
+    pub s3_bucket: String,
+}
+
+impl Default for CasAwsConfig {
+    fn default() -> Self {
+        Self {
+            region: "us-east-1".to_owned(),
+            credentials_secret: "cas-aws".to_owned(),
+            sqs_queue_url: "".to_owned(),
+            s3_endpoint: None,
+            s3_bucket: "merkle-car".to_owned(),
+        }
+    }
+}
+
+impl TryFrom<CasAwsSpec> for CasAwsConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: CasAwsSpec) -> Result<Self, Self::Error> {
+        let default = Self::default();
+        Ok(Self {
+            region: value.region.unwrap_or(default.region),
+            credentials_secret: value
+                .credentials_secret
+                .unwrap_or(default.credentials_secret),
+            sqs_queue_url: value.sqs_queue_url.unwrap_or(default.sqs_queue_url),
+            s3_endpoint: value.s3_endpoint,
+            s3_bucket: value.s3_bucket.unwrap_or(default.s3_bucket),
+        })
+    }
 }
 
 // Define clear defaults for this config
@@ -54,6 +98,7 @@ impl Default for CasConfig {
                 memory: Quantity("512Mi".to_owned()),
                 storage: Quantity("1Gi".to_owned()),
             },
+            ipfs_storage: Quantity("10Gi".to_owned()),
             ganache_resource_limits: ResourceLimitsConfig {
                 cpu: Quantity("250m".to_owned()),
                 memory: Quantity("1Gi".to_owned()),
@@ -69,23 +114,31 @@ impl Default for CasConfig {
                 memory: Quantity("1Gi".to_owned()),
                 storage: Quantity("1Gi".to_owned()),
             },
+            queue_backend: CasQueueBackend::default(),
+            worker_count: 1,
+            deploy_localstack: true,
+            aws: CasAwsConfig::default(),
         }
     }
 }
 
-impl From<Option<CasSpec>> for CasConfig {
-    fn from(value: Option<CasSpec>) -> Self {
+impl TryFrom<Option<CasSpec>> for CasConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: Option<CasSpec>) -> Result<Self, Self::Error> {
         match value {
-            Some(spec) => spec.into(),
-            None => Default::default(),
+            Some(spec) => spec.try_into(),
+            None => Ok(Default::default()),
         }
     }
 }
 
-impl From<CasSpec> for CasConfig {
-    fn from(value: CasSpec) -> Self {
+impl TryFrom<CasSpec> for CasConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: CasSpec) -> Result<Self, Self::Error> {
         let default = Self::default();
-        Self {
+        let config = Self {
             image: value.image.unwrap_or(default.image),
             image_pull_policy: value.image_pull_policy.unwrap_or(default.image_pull_policy),
             cas_resource_limits: ResourceLimitsConfig::from_spec(
@@ -96,6 +149,7 @@ impl From<CasSpec> for CasConfig {
                 value.ipfs_resource_limits,
                 default.ipfs_resource_limits,
             ),
+            ipfs_storage: value.cas_ipfs_storage.unwrap_or(default.ipfs_storage),
             ganache_resource_limits: ResourceLimitsConfig::from_spec(
                 value.ganache_resource_limits,
                 default.ganache_resource_limits,
@@ -108,17 +162,66 @@ impl From<CasSpec> for CasConfig {
                 value.localstack_resource_limits,
                 default.localstack_resource_limits,
             ),
+            queue_backend: value.queue_backend.unwrap_or(default.queue_backend),
+            worker_count: value.worker_count.unwrap_or(default.worker_count),
+            deploy_localstack: value.deploy_localstack.unwrap_or(default.deploy_localstack),
+            aws: value
+                .aws
+                .map(CasAwsConfig::try_from)
+                .transpose()?
+                .unwrap_or(default.aws),
+        };
+        if config.worker_count == 0 {
+            return Err(ConfigError::new(
+                "cas.workerCount",
+                "CAS needs at least one worker to anchor",
+            ));
+        }
+        // Without LocalStack there is no queue to fall back to
+        if config.uses_sqs() && !config.deploy_localstack && config.aws.sqs_queue_url.is_empty() {
+            return Err(ConfigError::new(
+                "cas.aws.sqsQueueUrl",
+                "must be set to use sqs without LocalStack",
+            ));
         }
+        Ok(config)
+    }
+}
+
+impl CasConfig {
+    /// Reports whether CAS uses SQS, and therefore LocalStack, for its queue.
+    pub fn uses_sqs(&self) -> bool {
+        self.queue_backend == CasQueueBackend::Sqs
+    }
+
+    /// Reports whether LocalStack is deployed, it is only needed to emulate SQS.
+    pub fn uses_localstack(&self) -> bool {
+        self.deploy_localstack && self.uses_sqs()
+    }
+
+    /// Reports whether Merkle CARs can be stored in S3, either emulated by LocalStack or external.
+    pub fn uses_s3(&self) -> bool {
+        self.uses_localstack() || !self.deploy_localstack
+    }
+}
+
+/// Name of the container of the nth CAS worker, the first keeps the name of the sole worker.
+fn cas_worker_name(i: u32) -> String {
+    if i == 0 {
+        "cas-worker".to_owned()
+    } else {
+        format!("cas-worker-{i}")
     }
 }
 
 // TODO make this a deployment
 pub fn cas_stateful_set_spec(
     ns: &str,
-    config: impl Into<CasConfig>,
+    config: &CasConfig,
     datadog: &DataDogConfig,
 ) -> StatefulSetSpec {
-    let config = config.into();
+    let uses_sqs = config.uses_sqs();
+    let uses_localstack = config.uses_localstack();
     let pg_env = vec![
         EnvVar {
             name: "DB_NAME".to_owned(),
@@ -155,33 +258,103 @@ pub fn cas_stateful_set_spec(
             ..Default::default()
         },
     ];
-    let aws_env = vec![
-        EnvVar {
-            name: "AWS_ACCOUNT_ID".to_owned(),
-            value: Some("000000000000".to_owned()),
-            ..Default::default()
-        },
-        EnvVar {
-            name: "AWS_REGION".to_owned(),
-            value: Some("us-east-1".to_owned()),
-            ..Default::default()
-        },
-        EnvVar {
-            name: "AWS_ACCESS_KEY_ID".to_owned(),
-            value: Some(".".to_owned()),
-            ..Default::default()
-        },
-        EnvVar {
-            name: "AWS_SECRET_ACCESS_KEY".to_owned(),
-            value: Some(".".to_owned()),
-            ..Default::default()
-        },
-        EnvVar {
-            name: "SQS_QUEUE_URL".to_owned(),
-            value: Some("http://localstack:4566/000000000000/cas-anchor-dev-".to_owned()),
+    // An empty queue URL makes CAS use its in-memory queue
+    let sqs_queue_url = if uses_localstack {
+        "http://localstack:4566/000000000000/cas-anchor-dev-".to_owned()
+    } else if uses_sqs {
+        config.aws.sqs_queue_url.clone()
+    } else {
+        "".to_owned()
+    };
+    let aws_env = if config.deploy_localstack {
+        vec![
+            EnvVar {
+                name: "AWS_ACCOUNT_ID".to_owned(),
+                value: Some("000000000000".to_owned()),
+                ..Default::default()
+            },
+            EnvVar {
+                name: "AWS_REGION".to_owned(),
+                value: Some("us-east-1".to_owned()),
+                ..Default::default()
+            },
+            EnvVar {
+                name: "AWS_ACCESS_KEY_ID".to_owned(),
+                value: Some(".".to_owned()),
+                ..Default::default()
+            },
+            EnvVar {
+                name: "AWS_SECRET_ACCESS_KEY".to_owned(),
+                value: Some(".".to_owned()),
+                ..Default::default()
+            },
+            EnvVar {
+                name: "SQS_QUEUE_URL".to_owned(),
+                value: Some(sqs_queue_url),
+                ..Default::default()
+            },
+        ]
+    } else {
+        let credential = |name: &str, key: &str| EnvVar {
+            name: name.to_owned(),
+            value_from: Some(EnvVarSource {
+                secret_key_ref: Some(SecretKeySelector {
+                    key: key.to_owned(),
+                    name: Some(config.aws.credentials_secret.clone()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
             ..Default::default()
-        },
-    ];
+        };
+        vec![
+            EnvVar {
+                name: "AWS_REGION".to_owned(),
+                value: Some(config.aws.region.clone()),
+                ..Default::default()
+            },
+            credential("AWS_ACCESS_KEY_ID", "access-key-id"),
+            credential("AWS_SECRET_ACCESS_KEY", "secret-access-key"),
+            EnvVar {
+                name: "SQS_QUEUE_URL".to_owned(),
+                value: Some(sqs_queue_url),
+                ..Default::default()
+            },
+        ]
+    };
+    let s3_env = if config.deploy_localstack {
+        vec![
+            EnvVar {
+                name: "S3_BUCKET_NAME".to_owned(),
+                value: Some("merkle-car".to_owned()),
+                ..Default::default()
+            },
+            EnvVar {
+                name: "S3_ENDPOINT".to_owned(),
+                value: Some("http://localstack:4566".to_owned()),
+                ..Default::default()
+            },
+        ]
+    } else {
+        [
+            vec![EnvVar {
+                name: "S3_BUCKET_NAME".to_owned(),
+                value: Some(config.aws.s3_bucket.clone()),
+                ..Default::default()
+            }],
+            config
+                .aws
+                .s3_endpoint
+                .iter()
+                .map(|endpoint| EnvVar {
+                    name: "S3_ENDPOINT".to_owned(),
+                    value: Some(endpoint.to_owned()),
+                    ..Default::default()
+                })
+                .collect(),
+        ]
+        .concat()
+    };
     let eth_env = vec![
         EnvVar {
             name: "ETH_GAS_LIMIT".to_owned(),
@@ -228,20 +401,12 @@ pub fn cas_stateful_set_spec(
             },
             EnvVar {
                 name: "MERKLE_CAR_STORAGE_MODE".to_owned(),
-                value: Some("s3".to_owned()),
-                ..Default::default()
-            },
-            EnvVar {
-                name: "S3_BUCKET_NAME".to_owned(),
-                value: Some("merkle-car".to_owned()),
-                ..Default::default()
-            },
-            EnvVar {
-                name: "S3_ENDPOINT".to_owned(),
-                value: Some("http://localstack:4566".to_owned()),
+                // Merkle CARs are stored in LocalStack or external S3
+                value: Some(if config.uses_s3() { "s3" } else { "disabled" }.to_owned()),
                 ..Default::default()
             },
         ],
+        s3_env,
     ]
     .concat();
 
@@ -267,7 +432,7 @@ pub fn cas_stateful_set_spec(
     ]
     .concat();
 
-    datadog.inject_env(&mut cas_api_env);
+    datadog.inject_env(&mut cas_api_env, ns, CAS_APP);
 
     StatefulSetSpec {
         replicas: Some(1),
@@ -280,7 +445,7 @@ pub fn cas_stateful_set_spec(
             metadata: Some(ObjectMeta {
                 labels: selector_labels(CAS_APP).map(|mut lbls| {
                     lbls.append(&mut managed_labels().unwrap());
-                    datadog.inject_labels(&mut lbls, ns.to_owned(), "cas".to_owned());
+                    datadog.inject_labels(&mut lbls, ns, CAS_APP);
                     lbls
                 }),
 
@@ -291,74 +456,83 @@ pub fn cas_stateful_set_spec(
                 ..Default::default()
             }),
             spec: Some(PodSpec {
-                init_containers: Some(vec![
-                    Container {
-                        env: Some(eth_env),
-                        image: Some("public.ecr.aws/r5b3e0r5/3box/cas-contract".to_owned()),
-                        image_pull_policy: Some("IfNotPresent".to_owned()),
-                        name: "launch-contract".to_owned(),
-                        ..Default::default()
-                    },
-                    Container {
-                        env: Some(
-                            [
-                                pg_env.clone(),
-                                vec![EnvVar {
-                                    name: "NODE_ENV".to_owned(),
-                                    value: Some("dev".to_owned()),
-                                    ..Default::default()
-                                }],
-                            ]
-                            .concat(),
-                        ),
-                        command: Some(
-                            ["./node_modules/knex/bin/cli.js", "migrate:latest"]
+                init_containers: Some(
+                    vec![
+                        Container {
+                            env: Some(eth_env),
+                            image: Some("public.ecr.aws/r5b3e0r5/3box/cas-contract".to_owned()),
+                            image_pull_policy: Some("IfNotPresent".to_owned()),
+                            name: "launch-contract".to_owned(),
+                            ..Default::default()
+                        },
+                        Container {
+                            env: Some(
+                                [
+                                    pg_env.clone(),
+                                    vec![EnvVar {
+                                        name: "NODE_ENV".to_owned(),
+                                        value: Some("dev".to_owned()),
+                                        ..Default::default()
+                                    }],
+                                ]
+                                .concat(),
+                            ),
+                            command: Some(
+                                ["./node_modules/knex/bin/cli.js", "migrate:latest"]
+                                    .map(String::from)
+                                    .to_vec(),
+                            ),
+                            image: Some(config.image.clone()),
+                            image_pull_policy: Some(config.image_pull_policy.clone()),
+                            name: "cas-migrations".to_owned(),
+                            ..Default::default()
+                        },
+                    ]
+                    .into_iter()
+                    // Creating the merkle-car bucket requires LocalStack
+                    .chain(uses_localstack.then(|| {
+                        Container {
+                            env: Some(aws_env.clone()),
+                            command: Some(
+                                [
+                                    "aws",
+                                    "s3api",
+                                    "create-bucket",
+                                    "--bucket",
+                                    "merkle-car",
+                                    "--endpoint-url",
+                                    "http://localstack:4566",
+                                ]
                                 .map(String::from)
                                 .to_vec(),
-                        ),
-                        image: Some(config.image.clone()),
-                        image_pull_policy: Some(config.image_pull_policy.clone()),
-                        name: "cas-migrations".to_owned(),
-                        ..Default::default()
-                    },
-                    Container {
-                        env: Some(aws_env.clone()),
-                        command: Some(
-                            [
-                                "aws",
-                                "s3api",
-                                "create-bucket",
-                                "--bucket",
-                                "merkle-car",
-                                "--endpoint-url",
-                                "http://localstack:4566",
-                            ]
-                            .map(String::from)
-                            .to_vec(),
-                        ),
-                        image: Some("amazon/aws-cli".to_owned()),
-                        image_pull_policy: Some("IfNotPresent".to_owned()),
-                        name: "aws-cli".to_owned(),
-                        ..Default::default()
-                    },
-                ]),
-                containers: vec![
-                    Container {
-                        env: Some(cas_api_env),
-                        image: Some(config.image.clone()),
-                        image_pull_policy: Some(config.image_pull_policy.clone()),
-                        name: "cas-api".to_owned(),
-                        ports: Some(vec![ContainerPort {
-                            container_port: 8081,
+                            ),
+                            image: Some("amazon/aws-cli".to_owned()),
+                            image_pull_policy: Some("IfNotPresent".to_owned()),
+                            name: "aws-cli".to_owned(),
                             ..Default::default()
-                        }]),
-                        resources: Some(ResourceRequirements {
-                            limits: Some(config.cas_resource_limits.clone().into()),
-                            requests: Some(config.cas_resource_limits.clone().into()),
-                            ..Default::default()
-                        }),
+                        }
+                    }))
+                    .collect(),
+                ),
+                containers: vec![Container {
+                    env: Some(cas_api_env),
+                    image: Some(config.image.clone()),
+                    image_pull_policy: Some(config.image_pull_policy.clone()),
+                    name: "cas-api".to_owned(),
+                    ports: Some(vec![ContainerPort {
+                        container_port: 8081,
                         ..Default::default()
-                    },
+                    }]),
+                    resources: Some(ResourceRequirements {
+                        limits: Some(config.cas_resource_limits.clone().into()),
+                        requests: Some(config.cas_resource_limits.clone().into()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }]
+                .into_iter()
+                // Each long-lived worker anchors the pending requests concurrently
+                .chain((0..config.worker_count).map(|i| {
                     Container {
                         env: Some(
                             [
@@ -403,27 +577,33 @@ pub fn cas_stateful_set_spec(
                             ]
                             .concat(),
                         ),
-                        image: Some(config.image),
-                        image_pull_policy: Some(config.image_pull_policy),
-                        name: "cas-worker".to_owned(),
+                        image: Some(config.image.clone()),
+                        image_pull_policy: Some(config.image_pull_policy.clone()),
+                        name: cas_worker_name(i),
                         resources: Some(ResourceRequirements {
                             limits: Some(config.cas_resource_limits.clone().into()),
                             requests: Some(config.cas_resource_limits.clone().into()),
                             ..Default::default()
                         }),
                         ..Default::default()
-                    },
+                    }
+                }))
+                // The scheduler consumes the SQS queue, hosted by LocalStack unless external
+                .chain(uses_sqs.then(|| {
                     Container {
                         env: Some(
                             [
                                 pg_env,
                                 aws_env,
-                                vec![
-                                    EnvVar {
+                                uses_localstack
+                                    .then(|| EnvVar {
                                         name: "AWS_ENDPOINT".to_owned(),
                                         value: Some("http://localstack:4566".to_owned()),
                                         ..Default::default()
-                                    },
+                                    })
+                                    .into_iter()
+                                    .collect(),
+                                vec![
                                     EnvVar {
                                         name: "ANCHOR_BATCH_SIZE".to_owned(),
                                         value: Some("20".to_owned()),
@@ -445,8 +625,8 @@ pub fn cas_stateful_set_spec(
                                         value: Some("0s".to_owned()),
                                         ..Default::default()
                                     },
-                                    // Don't launch any workers through the scheduler since we're going to use a long-lived
-                                    // worker.
+                                    // Don't launch any workers through the scheduler since we're going to use long-lived
+                                    // workers.
                                     EnvVar {
                                         name: "MAX_ANCHOR_WORKERS".to_owned(),
                                         value: Some("0".to_owned()),
@@ -460,12 +640,13 @@ pub fn cas_stateful_set_spec(
                         name: "cas-scheduler".to_owned(),
                         resources: Some(ResourceRequirements {
                             limits: Some(config.cas_resource_limits.clone().into()),
-                            requests: Some(config.cas_resource_limits.into()),
+                            requests: Some(config.cas_resource_limits.clone().into()),
                             ..Default::default()
                         }),
                         ..Default::default()
-                    },
-                ],
+                    }
+                }))
+                .collect(),
                 volumes: Some(vec![Volume {
                     name: "cas-data".to_owned(),
                     persistent_volume_claim: Some(PersistentVolumeClaimVolumeSource {
@@ -513,8 +694,7 @@ pub fn cas_service_spec() -> ServiceSpec {
     }
 }
 
-pub fn cas_ipfs_stateful_set_spec(config: impl Into<CasConfig>) -> StatefulSetSpec {
-    let config = config.into();
+pub fn cas_ipfs_stateful_set_spec(config: &CasConfig) -> StatefulSetSpec {
     StatefulSetSpec {
         replicas: Some(1),
         selector: LabelSelector {
@@ -581,7 +761,7 @@ pub fn cas_ipfs_stateful_set_spec(config: impl Into<CasConfig>) -> StatefulSetSp
                     ]),
                     resources: Some(ResourceRequirements {
                         limits: Some(config.ipfs_resource_limits.clone().into()),
-                        requests: Some(config.ipfs_resource_limits.into()),
+                        requests: Some(config.ipfs_resource_limits.clone().into()),
                         ..Default::default()
                     }),
                     volume_mounts: Some(vec![VolumeMount {
@@ -612,7 +792,7 @@ pub fn cas_ipfs_stateful_set_spec(config: impl Into<CasConfig>) -> StatefulSetSp
                 resources: Some(ResourceRequirements {
                     requests: Some(BTreeMap::from_iter(vec![(
                         "storage".to_owned(),
-                        Quantity("10Gi".to_owned()),
+                        config.ipfs_storage.clone(),
                     )])),
                     ..Default::default()
                 }),
@@ -637,8 +817,7 @@ pub fn cas_ipfs_service_spec() -> ServiceSpec {
         ..Default::default()
     }
 }
-pub fn ganache_stateful_set_spec(config: impl Into<CasConfig>) -> StatefulSetSpec {
-    let config = config.into();
+pub fn ganache_stateful_set_spec(config: &CasConfig) -> StatefulSetSpec {
     StatefulSetSpec {
         replicas: Some(1),
         selector: LabelSelector {
@@ -671,7 +850,7 @@ pub fn ganache_stateful_set_spec(config: impl Into<CasConfig>) -> StatefulSetSpe
                     }]),
                     resources: Some(ResourceRequirements {
                         limits: Some(config.ganache_resource_limits.clone().into()),
-                        requests: Some(config.ganache_resource_limits.into()),
+                        requests: Some(config.ganache_resource_limits.clone().into()),
                         ..Default::default()
                     }),
                     volume_mounts: Some(vec![VolumeMount {
@@ -727,8 +906,7 @@ pub fn ganache_service_spec() -> ServiceSpec {
         ..Default::default()
     }
 }
-pub fn postgres_stateful_set_spec(config: impl Into<CasConfig>) -> StatefulSetSpec {
-    let config = config.into();
+pub fn postgres_stateful_set_spec(config: &CasConfig) -> StatefulSetSpec {
     StatefulSetSpec {
         replicas: Some(1),
         selector: LabelSelector {
@@ -784,7 +962,7 @@ pub fn postgres_stateful_set_spec(config: impl Into<CasConfig>) -> StatefulSetSp
                     }]),
                     resources: Some(ResourceRequirements {
                         limits: Some(config.postgres_resource_limits.clone().into()),
-                        requests: Some(config.postgres_resource_limits.into()),
+                        requests: Some(config.postgres_resource_limits.clone().into()),
                         ..Default::default()
                     }),
                     volume_mounts: Some(vec![VolumeMount {
@@ -847,8 +1025,7 @@ pub fn postgres_service_spec() -> ServiceSpec {
     }
 }
 
-pub fn localstack_stateful_set_spec(config: impl Into<CasConfig>) -> StatefulSetSpec {
-    let config = config.into();
+pub fn localstack_stateful_set_spec(config: &CasConfig) -> StatefulSetSpec {
     StatefulSetSpec {
         replicas: Some(1),
         selector: LabelSelector {
@@ -872,7 +1049,7 @@ pub fn localstack_stateful_set_spec(config: impl Into<CasConfig>) -> StatefulSet
                     }]),
                     resources: Some(ResourceRequirements {
                         limits: Some(config.localstack_resource_limits.clone().into()),
-                        requests: Some(config.localstack_resource_limits.into()),
+                        requests: Some(config.localstack_resource_limits.clone().into()),
                         ..Default::default()
                     }),
                     volume_mounts: Some(vec![VolumeMount {
@@ -929,3 +1106,61 @@ pub fn localstack_service_spec() -> ServiceSpec {
         ..Default::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container_names(spec: &StatefulSetSpec) -> Vec<String> {
+        spec.template
+            .spec
+            .as_ref()
+            .unwrap()
+            .containers
+            .iter()
+            .map(|container| container.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn cas_worker_count() {
+        let config = CasConfig::try_from(CasSpec {
+            worker_count: Some(3),
+            ..Default::default()
+        })
+        .unwrap();
+        let spec = cas_stateful_set_spec("keramik-test", &config, &DataDogConfig::default());
+        assert_eq!(
+            vec![
+                "cas-api",
+                "cas-worker",
+                "cas-worker-1",
+                "cas-worker-2",
+                "cas-scheduler"
+            ],
+            container_names(&spec)
+        );
+        // The workers all anchor from the same queue
+        let containers = &spec.template.spec.as_ref().unwrap().containers;
+        assert_eq!(containers[1].env, containers[3].env);
+
+        assert_eq!(
+            vec!["cas-api", "cas-worker", "cas-scheduler"],
+            container_names(&cas_stateful_set_spec(
+                "keramik-test",
+                &CasConfig::default(),
+                &DataDogConfig::default()
+            ))
+        );
+        assert_eq!(
+            "cas.workerCount",
+            CasConfig::try_from(CasSpec {
+                worker_count: Some(0),
+                ..Default::default()
+            })
+            .err()
+            .unwrap()
+            .field
+        );
+    }
+}
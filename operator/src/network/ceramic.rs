@@ -3,12 +3,16 @@ use std::collections::{BTreeMap, HashMap};
 use k8s_openapi::{
     api::{
         apps::v1::{RollingUpdateStatefulSetStrategy, StatefulSetSpec, StatefulSetUpdateStrategy},
+        batch::v1::JobSpec,
         core::v1::{
-            ConfigMapVolumeSource, Container, ContainerPort, EmptyDirVolumeSource, EnvVar,
-            EnvVarSource, HTTPGetAction, PersistentVolumeClaim, PersistentVolumeClaimSpec,
-            PersistentVolumeClaimVolumeSource, PodSecurityContext, PodSpec, PodTemplateSpec, Probe,
-            ResourceRequirements, SecretKeySelector, ServicePort, ServiceSpec, Volume, VolumeMount,
+            Affinity, ConfigMapVolumeSource, Container, ContainerPort, EmptyDirVolumeSource,
+            EnvVar, EnvVarSource, ExecAction, HTTPGetAction, NodeAffinity, NodeSelector,
+            NodeSelectorRequirement, NodeSelectorTerm, ObjectFieldSelector, PersistentVolumeClaim,
+            PersistentVolumeClaimSpec, PersistentVolumeClaimVolumeSource, PodSecurityContext,
+            PodSpec, PodTemplateSpec, Probe, ResourceRequirements, SecretKeySelector,
+            SecretVolumeSource, ServicePort, ServiceSpec, TCPSocketAction, Volume, VolumeMount,
         },
+        rbac::v1::{PolicyRule, Role, RoleBinding, RoleRef, Subject},
     },
     apimachinery::pkg::{
         api::resource::Quantity, apis::meta::v1::LabelSelector, util::intstr::IntOrString,
@@ -16,16 +20,19 @@ use k8s_openapi::{
 };
 use kube::core::ObjectMeta;
 
-use crate::labels::{managed_labels, selector_labels};
+use crate::labels::{insert_revision_label, managed_labels, selector_labels, validate_revision};
 use crate::network::{
     controller::{
-        CAS_SERVICE_NAME, CERAMIC_APP, CERAMIC_LOCAL_NETWORK_TYPE, GANACHE_SERVICE_NAME,
-        INIT_CONFIG_MAP_NAME,
+        CAS_SERVICE_NAME, CERAMIC_APP, CERAMIC_CONFIG_SECRET_NAME, CERAMIC_INIT_ACCOUNT,
+        CERAMIC_LOCAL_NETWORK_TYPE, GANACHE_SERVICE_NAME, INIT_CONFIG_MAP_NAME,
     },
     datadog::DataDogConfig,
+    probe::ProbeConfig,
     resource_limits::ResourceLimitsConfig,
-    CeramicSpec, GoIpfsSpec, IpfsSpec, NetworkSpec, RustIpfsSpec,
+    CeramicPostgresSpec, CeramicSpec, GoIpfsSpec, IpfsSpec, NetworkSpec, PodManagementPolicy,
+    RateLimitSpec, RustIpfsSpec, SecretKeySpec, UpdateStrategyType,
 };
+use crate::utils::ConfigError;
 
 use crate::network::controller::{CERAMIC_SERVICE_API_PORT, CERAMIC_SERVICE_IPFS_PORT};
 
@@ -33,6 +40,30 @@ use super::controller::{CERAMIC_POSTGRES_APP, CERAMIC_POSTGRES_SERVICE_NAME, DB_
 
 const IPFS_CONTAINER_NAME: &str = "ipfs";
 const IPFS_DATA_PV_CLAIM: &str = "ipfs-data";
+const IPFS_P2P_KEY_VOLUME: &str = "ipfs-p2p-key";
+/// Directory ceramic-one reads its libp2p key from when the key is kept in a secret.
+const IPFS_P2P_KEY_DIR: &str = "/keramik/p2p-key";
+/// Port of the Prometheus metrics exposed by ceramic.
+const CERAMIC_METRICS_PORT: i32 = 9464;
+
+/// Types of the Kubernetes services exposing the ceramic API.
+pub const CERAMIC_SERVICE_TYPES: [&str; 3] = ["ClusterIP", "NodePort", "LoadBalancer"];
+/// Supported actions of the ceramic container probes.
+pub const CERAMIC_PROBE_TYPES: [&str; 3] = ["httpGet", "tcpSocket", "exec"];
+/// Termination message policies of the containers of ceramic pods.
+const TERMINATION_MESSAGE_POLICIES: [&str; 2] = ["File", "FallbackToLogsOnError"];
+
+/// Log formats supported by the ceramic logger.
+const LOG_FORMATS: [&str; 2] = ["json", "text"];
+
+/// Names of the Ceramic networks ceramic-one can connect to.
+pub const CERAMIC_ONE_NETWORKS: [&str; 5] = [
+    "mainnet",
+    "testnet-clay",
+    "dev-unstable",
+    "local",
+    "in-memory",
+];
 
 pub fn config_maps(
     info: &CeramicInfo,
@@ -40,6 +71,51 @@ pub fn config_maps(
 ) -> BTreeMap<String, BTreeMap<String, String>> {
     let mut config_maps = BTreeMap::new();
     if config.init_config_map == INIT_CONFIG_MAP_NAME {
+        let http_api_rate_limit = config
+            .http_api_rate_limit
+            .as_ref()
+            .map(|rate_limit| {
+                format!(
+                    r#",
+        "rate-limit": {{
+            "requests": {},
+            "window-seconds": {}
+        }}"#,
+                    rate_limit.requests, rate_limit.window_seconds
+                )
+            })
+            .unwrap_or_default();
+        let metrics_exemplars = if config.metrics_exemplars {
+            r#",
+        "prometheus-exporter-exemplars-enabled": true"#
+        } else {
+            ""
+        };
+        let historical_sync = if config.historical_sync_supported {
+            HISTORICAL_SYNC_DAEMON_CONFIG
+        } else {
+            ""
+        };
+        let log_format = config
+            .log_format
+            .as_ref()
+            .map(|log_format| {
+                format!(
+                    r#",
+        "log-format": "{log_format}""#
+                )
+            })
+            .unwrap_or_default();
+        let daemon_config = daemon_config(
+            &http_api_rate_limit,
+            &log_format,
+            metrics_exemplars,
+            historical_sync,
+        );
+        let daemon_config = match &config.daemon_config_overlay {
+            Some(overlay) => overlay_daemon_config(&daemon_config, overlay),
+            None => daemon_config,
+        };
         config_maps.insert(INIT_CONFIG_MAP_NAME.to_owned(),
             BTreeMap::from_iter(vec![
              ("ceramic-init.sh".to_owned(),
@@ -52,79 +128,159 @@ export CERAMIC_ADMIN_DID=$(composedb did:from-private-key ${CERAMIC_ADMIN_PRIVAT
 CERAMIC_ADMIN_DID=$CERAMIC_ADMIN_DID envsubst < /ceramic-init/daemon-config.json > /config/daemon-config.json
 "#.to_owned()),
 
-("daemon-config.json".to_owned(),
-r#"{
-    "anchor": {
+("daemon-config.json".to_owned(), daemon_config),
+]));
+    }
+    config_maps.append(&mut config.ipfs.config_maps(info));
+    config_maps
+}
+
+/// Historical sync part of the daemon config template, older ceramic images fail to start with it.
+const HISTORICAL_SYNC_DAEMON_CONFIG: &str = r#",
+        "enable-historical-sync": ${ENABLE_HISTORICAL_SYNC}"#;
+
+/// Template of the daemon config, environment variables are substituted by the init script.
+fn daemon_config(
+    http_api_rate_limit: &str,
+    log_format: &str,
+    metrics_exemplars: &str,
+    historical_sync: &str,
+) -> String {
+    format!(
+        r#"{{
+    "anchor": {{
         "auth-method": "did"
-    },
-    "http-api": {
+    }},
+    "http-api": {{
         "cors-allowed-origins": [
-            "${CERAMIC_CORS_ALLOWED_ORIGINS}"
+            "${{CERAMIC_CORS_ALLOWED_ORIGINS}}"
         ],
         "admin-dids": [
-            "${CERAMIC_ADMIN_DID}"
-        ]
-    },
-    "ipfs": {
+            "${{CERAMIC_ADMIN_DID}}"
+        ]{http_api_rate_limit}
+    }},
+    "ipfs": {{
         "mode": "remote",
-        "host": "${CERAMIC_IPFS_HOST}"
-    },
-    "logger": {
-        "log-level": ${CERAMIC_LOG_LEVEL},
-        "log-to-files": false
-    },
-    "metrics": {
+        "host": "${{CERAMIC_IPFS_HOST}}"
+    }},
+    "logger": {{
+        "log-level": ${{CERAMIC_LOG_LEVEL}},
+        "log-to-files": false{log_format}
+    }},
+    "metrics": {{
         "metrics-exporter-enabled": false,
         "prometheus-exporter-enabled": true,
-        "prometheus-exporter-port": 9464
-    },
-    "network": {
-        "name": "${CERAMIC_NETWORK}"
-    },
-    "node": {
-        "privateSeedUrl": "inplace:ed25519#${CERAMIC_ADMIN_PRIVATE_KEY}"
-    },
-    "state-store": {
+        "prometheus-exporter-port": 9464{metrics_exemplars}
+    }},
+    "network": {{
+        "name": "${{CERAMIC_NETWORK}}"
+    }},
+    "node": {{
+        "privateSeedUrl": "inplace:ed25519#${{CERAMIC_ADMIN_PRIVATE_KEY}}"
+    }},
+    "state-store": {{
         "mode": "fs",
-        "local-directory": "${CERAMIC_STATE_STORE_PATH}"
-    },
-    "indexing": {
-        "db": "${DB_CONNECTION_STRING}",
+        "local-directory": "${{CERAMIC_STATE_STORE_PATH}}"
+    }},
+    "indexing": {{
+        "db": "${{DB_CONNECTION_STRING}}",
         "allow-queries-before-historical-sync": true,
-        "disable-composedb": false,
-        "enable-historical-sync": ${ENABLE_HISTORICAL_SYNC}
+        "disable-composedb": false{historical_sync}
+    }}
+}}"#
+    )
+}
+
+/// Placeholders of the daemon config template that are substituted without quotes.
+const UNQUOTED_DAEMON_CONFIG_VARS: [&str; 2] = ["CERAMIC_LOG_LEVEL", "ENABLE_HISTORICAL_SYNC"];
+
+/// Deep merge the overlay onto the daemon config template.
+/// The unquoted placeholders are quoted while merging so the template is valid JSON.
+fn overlay_daemon_config(template: &str, overlay: &serde_json::Value) -> String {
+    let mut template = template.to_owned();
+    for var in UNQUOTED_DAEMON_CONFIG_VARS {
+        template = template.replace(&format!(": ${{{var}}}"), &format!(": \"${{{var}}}\""));
     }
-}"#.to_owned()),
-]));
+    let mut daemon_config: serde_json::Value =
+        serde_json::from_str(&template).expect("daemon config template should be valid JSON");
+    merge_json(&mut daemon_config, overlay.clone());
+    let mut daemon_config = serde_json::to_string_pretty(&daemon_config)
+        .expect("daemon config should serialize to JSON");
+    for var in UNQUOTED_DAEMON_CONFIG_VARS {
+        daemon_config = daemon_config.replace(&format!("\"${{{var}}}\""), &format!("${{{var}}}"));
+    }
+    daemon_config
+}
+
+/// Deep merge the overlay onto the value.
+/// Objects are merged key by key and a null removes the key from the object.
+/// Any other overlay value, including arrays, replaces the value.
+fn merge_json(value: &mut serde_json::Value, overlay: serde_json::Value) {
+    match (value, overlay) {
+        (serde_json::Value::Object(value), serde_json::Value::Object(overlay)) => {
+            for (key, overlay) in overlay {
+                if overlay.is_null() {
+                    value.remove(&key);
+                } else {
+                    merge_json(value.entry(key).or_insert(serde_json::Value::Null), overlay);
+                }
+            }
+        }
+        (value, overlay @ serde_json::Value::Object(_)) => {
+            // Merge onto an empty object so nested nulls are dropped
+            *value = serde_json::Value::Object(Default::default());
+            merge_json(value, overlay);
+        }
+        (value, overlay) => *value = overlay,
     }
-    config_maps.append(&mut config.ipfs.config_maps(info));
-    config_maps
 }
 
-pub fn service_spec() -> ServiceSpec {
+/// Spec of the ceramic service, ready reports if any peer of the stateful set is ready.
+pub fn service_spec(config: &CeramicConfig, ready: bool) -> ServiceSpec {
     ServiceSpec {
-        ports: Some(vec![
-            ServicePort {
-                port: CERAMIC_SERVICE_API_PORT,
-                name: Some("api".to_owned()),
-                protocol: Some("TCP".to_owned()),
-                ..Default::default()
-            },
-            ServicePort {
-                port: CERAMIC_SERVICE_IPFS_PORT,
-                name: Some("ipfs".to_owned()),
-                protocol: Some("TCP".to_owned()),
-                ..Default::default()
-            },
-            ServicePort {
-                port: 4001,
-                name: Some("swarm-tcp".to_owned()),
-                protocol: Some("TCP".to_owned()),
-                ..Default::default()
-            },
-        ]),
+        ports: Some(
+            [
+                Some(ServicePort {
+                    port: CERAMIC_SERVICE_API_PORT,
+                    name: Some("api".to_owned()),
+                    protocol: Some("TCP".to_owned()),
+                    ..Default::default()
+                }),
+                // Peers reach the IPFS RPC API through their pod address, only expose it on the
+                // service when explicitly requested.
+                config.expose_ipfs_rpc.then(|| ServicePort {
+                    port: CERAMIC_SERVICE_IPFS_PORT,
+                    name: Some("ipfs".to_owned()),
+                    protocol: Some("TCP".to_owned()),
+                    ..Default::default()
+                }),
+                Some(ServicePort {
+                    port: config.ipfs.swarm_port(),
+                    name: Some("swarm-tcp".to_owned()),
+                    protocol: Some("TCP".to_owned()),
+                    ..Default::default()
+                }),
+                config.ipfs.swarm_quic_port().map(|port| ServicePort {
+                    port,
+                    name: Some("swarm-quic".to_owned()),
+                    protocol: Some("UDP".to_owned()),
+                    ..Default::default()
+                }),
+            ]
+            .into_iter()
+            .flatten()
+            .map(|port| ServicePort {
+                node_port: port
+                    .name
+                    .as_ref()
+                    .and_then(|name| config.node_ports.get(name))
+                    .copied(),
+                ..port
+            })
+            .collect(),
+        ),
         selector: selector_labels(CERAMIC_APP),
-        type_: Some("LoadBalancer".to_owned()),
+        type_: Some(config.service_type(ready).to_owned()),
         ..Default::default()
     }
 }
@@ -132,22 +288,179 @@ pub fn service_spec() -> ServiceSpec {
 pub struct CeramicConfig {
     pub weight: i32,
     pub init_config_map: String,
+    pub init_job: bool,
+    pub init_job_image: String,
     pub image: String,
     pub image_pull_policy: String,
+    pub init_image: String,
     pub ipfs: IpfsConfig,
     pub resource_limits: ResourceLimitsConfig,
+    pub init_resource_limits: ResourceLimitsConfig,
     pub db_type: String,
     pub postgres: CeramicPostgres,
+    pub db_connection_string: Option<String>,
     pub enable_historical_sync: bool,
+    pub historical_sync_supported: bool,
+    pub http_api_rate_limit: Option<RateLimitConfig>,
+    pub daemon_config_overlay: Option<serde_json::Value>,
+    pub expose_ipfs_rpc: bool,
+    pub service_type: String,
+    pub node_ports: HashMap<String, i32>,
+    pub defer_load_balancer: bool,
+    pub readiness_probe: ProbeConfig,
+    pub liveness_probe: ProbeConfig,
+    /// Probe delaying the readiness and liveness probes while the node starts, if any.
+    pub startup_probe: Option<ProbeConfig>,
+    pub probe_action: CeramicProbeAction,
+    pub update_strategy_type: UpdateStrategyType,
+    pub max_unavailable: IntOrString,
+    pub pod_management_policy: PodManagementPolicy,
+    pub require_node_labels: BTreeMap<String, String>,
+    pub metrics_exemplars: bool,
+    pub wait_for_postgres: bool,
+    pub termination_message_policy: String,
+    pub log_format: Option<String>,
+}
+
+/// Action of the ceramic container probes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CeramicProbeAction {
+    /// Request the healthcheck endpoint of the ceramic API.
+    HttpGet,
+    /// Connect to the ceramic API port.
+    TcpSocket,
+    /// Run the command in the ceramic container.
+    Exec(Vec<String>),
+}
+
+impl CeramicProbeAction {
+    /// Probe performing the action, without any timing.
+    fn probe(&self) -> Probe {
+        match self {
+            Self::HttpGet => Probe {
+                http_get: Some(HTTPGetAction {
+                    path: Some("/api/v0/node/healthcheck".to_owned()),
+                    port: IntOrString::String("api".to_owned()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            Self::TcpSocket => Probe {
+                tcp_socket: Some(TCPSocketAction {
+                    port: IntOrString::String("api".to_owned()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            Self::Exec(command) => Probe {
+                exec: Some(ExecAction {
+                    command: Some(command.clone()),
+                }),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Rate limiting applied by the Ceramic HTTP API.
+pub struct RateLimitConfig {
+    /// Maximum number of requests allowed per window
+    pub requests: i32,
+    /// Length of the rate limiting window in seconds
+    pub window_seconds: i32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests: 100,
+            window_seconds: 60,
+        }
+    }
+}
+
+impl TryFrom<RateLimitSpec> for RateLimitConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: RateLimitSpec) -> Result<Self, Self::Error> {
+        let default = Self::default();
+        let config = Self {
+            requests: value.requests.unwrap_or(default.requests),
+            window_seconds: value.window_seconds.unwrap_or(default.window_seconds),
+        };
+        for (field, value) in [
+            ("httpApiRateLimit.requests", config.requests),
+            ("httpApiRateLimit.windowSeconds", config.window_seconds),
+        ] {
+            if value <= 0 {
+                return Err(ConfigError::new(field, format!("{value} must be positive")));
+            }
+        }
+        Ok(config)
+    }
 }
 
 pub struct CeramicPostgres {
     /// Name of postgres db to use
-    pub db_name: Option<String>,
+    pub db_name: String,
     /// Name of postgres user to use
-    pub user_name: Option<String>,
+    pub user_name: String,
     /// Password for the postgres user
-    pub password: Option<String>,
+    pub password: String,
+    /// Image of the postgres container
+    pub image: String,
+    /// Pull policy for the postgres container image
+    pub image_pull_policy: String,
+    /// Resource limits of the postgres container
+    pub resource_limits: ResourceLimitsConfig,
+    /// Resource requests of the postgres container
+    pub resource_requests: ResourceLimitsConfig,
+}
+
+impl Default for CeramicPostgres {
+    fn default() -> Self {
+        Self {
+            db_name: "ceramic".to_owned(),
+            user_name: "ceramic".to_owned(),
+            password: "ceramic".to_owned(),
+            image: "postgres:15-alpine".to_owned(),
+            image_pull_policy: "IfNotPresent".to_owned(),
+            resource_limits: ResourceLimitsConfig {
+                cpu: Quantity("1".to_owned()),
+                memory: Quantity("1Gi".to_owned()),
+                storage: Quantity("2Gi".to_owned()),
+            },
+            resource_requests: ResourceLimitsConfig {
+                cpu: Quantity("1".to_owned()),
+                memory: Quantity("512Mi".to_owned()),
+                storage: Quantity("2Gi".to_owned()),
+            },
+        }
+    }
+}
+
+impl TryFrom<CeramicPostgresSpec> for CeramicPostgres {
+    type Error = ConfigError;
+
+    fn try_from(value: CeramicPostgresSpec) -> Result<Self, Self::Error> {
+        let default = Self::default();
+        Ok(Self {
+            db_name: value.db_name.unwrap_or(default.db_name),
+            user_name: value.user_name.unwrap_or(default.user_name),
+            password: value.password.unwrap_or(default.password),
+            image: value.image.unwrap_or(default.image),
+            image_pull_policy: value.image_pull_policy.unwrap_or(default.image_pull_policy),
+            // When set the spec applies to both requests and limits
+            resource_limits: ResourceLimitsConfig::from_spec(
+                value.resource_limits.clone(),
+                default.resource_limits,
+            ),
+            resource_requests: ResourceLimitsConfig::from_spec(
+                value.resource_limits,
+                default.resource_requests,
+            ),
+        })
+    }
 }
 
 /// Bundles all relevant config for a ceramic spec.
@@ -158,13 +471,20 @@ pub struct CeramicBundle<'a> {
     pub datadog: &'a DataDogConfig,
 }
 
+/// Prefix of every valid pubsub topic.
+const PUBSUB_TOPIC_PREFIX: &str = "/ceramic/";
+
 // Contains top level config for the network
 pub struct NetworkConfig {
     pub private_key_secret: Option<String>,
     pub network_type: String,
     pub pubsub_topic: String,
     pub eth_rpc_url: String,
+    pub eth_rpc_url_secret: Option<SecretKeySpec>,
     pub cas_api_url: String,
+    pub cas_api_url_secret: Option<SecretKeySpec>,
+    pub prometheus_annotations: bool,
+    pub revision: Option<String>,
 }
 
 impl Default for NetworkConfig {
@@ -174,15 +494,21 @@ impl Default for NetworkConfig {
             network_type: CERAMIC_LOCAL_NETWORK_TYPE.to_owned(),
             pubsub_topic: "/ceramic/local-keramik".to_owned(),
             eth_rpc_url: format!("http://{GANACHE_SERVICE_NAME}:8545"),
+            eth_rpc_url_secret: None,
             cas_api_url: format!("http://{CAS_SERVICE_NAME}:8081"),
+            cas_api_url_secret: None,
+            prometheus_annotations: true,
+            revision: None,
         }
     }
 }
 
-impl From<&NetworkSpec> for NetworkConfig {
-    fn from(value: &NetworkSpec) -> Self {
+impl TryFrom<&NetworkSpec> for NetworkConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: &NetworkSpec) -> Result<Self, Self::Error> {
         let default = NetworkConfig::default();
-        Self {
+        let config = Self {
             private_key_secret: value.private_key_secret.to_owned(),
             network_type: value
                 .network_type
@@ -193,7 +519,38 @@ impl From<&NetworkSpec> for NetworkConfig {
                 .to_owned()
                 .unwrap_or(default.pubsub_topic),
             eth_rpc_url: value.eth_rpc_url.to_owned().unwrap_or(default.eth_rpc_url),
+            eth_rpc_url_secret: value.eth_rpc_url_secret.to_owned(),
             cas_api_url: value.cas_api_url.to_owned().unwrap_or(default.cas_api_url),
+            cas_api_url_secret: value.cas_api_url_secret.to_owned(),
+            prometheus_annotations: value
+                .prometheus_annotations
+                .unwrap_or(default.prometheus_annotations),
+            revision: value.revision.to_owned(),
+        };
+        config.validate_pubsub_topic()?;
+        if let Some(revision) = &config.revision {
+            validate_revision(revision)?;
+        }
+        Ok(config)
+    }
+}
+
+impl NetworkConfig {
+    /// Check the pubsub topic is one ceramic accepts at startup.
+    fn validate_pubsub_topic(&self) -> Result<(), ConfigError> {
+        // Ceramic expects topics of the form /ceramic/<name>, an empty topic clears it
+        if self.pubsub_topic.is_empty() {
+            return Ok(());
+        }
+        match self.pubsub_topic.strip_prefix(PUBSUB_TOPIC_PREFIX) {
+            Some(name) if !name.is_empty() && !name.contains(char::is_whitespace) => Ok(()),
+            _ => Err(ConfigError::new(
+                "pubsubTopic",
+                format!(
+                    "{} does not match {PUBSUB_TOPIC_PREFIX}<name>",
+                    self.pubsub_topic
+                ),
+            )),
         }
     }
 }
@@ -267,10 +624,28 @@ impl IpfsConfig {
     }
     fn volumes(&self, info: &CeramicInfo) -> Vec<Volume> {
         match self {
-            IpfsConfig::Rust(_) => Vec::new(),
+            IpfsConfig::Rust(config) => config.volumes(),
             IpfsConfig::Go(config) => config.volumes(info),
         }
     }
+    fn swarm_port(&self) -> i32 {
+        match self {
+            IpfsConfig::Rust(config) => config.swarm_port,
+            IpfsConfig::Go(config) => config.swarm_port,
+        }
+    }
+    fn swarm_quic_port(&self) -> Option<i32> {
+        match self {
+            IpfsConfig::Rust(config) => config.swarm_quic_port(),
+            IpfsConfig::Go(_) => None,
+        }
+    }
+    fn volume_size(&self) -> Quantity {
+        match self {
+            IpfsConfig::Rust(config) => config.volume_size.clone(),
+            IpfsConfig::Go(config) => config.volume_size.clone(),
+        }
+    }
 }
 
 pub struct RustIpfsConfig {
@@ -278,7 +653,36 @@ pub struct RustIpfsConfig {
     image_pull_policy: String,
     resource_limits: ResourceLimitsConfig,
     rust_log: String,
+    swarm_port: i32,
+    swarm_addresses: Vec<String>,
+    network: String,
+    local_network_id: String,
+    store_dir: String,
+    kademlia_replication: Option<i32>,
+    kademlia_parallelism: Option<i32>,
+    volume_size: Quantity,
     env: Option<HashMap<String, String>>,
+    readiness_probe: ProbeConfig,
+    liveness_probe: ProbeConfig,
+    p2p_key_secret: Option<String>,
+}
+
+fn default_ipfs_readiness_probe() -> ProbeConfig {
+    ProbeConfig {
+        initial_delay_seconds: 10,
+        period_seconds: 5,
+        timeout_seconds: 5,
+        failure_threshold: None,
+    }
+}
+
+fn default_ipfs_liveness_probe() -> ProbeConfig {
+    ProbeConfig {
+        initial_delay_seconds: 30,
+        period_seconds: 10,
+        timeout_seconds: 5,
+        failure_threshold: None,
+    }
 }
 
 impl Default for RustIpfsConfig {
@@ -292,14 +696,61 @@ impl Default for RustIpfsConfig {
                 storage: Quantity("1Gi".to_owned()),
             },
             rust_log: "info,ceramic_one=debug,tracing_actix_web=debug,quinn_proto=error".to_owned(),
+            swarm_port: 4001,
+            swarm_addresses: vec!["/ip4/0.0.0.0/tcp/4001".to_owned()],
+            network: "local".to_owned(),
+            // We can use a hard coded value since nodes from other networks should not be
+            // able to connect.
+            local_network_id: "0".to_owned(),
+            store_dir: "/data/ipfs".to_owned(),
+            kademlia_replication: None,
+            kademlia_parallelism: None,
+            volume_size: Quantity("10Gi".to_owned()),
             env: None,
+            readiness_probe: default_ipfs_readiness_probe(),
+            liveness_probe: default_ipfs_liveness_probe(),
+            p2p_key_secret: None,
         }
     }
 }
-impl From<RustIpfsSpec> for RustIpfsConfig {
-    fn from(value: RustIpfsSpec) -> Self {
+impl TryFrom<RustIpfsSpec> for RustIpfsConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: RustIpfsSpec) -> Result<Self, Self::Error> {
         let default = RustIpfsConfig::default();
-        Self {
+        let (swarm_port, swarm_addresses) = match value.swarm_addresses {
+            Some(addresses) => {
+                // The tcp listener is what the container and service ports expose,
+                // so its port takes the place of swarmPort.
+                let tcp_port = addresses.iter().find_map(|addr| {
+                    let protocols: Vec<&str> = addr.split('/').collect();
+                    protocols.windows(2).find_map(|window| match window {
+                        ["tcp", port] => port.parse::<i32>().ok(),
+                        _ => None,
+                    })
+                });
+                let Some(tcp_port) = tcp_port else {
+                    return Err(ConfigError::new(
+                        "ipfs.rust.swarmAddresses",
+                        "must include a tcp address",
+                    ));
+                };
+                match value.swarm_port {
+                    Some(swarm_port) if swarm_port != tcp_port => {
+                        return Err(ConfigError::new(
+                            "ipfs.rust.swarmAddresses",
+                            format!("tcp port {tcp_port} does not match swarmPort {swarm_port}"),
+                        ));
+                    }
+                    _ => (tcp_port, addresses),
+                }
+            }
+            None => {
+                let swarm_port = value.swarm_port.unwrap_or(default.swarm_port);
+                (swarm_port, vec![format!("/ip4/0.0.0.0/tcp/{swarm_port}")])
+            }
+        };
+        Ok(Self {
             image: value.image.unwrap_or(default.image),
             image_pull_policy: value.image_pull_policy.unwrap_or(default.image_pull_policy),
             resource_limits: ResourceLimitsConfig::from_spec(
@@ -307,8 +758,19 @@ impl From<RustIpfsSpec> for RustIpfsConfig {
                 default.resource_limits,
             ),
             rust_log: value.rust_log.unwrap_or(default.rust_log),
+            swarm_port,
+            swarm_addresses,
+            network: value.network.unwrap_or(default.network),
+            local_network_id: value.local_network_id.unwrap_or(default.local_network_id),
+            store_dir: value.store_dir.unwrap_or(default.store_dir),
+            kademlia_replication: value.kademlia_replication,
+            kademlia_parallelism: value.kademlia_parallelism,
+            volume_size: value.volume_size.unwrap_or(default.volume_size),
             env: value.env,
-        }
+            readiness_probe: ProbeConfig::from_spec(value.readiness_probe, default.readiness_probe),
+            liveness_probe: ProbeConfig::from_spec(value.liveness_probe, default.liveness_probe),
+            p2p_key_secret: value.p2p_key_secret,
+        })
     }
 }
 
@@ -317,6 +779,10 @@ pub struct GoIpfsConfig {
     image_pull_policy: String,
     resource_limits: ResourceLimitsConfig,
     commands: Vec<String>,
+    swarm_port: i32,
+    volume_size: Quantity,
+    readiness_probe: ProbeConfig,
+    liveness_probe: ProbeConfig,
 }
 impl Default for GoIpfsConfig {
     fn default() -> Self {
@@ -329,94 +795,423 @@ impl Default for GoIpfsConfig {
                 storage: Quantity("2Gi".to_owned()),
             },
             commands: vec![],
+            swarm_port: 4001,
+            volume_size: Quantity("10Gi".to_owned()),
+            readiness_probe: default_ipfs_readiness_probe(),
+            liveness_probe: default_ipfs_liveness_probe(),
         }
     }
 }
-impl From<GoIpfsSpec> for GoIpfsConfig {
-    fn from(value: GoIpfsSpec) -> Self {
+impl TryFrom<GoIpfsSpec> for GoIpfsConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: GoIpfsSpec) -> Result<Self, Self::Error> {
         let default = GoIpfsConfig::default();
-        Self {
+        Ok(Self {
             image: value.image.unwrap_or(default.image),
             image_pull_policy: value.image_pull_policy.unwrap_or(default.image_pull_policy),
             resource_limits: ResourceLimitsConfig::from_spec(
                 value.resource_limits,
                 default.resource_limits,
             ),
-            commands: value.commands.unwrap_or(default.commands),
-        }
+            commands: value
+                .commands
+                .unwrap_or(default.commands)
+                .into_iter()
+                .chain(
+                    value
+                        .config_patches
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|(key, value)| config_patch_command(key, value)),
+                )
+                .collect(),
+            swarm_port: value.swarm_port.unwrap_or(default.swarm_port),
+            volume_size: value.volume_size.unwrap_or(default.volume_size),
+            readiness_probe: ProbeConfig::from_spec(value.readiness_probe, default.readiness_probe),
+            liveness_probe: ProbeConfig::from_spec(value.liveness_probe, default.liveness_probe),
+        })
     }
 }
 
+/// Shell command setting the ipfs config key to the JSON value.
+fn config_patch_command(key: &str, value: &serde_json::Value) -> String {
+    // Single quote the value for the shell, escaping any embedded single quotes
+    let value = value.to_string().replace('\'', r#"'\''"#);
+    format!("ipfs config --json {key} '{value}'")
+}
+
 impl Default for CeramicConfig {
     fn default() -> Self {
+        let resource_limits = ResourceLimitsConfig {
+            cpu: Quantity("1".to_owned()),
+            memory: Quantity("1Gi".to_owned()),
+            storage: Quantity("2Gi".to_owned()),
+        };
         Self {
             weight: 1,
             init_config_map: INIT_CONFIG_MAP_NAME.to_owned(),
+            init_job: false,
+            init_job_image: "bitnami/kubectl:1.28".to_owned(),
             image: "ceramicnetwork/composedb:latest".to_owned(),
             image_pull_policy: "Always".to_owned(),
+            init_image: "ceramicnetwork/composedb:latest".to_owned(),
             ipfs: IpfsConfig::default(),
-            resource_limits: ResourceLimitsConfig {
-                cpu: Quantity("1".to_owned()),
-                memory: Quantity("1Gi".to_owned()),
-                storage: Quantity("2Gi".to_owned()),
-            },
+            init_resource_limits: resource_limits.clone(),
+            resource_limits,
             db_type: DB_TYPE_POSTGRES.to_owned(),
-            postgres: CeramicPostgres {
-                db_name: None,
-                user_name: None,
-                password: None,
-            },
+            postgres: CeramicPostgres::default(),
+            db_connection_string: None,
             enable_historical_sync: true,
+            historical_sync_supported: true,
+            http_api_rate_limit: None,
+            daemon_config_overlay: None,
+            expose_ipfs_rpc: false,
+            service_type: "LoadBalancer".to_owned(),
+            node_ports: HashMap::new(),
+            defer_load_balancer: false,
+            readiness_probe: ProbeConfig {
+                initial_delay_seconds: 10,
+                period_seconds: 1,
+                timeout_seconds: 30,
+                failure_threshold: None,
+            },
+            liveness_probe: ProbeConfig {
+                initial_delay_seconds: 20,
+                period_seconds: 3,
+                timeout_seconds: 30,
+                failure_threshold: None,
+            },
+            // Historical sync is enabled by default
+            startup_probe: Some(historical_sync_startup_probe()),
+            probe_action: CeramicProbeAction::HttpGet,
+            update_strategy_type: UpdateStrategyType::RollingUpdate,
+            max_unavailable: IntOrString::String("50%".to_owned()),
+            pod_management_policy: PodManagementPolicy::Parallel,
+            require_node_labels: BTreeMap::new(),
+            metrics_exemplars: false,
+            wait_for_postgres: true,
+            termination_message_policy: "FallbackToLogsOnError".to_owned(),
+            log_format: None,
         }
     }
 }
 
+// Startup probe allowing the node 30 minutes to complete its initial historical sync.
+fn historical_sync_startup_probe() -> ProbeConfig {
+    ProbeConfig {
+        initial_delay_seconds: 10,
+        period_seconds: 10,
+        timeout_seconds: 30,
+        failure_threshold: Some(180),
+    }
+}
+
+impl CeramicConfig {
+    /// Type of the ceramic service, a deferred LoadBalancer is ClusterIP until a peer is ready.
+    fn service_type(&self, ready: bool) -> &str {
+        if self.defer_load_balancer && !ready && self.service_type == "LoadBalancer" {
+            "ClusterIP"
+        } else {
+            &self.service_type
+        }
+    }
+
+    /// Reports if ceramic uses a postgres deployed alongside it.
+    pub fn managed_postgres(&self) -> bool {
+        self.db_connection_string.is_none() && self.db_type == DB_TYPE_POSTGRES
+    }
+
+    /// Update strategy of the ceramic stateful set.
+    fn update_strategy(&self) -> StatefulSetUpdateStrategy {
+        match self.update_strategy_type {
+            UpdateStrategyType::RollingUpdate => StatefulSetUpdateStrategy {
+                rolling_update: Some(RollingUpdateStatefulSetStrategy {
+                    max_unavailable: Some(self.max_unavailable.clone()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            UpdateStrategyType::OnDelete => StatefulSetUpdateStrategy {
+                type_: Some("OnDelete".to_owned()),
+                rolling_update: None,
+            },
+        }
+    }
+
+    /// Affinity of the ceramic pods, requiring nodes with all of the required labels.
+    fn affinity(&self) -> Option<Affinity> {
+        if self.require_node_labels.is_empty() {
+            return None;
+        }
+        Some(Affinity {
+            node_affinity: Some(NodeAffinity {
+                required_during_scheduling_ignored_during_execution: Some(NodeSelector {
+                    // Requirements of a single term are ANDed
+                    node_selector_terms: vec![NodeSelectorTerm {
+                        match_expressions: Some(
+                            self.require_node_labels
+                                .iter()
+                                .map(|(key, value)| NodeSelectorRequirement {
+                                    key: key.to_owned(),
+                                    operator: "In".to_owned(),
+                                    values: Some(vec![value.to_owned()]),
+                                })
+                                .collect(),
+                        ),
+                        ..Default::default()
+                    }],
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+}
+
 pub struct CeramicConfigs(pub Vec<CeramicConfig>);
 
-impl From<Vec<CeramicSpec>> for CeramicConfigs {
-    fn from(value: Vec<CeramicSpec>) -> Self {
+impl TryFrom<Vec<CeramicSpec>> for CeramicConfigs {
+    type Error = ConfigError;
+
+    fn try_from(value: Vec<CeramicSpec>) -> Result<Self, Self::Error> {
         if value.is_empty() {
-            Self(vec![CeramicConfig::default()])
+            Ok(Self(vec![CeramicConfig::default()]))
         } else {
-            Self(value.into_iter().map(CeramicConfig::from).collect())
+            Ok(Self(
+                value
+                    .into_iter()
+                    .map(CeramicConfig::try_from)
+                    .collect::<Result<_, _>>()?,
+            ))
         }
     }
 }
 
-impl From<CeramicSpec> for CeramicConfig {
-    fn from(value: CeramicSpec) -> Self {
+impl TryFrom<CeramicSpec> for CeramicConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: CeramicSpec) -> Result<Self, Self::Error> {
+        validate_ceramic_spec(&value)?;
         let default = Self::default();
-        Self {
+        let resource_limits =
+            ResourceLimitsConfig::from_spec(value.resource_limits, default.resource_limits);
+        let probe = value.probe.unwrap_or_default();
+        let update_strategy = value.update_strategy.unwrap_or_default();
+        let image = value.image.unwrap_or(default.image);
+        let enable_historical_sync = value
+            .enable_historical_sync
+            .unwrap_or(default.enable_historical_sync);
+        let historical_sync_supported = value
+            .historical_sync_supported
+            .unwrap_or(default.historical_sync_supported);
+        let config = Self {
             weight: value.weight.unwrap_or(default.weight),
             init_config_map: value.init_config_map.unwrap_or(default.init_config_map),
-            image: value.image.unwrap_or(default.image),
+            init_job: value.init_job.unwrap_or(default.init_job),
+            init_job_image: value.init_job_image.unwrap_or(default.init_job_image),
+            init_image: value.init_image.unwrap_or_else(|| image.clone()),
+            image,
             image_pull_policy: value.image_pull_policy.unwrap_or(default.image_pull_policy),
-            ipfs: value.ipfs.map(Into::into).unwrap_or(default.ipfs),
-            resource_limits: ResourceLimitsConfig::from_spec(
-                value.resource_limits,
-                default.resource_limits,
+            ipfs: value
+                .ipfs
+                .map(IpfsConfig::try_from)
+                .transpose()?
+                .unwrap_or(default.ipfs),
+            resource_limits: resource_limits.clone(),
+            init_resource_limits: ResourceLimitsConfig::from_spec(
+                value.init_resource_limits,
+                resource_limits,
             ),
             db_type: value.db_type.unwrap_or(default.db_type),
-            postgres: CeramicPostgres {
-                db_name: value.ceramic_postgres.clone().unwrap().db_name,
-                user_name: value.ceramic_postgres.clone().unwrap().user_name,
-                password: value.ceramic_postgres.clone().unwrap().password,
+            postgres: value
+                .ceramic_postgres
+                .map(CeramicPostgres::try_from)
+                .transpose()?
+                .unwrap_or(default.postgres),
+            db_connection_string: value.db_connection_string,
+            enable_historical_sync,
+            historical_sync_supported,
+            http_api_rate_limit: value
+                .http_api_rate_limit
+                .map(RateLimitConfig::try_from)
+                .transpose()?,
+            daemon_config_overlay: value.daemon_config_overlay,
+            expose_ipfs_rpc: value.expose_ipfs_rpc.unwrap_or(default.expose_ipfs_rpc),
+            service_type: value.service_type.unwrap_or(default.service_type),
+            node_ports: value.node_ports.unwrap_or(default.node_ports),
+            defer_load_balancer: value
+                .defer_load_balancer
+                .unwrap_or(default.defer_load_balancer),
+            readiness_probe: ProbeConfig::from_spec(probe.readiness, default.readiness_probe),
+            liveness_probe: ProbeConfig::from_spec(probe.liveness, default.liveness_probe),
+            startup_probe: match probe.startup {
+                Some(startup) => Some(ProbeConfig::from_spec(
+                    Some(startup),
+                    historical_sync_startup_probe(),
+                )),
+                // The initial historical sync can take long, do not restart the node during it
+                None => (enable_historical_sync && historical_sync_supported)
+                    .then(historical_sync_startup_probe),
             },
-            enable_historical_sync: value.enable_historical_sync.unwrap_or(default.enable_historical_sync),
+            probe_action: match probe.probe_type.as_deref() {
+                Some("tcpSocket") => CeramicProbeAction::TcpSocket,
+                Some("exec") => CeramicProbeAction::Exec(probe.command.unwrap_or_default()),
+                _ => default.probe_action,
+            },
+            update_strategy_type: update_strategy
+                .type_
+                .unwrap_or(default.update_strategy_type),
+            max_unavailable: update_strategy
+                .max_unavailable
+                .unwrap_or(default.max_unavailable),
+            pod_management_policy: value
+                .pod_management_policy
+                .unwrap_or(default.pod_management_policy),
+            require_node_labels: value
+                .require_node_labels
+                .unwrap_or(default.require_node_labels),
+            metrics_exemplars: value.metrics_exemplars.unwrap_or(default.metrics_exemplars),
+            wait_for_postgres: value.wait_for_postgres.unwrap_or(default.wait_for_postgres),
+            termination_message_policy: value
+                .termination_message_policy
+                .unwrap_or(default.termination_message_policy),
+            log_format: value.log_format,
+        };
+        if config.managed_postgres() {
+            for (field, value) in [
+                ("ceramicPostgres.dbName", &config.postgres.db_name),
+                ("ceramicPostgres.userName", &config.postgres.user_name),
+                ("ceramicPostgres.password", &config.postgres.password),
+            ] {
+                if value.is_empty() {
+                    return Err(ConfigError::new(
+                        field,
+                        "must not be empty for a managed postgres",
+                    ));
+                }
+            }
         }
+        Ok(config)
     }
 }
 
-impl From<IpfsSpec> for IpfsConfig {
-    fn from(value: IpfsSpec) -> Self {
-        match value {
-            IpfsSpec::Rust(spec) => Self::Rust(spec.into()),
-            IpfsSpec::Go(spec) => Self::Go(spec.into()),
+/// Check the values of a ceramic spec that cannot be deployed.
+fn validate_ceramic_spec(spec: &CeramicSpec) -> Result<(), ConfigError> {
+    if let Some(IpfsSpec::Rust(RustIpfsSpec {
+        network: Some(network),
+        ..
+    })) = &spec.ipfs
+    {
+        if !CERAMIC_ONE_NETWORKS.contains(&network.as_str()) {
+            return Err(ConfigError::new(
+                "ipfs.rust.network",
+                format!(
+                    "unknown ceramic-one network {network}, expected one of {}",
+                    CERAMIC_ONE_NETWORKS.join(", ")
+                ),
+            ));
+        }
+    }
+    if let Some(service_type) = &spec.service_type {
+        if !CERAMIC_SERVICE_TYPES.contains(&service_type.as_str()) {
+            return Err(ConfigError::new(
+                "serviceType",
+                format!(
+                    "unknown ceramic service type {service_type}, expected one of {}",
+                    CERAMIC_SERVICE_TYPES.join(", ")
+                ),
+            ));
+        }
+    }
+    if spec.node_ports.is_some() && spec.service_type.as_deref() != Some("NodePort") {
+        return Err(ConfigError::new(
+            "nodePorts",
+            "ceramic node ports require the NodePort service type",
+        ));
+    }
+    if let Some(probe) = &spec.probe {
+        if let Some(probe_type) = &probe.probe_type {
+            if !CERAMIC_PROBE_TYPES.contains(&probe_type.as_str()) {
+                return Err(ConfigError::new(
+                    "probe.probeType",
+                    format!(
+                        "unknown ceramic probe type {probe_type}, expected one of {}",
+                        CERAMIC_PROBE_TYPES.join(", ")
+                    ),
+                ));
+            }
+            if probe_type == "exec" && probe.command.as_deref().unwrap_or_default().is_empty() {
+                return Err(ConfigError::new(
+                    "probe.command",
+                    "ceramic exec probes require a command",
+                ));
+            }
+        }
+    }
+    if let Some(db_connection_string) = &spec.db_connection_string {
+        if !db_connection_string.starts_with("postgres://") {
+            return Err(ConfigError::new(
+                "dbConnectionString",
+                "ceramic db connection string must start with postgres://",
+            ));
+        }
+    }
+    if let Some(policy) = &spec.termination_message_policy {
+        if !TERMINATION_MESSAGE_POLICIES.contains(&policy.as_str()) {
+            return Err(ConfigError::new(
+                "terminationMessagePolicy",
+                format!(
+                    "unknown termination message policy {policy}, expected one of {}",
+                    TERMINATION_MESSAGE_POLICIES.join(", ")
+                ),
+            ));
+        }
+    }
+    if let Some(log_format) = &spec.log_format {
+        if !LOG_FORMATS.contains(&log_format.as_str()) {
+            return Err(ConfigError::new(
+                "logFormat",
+                format!(
+                    "unknown log format {log_format}, expected one of {}",
+                    LOG_FORMATS.join(", ")
+                ),
+            ));
         }
     }
+    if let Some(overlay) = &spec.daemon_config_overlay {
+        if !overlay.is_object() {
+            return Err(ConfigError::new(
+                "daemonConfigOverlay",
+                "ceramic daemon config overlay must be a JSON object",
+            ));
+        }
+    }
+    Ok(())
+}
+
+impl TryFrom<IpfsSpec> for IpfsConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: IpfsSpec) -> Result<Self, Self::Error> {
+        Ok(match value {
+            IpfsSpec::Rust(spec) => Self::Rust(spec.try_into()?),
+            IpfsSpec::Go(spec) => Self::Go(spec.try_into()?),
+        })
+    }
 }
 
 impl RustIpfsConfig {
+    /// UDP port of the first quic swarm address, if any.
+    fn swarm_quic_port(&self) -> Option<i32> {
+        self.swarm_addresses.iter().find_map(|addr| {
+            let protocols: Vec<&str> = addr.split('/').collect();
+            protocols.windows(3).find_map(|window| match window {
+                ["udp", port, quic] if quic.starts_with("quic") => port.parse().ok(),
+                _ => None,
+            })
+        })
+    }
     fn container(&self) -> Container {
         let mut env = vec![
             EnvVar {
@@ -441,24 +1236,17 @@ impl RustIpfsConfig {
             },
             EnvVar {
                 name: "CERAMIC_ONE_SWARM_ADDRESSES".to_owned(),
-                value: Some("/ip4/0.0.0.0/tcp/4001".to_owned()),
+                value: Some(self.swarm_addresses.join(",")),
                 ..Default::default()
             },
             EnvVar {
                 name: "CERAMIC_ONE_STORE_DIR".to_owned(),
-                value: Some("/data/ipfs".to_owned()),
+                value: Some(self.store_dir.to_owned()),
                 ..Default::default()
             },
             EnvVar {
                 name: "CERAMIC_ONE_NETWORK".to_owned(),
-                value: Some("local".to_owned()),
-                ..Default::default()
-            },
-            EnvVar {
-                name: "CERAMIC_ONE_LOCAL_NETWORK_ID".to_owned(),
-                // We can use a hard coded value since nodes from other networks should not be
-                // able to connect.
-                value: Some("0".to_owned()),
+                value: Some(self.network.to_owned()),
                 ..Default::default()
             },
             EnvVar {
@@ -472,6 +1260,32 @@ impl RustIpfsConfig {
                 ..Default::default()
             },
         ];
+        if self.network == "local" {
+            env.push(EnvVar {
+                name: "CERAMIC_ONE_LOCAL_NETWORK_ID".to_owned(),
+                value: Some(self.local_network_id.to_owned()),
+                ..Default::default()
+            });
+        }
+        if self.p2p_key_secret.is_some() {
+            env.push(EnvVar {
+                name: "CERAMIC_ONE_P2P_KEY_DIR".to_owned(),
+                value: Some(IPFS_P2P_KEY_DIR.to_owned()),
+                ..Default::default()
+            });
+            // Used to select the key of this pod from the secret
+            env.push(EnvVar {
+                name: "POD_NAME".to_owned(),
+                value_from: Some(EnvVarSource {
+                    field_ref: Some(ObjectFieldSelector {
+                        field_path: "metadata.name".to_owned(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+        }
         if let Some(extra_env) = &self.env {
             extra_env.iter().for_each(|(key, value)| {
                 if let Some((pos, _)) = env.iter().enumerate().find(|(_, var)| &var.name == key) {
@@ -484,53 +1298,120 @@ impl RustIpfsConfig {
                 })
             });
         }
+        // Explicitly configured Kademlia values cannot be overridden by the extra env values.
+        for (name, value) in [
+            (
+                "CERAMIC_ONE_KADEMLIA_REPLICATION",
+                self.kademlia_replication,
+            ),
+            (
+                "CERAMIC_ONE_KADEMLIA_PARALLELISM",
+                self.kademlia_parallelism,
+            ),
+        ] {
+            if let Some(value) = value {
+                env.retain(|var| var.name != name);
+                env.push(EnvVar {
+                    name: name.to_owned(),
+                    value: Some(value.to_string()),
+                    ..Default::default()
+                });
+            }
+        }
         // Sort env vars so we can have stable tests
         env.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        // ceramic-one only serves its RPC API over POST so check the port is accepting
+        // connections instead.
+        let rpc_probe = Probe {
+            tcp_socket: Some(TCPSocketAction {
+                port: IntOrString::String("rpc".to_owned()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut volume_mounts = vec![VolumeMount {
+            mount_path: self.store_dir.to_owned(),
+            name: IPFS_DATA_PV_CLAIM.to_owned(),
+            ..Default::default()
+        }];
+        if self.p2p_key_secret.is_some() {
+            // Mount only the key of this pod, under the file name ceramic-one expects
+            volume_mounts.push(VolumeMount {
+                mount_path: format!("{IPFS_P2P_KEY_DIR}/id_ed25519_0"),
+                name: IPFS_P2P_KEY_VOLUME.to_owned(),
+                read_only: Some(true),
+                sub_path_expr: Some("$(POD_NAME)".to_owned()),
+                ..Default::default()
+            });
+        }
         Container {
             env: Some(env),
             image: Some(self.image.to_owned()),
             image_pull_policy: Some(self.image_pull_policy.to_owned()),
+            liveness_probe: Some(self.liveness_probe.probe(rpc_probe.clone())),
             name: IPFS_CONTAINER_NAME.to_owned(),
-            ports: Some(vec![
-                ContainerPort {
-                    container_port: 4001,
-                    name: Some("swarm-tcp".to_owned()),
-                    protocol: Some("TCP".to_owned()),
-                    ..Default::default()
-                },
-                ContainerPort {
-                    container_port: CERAMIC_SERVICE_IPFS_PORT,
-                    name: Some("rpc".to_owned()),
-                    protocol: Some("TCP".to_owned()),
-                    ..Default::default()
-                },
-                ContainerPort {
-                    container_port: 9465,
-                    name: Some("metrics".to_owned()),
-                    protocol: Some("TCP".to_owned()),
+            ports: Some(
+                [
+                    ContainerPort {
+                        container_port: self.swarm_port,
+                        name: Some("swarm-tcp".to_owned()),
+                        protocol: Some("TCP".to_owned()),
+                        ..Default::default()
+                    },
+                    ContainerPort {
+                        container_port: CERAMIC_SERVICE_IPFS_PORT,
+                        name: Some("rpc".to_owned()),
+                        protocol: Some("TCP".to_owned()),
+                        ..Default::default()
+                    },
+                    ContainerPort {
+                        container_port: 9465,
+                        name: Some("metrics".to_owned()),
+                        protocol: Some("TCP".to_owned()),
+                        ..Default::default()
+                    },
+                ]
+                .into_iter()
+                .chain(self.swarm_quic_port().map(|port| ContainerPort {
+                    container_port: port,
+                    name: Some("swarm-quic".to_owned()),
+                    protocol: Some("UDP".to_owned()),
                     ..Default::default()
-                },
-            ]),
+                }))
+                .collect(),
+            ),
+            readiness_probe: Some(self.readiness_probe.probe(rpc_probe)),
             resources: Some(ResourceRequirements {
                 limits: Some(self.resource_limits.clone().into()),
                 requests: Some(self.resource_limits.clone().into()),
                 ..Default::default()
             }),
-            volume_mounts: Some(vec![VolumeMount {
-                mount_path: "/data/ipfs".to_owned(),
-                name: IPFS_DATA_PV_CLAIM.to_owned(),
-                ..Default::default()
-            }]),
+            volume_mounts: Some(volume_mounts),
             ..Default::default()
         }
     }
+    fn volumes(&self) -> Vec<Volume> {
+        self.p2p_key_secret
+            .iter()
+            .map(|secret| Volume {
+                name: IPFS_P2P_KEY_VOLUME.to_owned(),
+                secret: Some(SecretVolumeSource {
+                    secret_name: Some(secret.to_owned()),
+                    default_mode: Some(0o400),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .collect()
+    }
 }
 
 impl GoIpfsConfig {
     fn config_maps(&self, info: &CeramicInfo) -> BTreeMap<String, BTreeMap<String, String>> {
         let mut ipfs_config = vec![(
             "001-config.sh".to_owned(),
-            r#"#!/bin/sh
+            format!(
+                r#"#!/bin/sh
 set -ex
 # Do not bootstrap against public nodes
 ipfs bootstrap rm all
@@ -542,13 +1423,14 @@ ipfs config  --json Addresses.Gateway '[]'
 # Enable pubsub
 ipfs config  --json PubSub.Enabled true
 # Only listen on specific tcp address as nothing else is exposed
-ipfs config  --json Addresses.Swarm '["/ip4/0.0.0.0/tcp/4001"]'
+ipfs config  --json Addresses.Swarm '["/ip4/0.0.0.0/tcp/{}"]'
 # Set explicit resource manager limits as Kubo computes them based off
 # the k8s node resources and not the pods limits.
 ipfs config Swarm.ResourceMgr.MaxMemory '400 MB'
 ipfs config --json Swarm.ResourceMgr.MaxFileDescriptors 500000
-"#
-            .to_owned(),
+"#,
+                self.swarm_port
+            ),
         )];
         if !self.commands.is_empty() {
             ipfs_config.push((
@@ -590,13 +1472,23 @@ ipfs config --json Swarm.ResourceMgr.MaxFileDescriptors 500000
                 ..Default::default()
             })
         }
+        // Kubo serves its metrics over GET on the RPC port, unlike the /api/v0 endpoints.
+        let rpc_probe = Probe {
+            http_get: Some(HTTPGetAction {
+                path: Some("/debug/metrics/prometheus".to_owned()),
+                port: IntOrString::String("rpc".to_owned()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
         Container {
             image: Some(self.image.to_owned()),
             image_pull_policy: Some(self.image_pull_policy.to_owned()),
+            liveness_probe: Some(self.liveness_probe.probe(rpc_probe.clone())),
             name: IPFS_CONTAINER_NAME.to_owned(),
             ports: Some(vec![
                 ContainerPort {
-                    container_port: 4001,
+                    container_port: self.swarm_port,
                     name: Some("swarm-tcp".to_owned()),
                     protocol: Some("TCP".to_owned()),
                     ..Default::default()
@@ -614,6 +1506,7 @@ ipfs config --json Swarm.ResourceMgr.MaxFileDescriptors 500000
                     ..Default::default()
                 },
             ]),
+            readiness_probe: Some(self.readiness_probe.probe(rpc_probe)),
             resources: Some(ResourceRequirements {
                 limits: Some(self.resource_limits.clone().into()),
                 requests: Some(self.resource_limits.clone().into()),
@@ -636,10 +1529,17 @@ ipfs config --json Swarm.ResourceMgr.MaxFileDescriptors 500000
     }
 }
 
-pub fn stateful_set_spec(ns: &str, bundle: &CeramicBundle<'_>) -> StatefulSetSpec {
+/// Env shared by the ceramic container and the ceramic init step.
+fn ceramic_env(bundle: &CeramicBundle<'_>) -> Vec<EnvVar> {
     let mut db_connection_string: String = "sqlite:///ceramic-data/ceramic.db".to_owned();
-    if bundle.config.db_type.eq(DB_TYPE_POSTGRES) {
-        db_connection_string = format!("postgres://{}:{}@{}:5432/{}", bundle.config.postgres.user_name.clone().unwrap(),bundle.config.postgres.password.clone().unwrap(),CERAMIC_POSTGRES_SERVICE_NAME.to_owned(), bundle.config.postgres.db_name.clone().unwrap())
+    if let Some(external) = &bundle.config.db_connection_string {
+        db_connection_string = external.to_owned();
+    } else if bundle.config.db_type.eq(DB_TYPE_POSTGRES) {
+        let postgres = &bundle.config.postgres;
+        db_connection_string = format!(
+            "postgres://{}:{}@{CERAMIC_POSTGRES_SERVICE_NAME}:5432/{}",
+            postgres.user_name, postgres.password, postgres.db_name
+        );
     }
 
     let mut ceramic_env = vec![
@@ -653,16 +1553,16 @@ pub fn stateful_set_spec(ns: &str, bundle: &CeramicBundle<'_>) -> StatefulSetSpe
             value: Some(bundle.net_config.pubsub_topic.to_owned()),
             ..Default::default()
         },
-        EnvVar {
-            name: "ETH_RPC_URL".to_owned(),
-            value: Some(bundle.net_config.eth_rpc_url.to_owned()),
-            ..Default::default()
-        },
-        EnvVar {
-            name: "CAS_API_URL".to_owned(),
-            value: Some(bundle.net_config.cas_api_url.to_owned()),
-            ..Default::default()
-        },
+        url_env(
+            "ETH_RPC_URL",
+            &bundle.net_config.eth_rpc_url,
+            bundle.net_config.eth_rpc_url_secret.as_ref(),
+        ),
+        url_env(
+            "CAS_API_URL",
+            &bundle.net_config.cas_api_url,
+            bundle.net_config.cas_api_url_secret.as_ref(),
+        ),
         EnvVar {
             name: "CERAMIC_STATE_STORE_PATH".to_owned(),
             value: Some("/ceramic-data/statestore".to_owned()),
@@ -688,13 +1588,42 @@ pub fn stateful_set_spec(ns: &str, bundle: &CeramicBundle<'_>) -> StatefulSetSpe
             value: Some(db_connection_string),
             ..Default::default()
         },
-        EnvVar {
+    ];
+    if bundle.config.historical_sync_supported {
+        ceramic_env.push(EnvVar {
             name: "ENABLE_HISTORICAL_SYNC".to_owned(),
             value: Some(bundle.config.enable_historical_sync.to_string()),
             ..Default::default()
+        });
+    }
+    ceramic_env
+}
+
+/// Env var of a URL, read from the secret when set so it stays out of the manifest.
+fn url_env(name: &str, url: &str, secret: Option<&SecretKeySpec>) -> EnvVar {
+    match secret {
+        Some(secret) => EnvVar {
+            name: name.to_owned(),
+            value_from: Some(EnvVarSource {
+                secret_key_ref: Some(SecretKeySelector {
+                    key: secret.key.to_owned(),
+                    name: Some(secret.name.to_owned()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
         },
-    ];
+        None => EnvVar {
+            name: name.to_owned(),
+            value: Some(url.to_owned()),
+            ..Default::default()
+        },
+    }
+}
 
+/// Container that derives the admin DID and renders the daemon config into the config volume.
+fn init_container(bundle: &CeramicBundle<'_>) -> Container {
     let mut init_env = vec![EnvVar {
         name: "CERAMIC_ADMIN_PRIVATE_KEY".to_owned(),
         value_from: Some(EnvVarSource {
@@ -707,11 +1636,66 @@ pub fn stateful_set_spec(ns: &str, bundle: &CeramicBundle<'_>) -> StatefulSetSpe
         }),
         ..Default::default()
     }];
-    init_env.append(&mut ceramic_env.clone());
+    init_env.append(&mut ceramic_env(bundle));
+    Container {
+        command: Some(vec![
+            "/bin/bash".to_owned(),
+            "-c".to_owned(),
+            "/ceramic-init/ceramic-init.sh".to_owned(),
+        ]),
+        env: Some(init_env),
+        image: Some(bundle.config.init_image.to_owned()),
+        image_pull_policy: Some(bundle.config.image_pull_policy.to_owned()),
+        name: "init-ceramic-config".to_owned(),
+        termination_message_policy: Some(bundle.config.termination_message_policy.to_owned()),
+        resources: Some(ResourceRequirements {
+            limits: Some(bundle.config.init_resource_limits.clone().into()),
+            requests: Some(bundle.config.init_resource_limits.clone().into()),
+            ..Default::default()
+        }),
+        volume_mounts: Some(vec![
+            VolumeMount {
+                mount_path: "/config".to_owned(),
+                name: "config-volume".to_owned(),
+                ..Default::default()
+            },
+            VolumeMount {
+                mount_path: "/ceramic-init".to_owned(),
+                name: "ceramic-init".to_owned(),
+                ..Default::default()
+            },
+        ]),
+        ..Default::default()
+    }
+}
 
-    bundle.datadog.inject_env(&mut ceramic_env);
+/// Init container blocking until the ceramic postgres accepts connections,
+/// so ceramic does not crash connecting to its index database.
+fn wait_for_postgres_container(bundle: &CeramicBundle<'_>) -> Container {
+    Container {
+        command: Some(vec![
+            "/bin/sh".to_owned(),
+            "-c".to_owned(),
+            format!(
+                "until pg_isready -h {CERAMIC_POSTGRES_SERVICE_NAME} -p 5432; do sleep 1; done"
+            ),
+        ]),
+        image: Some(bundle.config.postgres.image.to_owned()),
+        image_pull_policy: Some(bundle.config.postgres.image_pull_policy.to_owned()),
+        name: "wait-for-postgres".to_owned(),
+        termination_message_policy: Some(bundle.config.termination_message_policy.to_owned()),
+        resources: Some(ResourceRequirements {
+            limits: Some(bundle.config.init_resource_limits.clone().into()),
+            requests: Some(bundle.config.init_resource_limits.clone().into()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
 
-    let mut volumes = vec![
+/// Volumes used by the init container.
+fn init_volumes(bundle: &CeramicBundle<'_>) -> Vec<Volume> {
+    vec![
         Volume {
             empty_dir: Some(EmptyDirVolumeSource::default()),
             name: "config-volume".to_owned(),
@@ -726,6 +1710,142 @@ pub fn stateful_set_spec(ns: &str, bundle: &CeramicBundle<'_>) -> StatefulSetSpe
             name: "ceramic-init".to_owned(),
             ..Default::default()
         },
+    ]
+}
+
+/// Job that runs the init step once and publishes the rendered daemon config as a secret,
+/// instead of running the init container in every ceramic pod.
+/// The config is kept in a secret since the admin private key is rendered into it.
+pub fn init_job_spec(bundle: &CeramicBundle<'_>) -> JobSpec {
+    let secret = bundle.info.new_name(CERAMIC_CONFIG_SECRET_NAME);
+    JobSpec {
+        backoff_limit: Some(4),
+        template: PodTemplateSpec {
+            spec: Some(PodSpec {
+                containers: vec![Container {
+                    command: Some(vec![
+                        "/bin/sh".to_owned(),
+                        "-c".to_owned(),
+                        format!("kubectl create secret generic {secret} --from-file=/config/daemon-config.json --dry-run=client -o yaml | kubectl apply -f -"),
+                    ]),
+                    image: Some(bundle.config.init_job_image.clone()),
+                    image_pull_policy: Some("IfNotPresent".to_owned()),
+                    name: "publish-ceramic-config".to_owned(),
+                    resources: Some(ResourceRequirements {
+                        limits: Some(bundle.config.init_resource_limits.clone().into()),
+                        requests: Some(bundle.config.init_resource_limits.clone().into()),
+                        ..Default::default()
+                    }),
+                    volume_mounts: Some(vec![VolumeMount {
+                        mount_path: "/config".to_owned(),
+                        name: "config-volume".to_owned(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                }],
+                init_containers: Some(vec![init_container(bundle)]),
+                restart_policy: Some("Never".to_owned()),
+                service_account_name: Some(CERAMIC_INIT_ACCOUNT.to_owned()),
+                volumes: Some(init_volumes(bundle)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// Reports whether an applied init job runs a different init step than the desired job.
+/// Only the images, commands and env values are compared since the server defaults the other
+/// fields of the pod template.
+pub fn init_job_changed(current: &JobSpec, desired: &JobSpec) -> bool {
+    let steps = |spec: &JobSpec| {
+        spec.template
+            .spec
+            .iter()
+            .flat_map(|pod| pod.init_containers.iter().flatten().chain(&pod.containers))
+            .map(|container| {
+                let env: Vec<_> = container
+                    .env
+                    .iter()
+                    .flatten()
+                    .map(|env| (env.name.clone(), env.value.clone()))
+                    .collect();
+                (container.image.clone(), container.command.clone(), env)
+            })
+            .collect::<Vec<_>>()
+    };
+    steps(current) != steps(desired)
+}
+
+/// Allows the init job to publish the rendered daemon config.
+pub fn init_role() -> Role {
+    Role {
+        rules: Some(vec![PolicyRule {
+            api_groups: Some(vec!["".to_owned()]),
+            resources: Some(vec!["secrets".to_owned()]),
+            verbs: vec!["get".to_owned(), "create".to_owned(), "patch".to_owned()],
+            ..Default::default()
+        }]),
+        ..Default::default()
+    }
+}
+
+pub fn init_role_binding(ns: &str) -> RoleBinding {
+    RoleBinding {
+        role_ref: RoleRef {
+            kind: "Role".to_owned(),
+            name: CERAMIC_INIT_ACCOUNT.to_owned(),
+            api_group: "rbac.authorization.k8s.io".to_owned(),
+        },
+        subjects: Some(vec![Subject {
+            kind: "ServiceAccount".to_owned(),
+            name: CERAMIC_INIT_ACCOUNT.to_owned(),
+            namespace: Some(ns.to_owned()),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    }
+}
+
+// Annotations conventionally used by Prometheus to discover pods to scrape.
+fn prometheus_annotations() -> BTreeMap<String, String> {
+    BTreeMap::from_iter(vec![
+        ("prometheus.io/scrape".to_owned(), "true".to_owned()),
+        (
+            "prometheus.io/port".to_owned(),
+            CERAMIC_METRICS_PORT.to_string(),
+        ),
+        ("prometheus.io/path".to_owned(), "/metrics".to_owned()),
+    ])
+}
+
+pub fn stateful_set_spec(ns: &str, bundle: &CeramicBundle<'_>) -> StatefulSetSpec {
+    let mut ceramic_env = ceramic_env(bundle);
+    bundle.datadog.inject_env(&mut ceramic_env, ns, CERAMIC_APP);
+
+    let mut init_containers = Vec::new();
+    if bundle.config.wait_for_postgres && bundle.config.managed_postgres() {
+        init_containers.push(wait_for_postgres_container(bundle));
+    }
+    if !bundle.config.init_job {
+        init_containers.push(init_container(bundle));
+    }
+
+    let mut volumes = if bundle.config.init_job {
+        // The init job has already rendered the daemon config into a secret
+        vec![Volume {
+            name: "config-volume".to_owned(),
+            secret: Some(SecretVolumeSource {
+                secret_name: Some(bundle.info.new_name(CERAMIC_CONFIG_SECRET_NAME)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }]
+    } else {
+        init_volumes(bundle)
+    };
+    volumes.append(&mut vec![
         Volume {
             name: "ceramic-data".to_owned(),
             persistent_volume_claim: Some(PersistentVolumeClaimVolumeSource {
@@ -742,12 +1862,20 @@ pub fn stateful_set_spec(ns: &str, bundle: &CeramicBundle<'_>) -> StatefulSetSpe
             }),
             ..Default::default()
         },
-    ];
+    ]);
 
     volumes.append(&mut bundle.config.ipfs.volumes(&bundle.info));
 
     StatefulSetSpec {
-        pod_management_policy: Some("Parallel".to_owned()),
+        // Kubernetes rejects changes to the policy of an existing stateful set, so changing it
+        // fails to apply until the stateful set is recreated.
+        pod_management_policy: Some(
+            match bundle.config.pod_management_policy {
+                PodManagementPolicy::OrderedReady => "OrderedReady",
+                PodManagementPolicy::Parallel => "Parallel",
+            }
+            .to_owned(),
+        ),
         replicas: Some(bundle.info.replicas),
         selector: LabelSelector {
             match_labels: selector_labels(CERAMIC_APP),
@@ -761,20 +1889,23 @@ pub fn stateful_set_spec(ns: &str, bundle: &CeramicBundle<'_>) -> StatefulSetSpe
                     "/metrics".to_owned(),
                 )]))
                 .map(|mut annotations| {
+                    if bundle.net_config.prometheus_annotations {
+                        annotations.append(&mut prometheus_annotations());
+                    }
                     bundle.datadog.inject_annotations(&mut annotations);
                     annotations
                 }),
 
                 labels: selector_labels(CERAMIC_APP).map(|mut lbls| {
                     lbls.append(&mut managed_labels().unwrap());
-                    bundle
-                        .datadog
-                        .inject_labels(&mut lbls, ns.to_owned(), "ceramic".to_owned());
+                    insert_revision_label(&mut lbls, bundle.net_config.revision.as_deref());
+                    bundle.datadog.inject_labels(&mut lbls, ns, CERAMIC_APP);
                     lbls
                 }),
                 ..Default::default()
             }),
             spec: Some(PodSpec {
+                affinity: bundle.config.affinity(),
                 containers: vec![
                     Container {
                         command: Some(vec![
@@ -794,34 +1925,29 @@ pub fn stateful_set_spec(ns: &str, bundle: &CeramicBundle<'_>) -> StatefulSetSpe
                                 ..Default::default()
                             },
                             ContainerPort {
-                                container_port: 9464,
+                                container_port: CERAMIC_METRICS_PORT,
                                 name: Some("metrics".to_owned()),
                                 protocol: Some("TCP".to_owned()),
                                 ..Default::default()
                             },
                         ]),
-                        readiness_probe: Some(Probe {
-                            http_get: Some(HTTPGetAction {
-                                path: Some("/api/v0/node/healthcheck".to_owned()),
-                                port: IntOrString::String("api".to_owned()),
-                                ..Default::default()
-                            }),
-                            initial_delay_seconds: Some(10),
-                            period_seconds: Some(1),
-                            timeout_seconds: Some(30),
-                            ..Default::default()
-                        }),
-                        liveness_probe: Some(Probe {
-                            http_get: Some(HTTPGetAction {
-                                path: Some("/api/v0/node/healthcheck".to_owned()),
-                                port: IntOrString::String("api".to_owned()),
-                                ..Default::default()
-                            }),
-                            initial_delay_seconds: Some(20),
-                            period_seconds: Some(3),
-                            timeout_seconds: Some(30),
-                            ..Default::default()
-                        }),
+                        readiness_probe: Some(
+                            bundle
+                                .config
+                                .readiness_probe
+                                .probe(bundle.config.probe_action.probe()),
+                        ),
+                        liveness_probe: Some(
+                            bundle
+                                .config
+                                .liveness_probe
+                                .probe(bundle.config.probe_action.probe()),
+                        ),
+                        startup_probe: bundle
+                            .config
+                            .startup_probe
+                            .as_ref()
+                            .map(|probe| probe.probe(bundle.config.probe_action.probe())),
 
                         resources: Some(ResourceRequirements {
                             limits: Some(bundle.config.resource_limits.clone().into()),
@@ -840,50 +1966,24 @@ pub fn stateful_set_spec(ns: &str, bundle: &CeramicBundle<'_>) -> StatefulSetSpe
                                 ..Default::default()
                             },
                         ]),
+                        termination_message_policy: Some(
+                            bundle.config.termination_message_policy.to_owned(),
+                        ),
                         ..Default::default()
                     },
-                    bundle.config.ipfs.container(&bundle.info),
+                    Container {
+                        termination_message_policy: Some(
+                            bundle.config.termination_message_policy.to_owned(),
+                        ),
+                        ..bundle.config.ipfs.container(&bundle.info)
+                    },
                 ],
-                init_containers: Some(vec![Container {
-                    command: Some(vec![
-                        "/bin/bash".to_owned(),
-                        "-c".to_owned(),
-                        "/ceramic-init/ceramic-init.sh".to_owned(),
-                    ]),
-                    env: Some(init_env),
-                    image: Some(bundle.config.image.to_owned()),
-                    image_pull_policy: Some(bundle.config.image_pull_policy.to_owned()),
-                    name: "init-ceramic-config".to_owned(),
-                    resources: Some(ResourceRequirements {
-                        limits: Some(bundle.config.resource_limits.clone().into()),
-                        requests: Some(bundle.config.resource_limits.clone().into()),
-                        ..Default::default()
-                    }),
-                    volume_mounts: Some(vec![
-                        VolumeMount {
-                            mount_path: "/config".to_owned(),
-                            name: "config-volume".to_owned(),
-                            ..Default::default()
-                        },
-                        VolumeMount {
-                            mount_path: "/ceramic-init".to_owned(),
-                            name: "ceramic-init".to_owned(),
-                            ..Default::default()
-                        },
-                    ]),
-                    ..Default::default()
-                }]),
+                init_containers: (!init_containers.is_empty()).then_some(init_containers),
                 volumes: Some(volumes),
                 ..Default::default()
             }),
         },
-        update_strategy: Some(StatefulSetUpdateStrategy {
-            rolling_update: Some(RollingUpdateStatefulSetStrategy {
-                max_unavailable: Some(IntOrString::String("50%".to_owned())),
-                ..Default::default()
-            }),
-            ..Default::default()
-        }),
+        update_strategy: Some(bundle.config.update_strategy()),
         volume_claim_templates: Some(vec![
             PersistentVolumeClaim {
                 metadata: ObjectMeta {
@@ -913,7 +2013,7 @@ pub fn stateful_set_spec(ns: &str, bundle: &CeramicBundle<'_>) -> StatefulSetSpe
                     resources: Some(ResourceRequirements {
                         requests: Some(BTreeMap::from_iter(vec![(
                             "storage".to_owned(),
-                            Quantity("10Gi".to_owned()),
+                            bundle.config.ipfs.volume_size(),
                         )])),
                         ..Default::default()
                     }),
@@ -945,22 +2045,22 @@ pub fn postgres_stateful_set_spec(bundle: &CeramicBundle<'_>) -> StatefulSetSpec
                     env: Some(vec![
                         EnvVar {
                             name: "POSTGRES_DB".to_owned(),
-                            value: postgres_config.db_name.clone(),
+                            value: Some(postgres_config.db_name.clone()),
                             ..Default::default()
                         },
                         EnvVar {
                             name: "POSTGRES_PASSWORD".to_owned(),
-                            value: postgres_config.password.clone(),
+                            value: Some(postgres_config.password.clone()),
                             ..Default::default()
                         },
                         EnvVar {
                             name: "POSTGRES_USER".to_owned(),
-                            value: postgres_config.user_name.clone(),
+                            value: Some(postgres_config.user_name.clone()),
                             ..Default::default()
                         },
                     ]),
-                    image: Some("postgres:15-alpine".to_owned()),
-                    image_pull_policy: Some("IfNotPresent".to_owned()),
+                    image: Some(postgres_config.image.clone()),
+                    image_pull_policy: Some(postgres_config.image_pull_policy.clone()),
                     name: "postgres".to_owned(),
                     ports: Some(vec![ContainerPort {
                         container_port: 5432,
@@ -968,22 +2068,8 @@ pub fn postgres_stateful_set_spec(bundle: &CeramicBundle<'_>) -> StatefulSetSpec
                         ..Default::default()
                     }]),
                     resources: Some(ResourceRequirements {
-                        limits: Some(
-                            (ResourceLimitsConfig {
-                                cpu: Quantity("1".to_owned()),
-                                memory: Quantity("1Gi".to_owned()),
-                                storage: Quantity("2Gi".to_owned()),
-                            })
-                            .into(),
-                        ),
-                        requests: Some(
-                            (ResourceLimitsConfig {
-                                cpu: Quantity("1".to_owned()),
-                                memory: Quantity("512Mi".to_owned()),
-                                storage: Quantity("2Gi".to_owned()),
-                            })
-                            .into(),
-                        ),
+                        limits: Some(postgres_config.resource_limits.clone().into()),
+                        requests: Some(postgres_config.resource_requests.clone().into()),
                         ..Default::default()
                     }),
                     volume_mounts: Some(vec![VolumeMount {
@@ -1046,3 +2132,606 @@ pub fn postgres_service_spec() -> ServiceSpec {
         ..Default::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use crate::network::{CeramicProbeSpec, ProbeSpec, ResourceLimitsSpec, UpdateStrategySpec};
+
+    fn update_strategy(spec: UpdateStrategySpec) -> StatefulSetUpdateStrategy {
+        CeramicConfig::try_from(CeramicSpec {
+            update_strategy: Some(spec),
+            ..Default::default()
+        })
+        .unwrap()
+        .update_strategy()
+    }
+
+    #[test]
+    fn rolling_update_strategy() {
+        assert_eq!(
+            StatefulSetUpdateStrategy {
+                type_: None,
+                rolling_update: Some(RollingUpdateStatefulSetStrategy {
+                    max_unavailable: Some(IntOrString::Int(2)),
+                    partition: None,
+                }),
+            },
+            update_strategy(UpdateStrategySpec {
+                type_: Some(UpdateStrategyType::RollingUpdate),
+                max_unavailable: Some(IntOrString::Int(2)),
+            })
+        );
+    }
+
+    #[test]
+    fn default_update_strategy() {
+        assert_eq!(
+            Some(IntOrString::String("50%".to_owned())),
+            update_strategy(UpdateStrategySpec::default())
+                .rolling_update
+                .and_then(|rolling_update| rolling_update.max_unavailable)
+        );
+    }
+
+    #[test]
+    fn on_delete_update_strategy() {
+        assert_eq!(
+            StatefulSetUpdateStrategy {
+                type_: Some("OnDelete".to_owned()),
+                rolling_update: None,
+            },
+            update_strategy(UpdateStrategySpec {
+                type_: Some(UpdateStrategyType::OnDelete),
+                // Ignored without rolling updates
+                max_unavailable: Some(IntOrString::Int(2)),
+            })
+        );
+    }
+
+    #[test]
+    fn merge_json_objects() {
+        let mut value = json!({
+            "anchor": { "auth-method": "did" },
+            "logger": { "log-level": 2, "log-to-files": false },
+        });
+        merge_json(
+            &mut value,
+            json!({
+                "logger": { "log-level": 0 },
+                "node": { "sync-override": true },
+            }),
+        );
+        assert_eq!(
+            json!({
+                "anchor": { "auth-method": "did" },
+                "logger": { "log-level": 0, "log-to-files": false },
+                "node": { "sync-override": true },
+            }),
+            value
+        );
+    }
+
+    #[test]
+    fn merge_json_replaces_arrays() {
+        let mut value = json!({ "cors-allowed-origins": ["a", "b"] });
+        merge_json(&mut value, json!({ "cors-allowed-origins": ["c"] }));
+        assert_eq!(json!({ "cors-allowed-origins": ["c"] }), value);
+    }
+
+    #[test]
+    fn merge_json_replaces_between_arrays_and_objects() {
+        let mut value = json!({ "a": [1, 2], "b": { "c": 1 } });
+        merge_json(&mut value, json!({ "a": { "c": 1 }, "b": [1, 2] }));
+        assert_eq!(json!({ "a": { "c": 1 }, "b": [1, 2] }), value);
+    }
+
+    #[test]
+    fn merge_json_null_removes() {
+        let mut value = json!({ "metrics": { "metrics-exporter-enabled": false, "port": 9464 } });
+        merge_json(
+            &mut value,
+            json!({ "metrics": { "port": null }, "missing": { "nested": null } }),
+        );
+        assert_eq!(
+            json!({ "metrics": { "metrics-exporter-enabled": false }, "missing": {} }),
+            value
+        );
+    }
+
+    #[test]
+    fn overlay_keeps_placeholders() {
+        let overlay = json!({
+            "http-api": { "cors-allowed-origins": ["https://example.com"] },
+            "indexing": { "disable-composedb": true },
+        });
+        let daemon_config = overlay_daemon_config(
+            &daemon_config("", "", "", HISTORICAL_SYNC_DAEMON_CONFIG),
+            &overlay,
+        );
+        assert!(daemon_config.contains(r#""log-level": ${CERAMIC_LOG_LEVEL},"#));
+        assert!(daemon_config.contains(r#""enable-historical-sync": ${ENABLE_HISTORICAL_SYNC}"#));
+        let quoted = daemon_config
+            .replace("${CERAMIC_LOG_LEVEL}", "2")
+            .replace("${ENABLE_HISTORICAL_SYNC}", "false");
+        let daemon_config: serde_json::Value = serde_json::from_str(&quoted).unwrap();
+        assert_eq!(
+            json!(["https://example.com"]),
+            daemon_config["http-api"]["cors-allowed-origins"]
+        );
+        assert_eq!(
+            json!(["${CERAMIC_ADMIN_DID}"]),
+            daemon_config["http-api"]["admin-dids"]
+        );
+        assert_eq!(json!(true), daemon_config["indexing"]["disable-composedb"]);
+        assert_eq!(json!(2), daemon_config["logger"]["log-level"]);
+    }
+
+    fn generated_daemon_config(spec: CeramicSpec) -> String {
+        let config_maps = config_maps(
+            &CeramicInfo::new("0", 1),
+            &CeramicConfig::try_from(spec).unwrap(),
+        );
+        config_maps[INIT_CONFIG_MAP_NAME]["daemon-config.json"].clone()
+    }
+
+    #[test]
+    fn historical_sync_unsupported() {
+        let supported = generated_daemon_config(CeramicSpec::default());
+        let unsupported = generated_daemon_config(CeramicSpec {
+            historical_sync_supported: Some(false),
+            ..Default::default()
+        });
+        assert!(supported.contains(r#""enable-historical-sync": ${ENABLE_HISTORICAL_SYNC}"#));
+        assert!(!unsupported.contains("enable-historical-sync"));
+        assert_eq!(
+            supported.replace(HISTORICAL_SYNC_DAEMON_CONFIG, ""),
+            unsupported
+        );
+        let unsupported: serde_json::Value =
+            serde_json::from_str(&unsupported.replace("${CERAMIC_LOG_LEVEL}", "2")).unwrap();
+        assert_eq!(json!(false), unsupported["indexing"]["disable-composedb"]);
+    }
+
+    #[test]
+    fn log_format() {
+        let default = generated_daemon_config(CeramicSpec::default());
+        let log_formats = LOG_FORMATS.map(|log_format| {
+            generated_daemon_config(CeramicSpec {
+                log_format: Some(log_format.to_owned()),
+                ..Default::default()
+            })
+        });
+        assert!(!default.contains("log-format"));
+        for (log_format, daemon_config) in LOG_FORMATS.iter().zip(&log_formats) {
+            assert_eq!(
+                default.replace(
+                    r#""log-to-files": false"#,
+                    &format!(
+                        r#""log-to-files": false,
+        "log-format": "{log_format}""#
+                    )
+                ),
+                *daemon_config
+            );
+            let daemon_config: serde_json::Value = serde_json::from_str(
+                &daemon_config
+                    .replace("${CERAMIC_LOG_LEVEL}", "2")
+                    .replace("${ENABLE_HISTORICAL_SYNC}", "false"),
+            )
+            .unwrap();
+            assert_eq!(json!(log_format), daemon_config["logger"]["log-format"]);
+        }
+        assert!(CeramicConfig::try_from(CeramicSpec {
+            log_format: Some("yaml".to_owned()),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    fn probe_action(probe_type: &str, command: Option<Vec<String>>) -> Probe {
+        CeramicConfig::try_from(CeramicSpec {
+            probe: Some(CeramicProbeSpec {
+                probe_type: Some(probe_type.to_owned()),
+                command,
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .unwrap()
+        .probe_action
+        .probe()
+    }
+
+    #[test]
+    fn http_get_probe() {
+        assert_eq!(
+            Probe {
+                http_get: Some(HTTPGetAction {
+                    path: Some("/api/v0/node/healthcheck".to_owned()),
+                    port: IntOrString::String("api".to_owned()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            probe_action("httpGet", None)
+        );
+        assert_eq!(
+            CeramicProbeAction::HttpGet,
+            CeramicConfig::try_from(CeramicSpec::default())
+                .unwrap()
+                .probe_action
+        );
+    }
+
+    #[test]
+    fn tcp_socket_probe() {
+        assert_eq!(
+            Probe {
+                tcp_socket: Some(TCPSocketAction {
+                    port: IntOrString::String("api".to_owned()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            probe_action("tcpSocket", None)
+        );
+    }
+
+    #[test]
+    fn exec_probe() {
+        let command = vec!["/usr/bin/healthcheck".to_owned(), "--api".to_owned()];
+        assert_eq!(
+            Probe {
+                exec: Some(ExecAction {
+                    command: Some(command.clone()),
+                }),
+                ..Default::default()
+            },
+            probe_action("exec", Some(command))
+        );
+    }
+
+    // Seconds the startup probe allows the node to start in, if there is one.
+    fn startup_budget(spec: CeramicSpec) -> Option<i32> {
+        let probe = CeramicConfig::try_from(spec)
+            .unwrap()
+            .startup_probe?
+            .probe(Probe::default());
+        Some(
+            probe.initial_delay_seconds.unwrap_or_default()
+                + probe.period_seconds.unwrap_or_default() * probe.failure_threshold.unwrap_or(3),
+        )
+    }
+
+    #[test]
+    fn historical_sync_startup_budget() {
+        assert_eq!(
+            Some(30 * 60 + 10),
+            startup_budget(CeramicSpec {
+                enable_historical_sync: Some(true),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            None,
+            startup_budget(CeramicSpec {
+                enable_historical_sync: Some(false),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            None,
+            startup_budget(CeramicSpec {
+                historical_sync_supported: Some(false),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            Some(60 + 10 * 180),
+            startup_budget(CeramicSpec {
+                enable_historical_sync: Some(false),
+                probe: Some(CeramicProbeSpec {
+                    startup: Some(ProbeSpec {
+                        initial_delay_seconds: Some(60),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn deferred_load_balancer() {
+        let config = CeramicConfig::try_from(CeramicSpec {
+            defer_load_balancer: Some(true),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            Some("ClusterIP".to_owned()),
+            service_spec(&config, false).type_
+        );
+        assert_eq!(
+            Some("LoadBalancer".to_owned()),
+            service_spec(&config, true).type_
+        );
+        // Only LoadBalancer services are deferred
+        let config = CeramicConfig::try_from(CeramicSpec {
+            defer_load_balancer: Some(true),
+            service_type: Some("NodePort".to_owned()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            Some("NodePort".to_owned()),
+            service_spec(&config, false).type_
+        );
+    }
+
+    #[test]
+    fn managed_postgres_connection_string() {
+        let config = CeramicConfig::try_from(CeramicSpec {
+            ceramic_postgres: Some(CeramicPostgresSpec {
+                db_name: Some("ceramic_db".to_owned()),
+                user_name: None,
+                password: Some("secret".to_owned()),
+                image: None,
+                image_pull_policy: None,
+                resource_limits: None,
+            }),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!("ceramic_db", config.postgres.db_name);
+        assert_eq!("ceramic", config.postgres.user_name);
+        assert_eq!("secret", config.postgres.password);
+    }
+
+    #[test]
+    fn managed_postgres_resource_limits() {
+        let config = CeramicConfig::try_from(CeramicSpec {
+            ceramic_postgres: Some(CeramicPostgresSpec {
+                db_name: None,
+                user_name: None,
+                password: None,
+                image: None,
+                image_pull_policy: None,
+                resource_limits: Some(ResourceLimitsSpec {
+                    cpu: None,
+                    memory: Some(Quantity("4Gi".to_owned())),
+                    storage: None,
+                }),
+            }),
+            ..Default::default()
+        })
+        .unwrap();
+        let bundle = CeramicBundle {
+            info: CeramicInfo::new("0", 1),
+            config: &config,
+            net_config: &NetworkConfig::default(),
+            datadog: &DataDogConfig::default(),
+        };
+        let resources = postgres_stateful_set_spec(&bundle)
+            .template
+            .spec
+            .unwrap()
+            .containers[0]
+            .resources
+            .clone()
+            .unwrap();
+        // The override applies to both the limits and the requests of the postgres container
+        for resources in [resources.limits.unwrap(), resources.requests.unwrap()] {
+            assert_eq!(Some(&Quantity("4Gi".to_owned())), resources.get("memory"));
+        }
+    }
+
+    #[test]
+    fn invalid_ceramic_specs() {
+        assert_eq!(
+            Err(ConfigError::new(
+                "ceramicPostgres.password",
+                "must not be empty for a managed postgres"
+            )),
+            CeramicConfig::try_from(CeramicSpec {
+                ceramic_postgres: Some(CeramicPostgresSpec {
+                    db_name: None,
+                    user_name: None,
+                    password: Some(String::new()),
+                    image: None,
+                    image_pull_policy: None,
+                    resource_limits: None,
+                }),
+                ..Default::default()
+            })
+            .map(|_| ())
+        );
+        assert_eq!(
+            Err(ConfigError::new(
+                "nodePorts",
+                "ceramic node ports require the NodePort service type"
+            )),
+            CeramicConfig::try_from(CeramicSpec {
+                node_ports: Some(HashMap::new()),
+                ..Default::default()
+            })
+            .map(|_| ())
+        );
+        assert_eq!(
+            Err(ConfigError::new(
+                "httpApiRateLimit.windowSeconds",
+                "0 must be positive"
+            )),
+            CeramicConfig::try_from(CeramicSpec {
+                http_api_rate_limit: Some(RateLimitSpec {
+                    requests: None,
+                    window_seconds: Some(0),
+                }),
+                ..Default::default()
+            })
+            .map(|_| ())
+        );
+        assert_eq!(
+            Err(ConfigError::new(
+                "ipfs.rust.swarmAddresses",
+                "tcp port 4001 does not match swarmPort 4002"
+            )),
+            CeramicConfig::try_from(CeramicSpec {
+                ipfs: Some(IpfsSpec::Rust(RustIpfsSpec {
+                    swarm_port: Some(4002),
+                    swarm_addresses: Some(vec!["/ip4/0.0.0.0/tcp/4001".to_owned()]),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            })
+            .map(|_| ())
+        );
+        assert_eq!(
+            Err(ConfigError::new(
+                "ipfs.rust.swarmAddresses",
+                "must include a tcp address"
+            )),
+            CeramicConfig::try_from(CeramicSpec {
+                ipfs: Some(IpfsSpec::Rust(RustIpfsSpec {
+                    swarm_addresses: Some(vec!["/ip4/0.0.0.0/udp/4001/quic-v1".to_owned()]),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            })
+            .map(|_| ())
+        );
+        assert!(CeramicConfigs::try_from(vec![
+            CeramicSpec::default(),
+            CeramicSpec {
+                service_type: Some("ExternalName".to_owned()),
+                ..Default::default()
+            },
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn init_job_changed() {
+        let job_spec = |spec: CeramicSpec| {
+            let config = CeramicConfig::try_from(CeramicSpec {
+                init_job: Some(true),
+                ..spec
+            })
+            .unwrap();
+            init_job_spec(&CeramicBundle {
+                info: CeramicInfo::new("0", 1),
+                config: &config,
+                net_config: &NetworkConfig::default(),
+                datadog: &DataDogConfig::default(),
+            })
+        };
+        let current = job_spec(CeramicSpec::default());
+        assert!(!super::init_job_changed(
+            &current,
+            &job_spec(CeramicSpec::default())
+        ));
+        assert!(super::init_job_changed(
+            &current,
+            &job_spec(CeramicSpec {
+                init_job_image: Some("bitnami/kubectl:1.29".to_owned()),
+                ..Default::default()
+            })
+        ));
+        assert!(super::init_job_changed(
+            &current,
+            &job_spec(CeramicSpec {
+                init_image: Some("ceramicnetwork/composedb:dev".to_owned()),
+                ..Default::default()
+            })
+        ));
+        // Fields defaulted by the server do not recreate the job
+        let mut defaulted = current.clone();
+        defaulted.template.spec.as_mut().unwrap().containers[0].termination_message_path =
+            Some("/dev/termination-log".to_owned());
+        assert!(!super::init_job_changed(&defaulted, &current));
+    }
+
+    // Termination message policies of the containers and init containers of the ceramic pods.
+    fn termination_message_policies(spec: CeramicSpec) -> Vec<Option<String>> {
+        let config = CeramicConfig::try_from(spec).unwrap();
+        let bundle = CeramicBundle {
+            info: CeramicInfo::new("0", 1),
+            config: &config,
+            net_config: &NetworkConfig::default(),
+            datadog: &DataDogConfig::default(),
+        };
+        let pod_spec = stateful_set_spec("keramik-test", &bundle)
+            .template
+            .spec
+            .unwrap();
+        pod_spec
+            .containers
+            .into_iter()
+            .chain(pod_spec.init_containers.unwrap_or_default())
+            .map(|container| container.termination_message_policy)
+            .collect()
+    }
+
+    #[test]
+    fn termination_message_policy() {
+        // ceramic, ipfs, wait-for-postgres and init-ceramic-config
+        assert_eq!(
+            vec![Some("FallbackToLogsOnError".to_owned()); 4],
+            termination_message_policies(CeramicSpec::default())
+        );
+        assert_eq!(
+            vec![Some("File".to_owned()); 4],
+            termination_message_policies(CeramicSpec {
+                termination_message_policy: Some("File".to_owned()),
+                ..Default::default()
+            })
+        );
+        assert!(CeramicConfig::try_from(CeramicSpec {
+            termination_message_policy: Some("Logs".to_owned()),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn network_config_pubsub_topic() {
+        let config = NetworkConfig::try_from(&NetworkSpec {
+            pubsub_topic: Some("/ceramic/dev".to_owned()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!("/ceramic/dev", config.pubsub_topic);
+        assert_eq!(
+            "invalid pubsubTopic: dev does not match /ceramic/<name>",
+            NetworkConfig::try_from(&NetworkSpec {
+                pubsub_topic: Some("dev".to_owned()),
+                ..Default::default()
+            })
+            .err()
+            .unwrap()
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn network_config_revision() {
+        let config = NetworkConfig::try_from(&NetworkSpec {
+            revision: Some("0a1b2c3".to_owned()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(Some("0a1b2c3".to_owned()), config.revision);
+        for revision in ["", "-0a1b2c3", "0a1b/2c3", &"a".repeat(64)] {
+            assert!(NetworkConfig::try_from(&NetworkSpec {
+                revision: Some(revision.to_owned()),
+                ..Default::default()
+            })
+            .is_err());
+        }
+    }
+}
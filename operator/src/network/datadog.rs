@@ -3,15 +3,30 @@ use std::collections::BTreeMap;
 use k8s_openapi::api::core::v1::{EnvVar, EnvVarSource, ObjectFieldSelector};
 
 use crate::network::DataDogSpec;
+use crate::utils::ConfigError;
 
 /// Describes if and how to configure datadog telemetry
 pub struct DataDogConfig {
     enabled: bool,
     version: String,
     profiling_enabled: bool,
+    env: Option<String>,
+    service: Option<String>,
 }
 
 impl DataDogConfig {
+    /// Value of the env unified service tag, the network namespace unless configured.
+    fn env_tag(&self, ns: &str) -> String {
+        self.env.to_owned().unwrap_or_else(|| ns.to_owned())
+    }
+    /// Value of the service unified service tag of a component, e.g. ceramic or cas.
+    fn service_tag(&self, component: &str) -> String {
+        match &self.service {
+            Some(service) => format!("{service}-{component}"),
+            None => component.to_owned(),
+        }
+    }
+
     pub fn inject_annotations(&self, annotations: &mut BTreeMap<String, String>) {
         if self.enabled {
             annotations.insert(
@@ -20,15 +35,13 @@ impl DataDogConfig {
             );
         }
     }
-    pub fn inject_labels(
-        &self,
-        labels: &mut BTreeMap<String, String>,
-        env: String,
-        service: String,
-    ) {
+    pub fn inject_labels(&self, labels: &mut BTreeMap<String, String>, ns: &str, component: &str) {
         if self.enabled {
-            labels.insert("tags.datadoghq.com/env".to_owned(), env);
-            labels.insert("tags.datadoghq.com/service".to_owned(), service);
+            labels.insert("tags.datadoghq.com/env".to_owned(), self.env_tag(ns));
+            labels.insert(
+                "tags.datadoghq.com/service".to_owned(),
+                self.service_tag(component),
+            );
             labels.insert(
                 "tags.datadoghq.com/version".to_owned(),
                 self.version.to_owned(),
@@ -39,7 +52,7 @@ impl DataDogConfig {
             );
         }
     }
-    pub fn inject_env(&self, env: &mut Vec<EnvVar>) {
+    pub fn inject_env(&self, env: &mut Vec<EnvVar>, ns: &str, component: &str) {
         if self.enabled {
             env.push(EnvVar {
                 name: "DD_AGENT_HOST".to_owned(),
@@ -52,6 +65,22 @@ impl DataDogConfig {
                 }),
                 ..Default::default()
             });
+            // Unified service tags, matching the pod labels
+            env.push(EnvVar {
+                name: "DD_ENV".to_owned(),
+                value: Some(self.env_tag(ns)),
+                ..Default::default()
+            });
+            env.push(EnvVar {
+                name: "DD_SERVICE".to_owned(),
+                value: Some(self.service_tag(component)),
+                ..Default::default()
+            });
+            env.push(EnvVar {
+                name: "DD_VERSION".to_owned(),
+                value: Some(self.version.to_owned()),
+                ..Default::default()
+            });
             env.push(EnvVar {
                 name: "DD_RUNTIME_METRICS_ENABLED".to_owned(),
                 value: Some("true".to_owned()),
@@ -73,21 +102,27 @@ impl Default for DataDogConfig {
             enabled: false,
             version: "0".to_owned(),
             profiling_enabled: false,
+            env: None,
+            service: None,
         }
     }
 }
 
-impl From<&Option<DataDogSpec>> for DataDogConfig {
-    fn from(value: &Option<DataDogSpec>) -> Self {
+impl TryFrom<&Option<DataDogSpec>> for DataDogConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: &Option<DataDogSpec>) -> Result<Self, Self::Error> {
         let default = DataDogConfig::default();
         if let Some(value) = value {
-            Self {
+            Ok(Self {
                 enabled: value.enabled.unwrap_or(default.enabled),
                 version: value.version.to_owned().unwrap_or(default.version),
                 profiling_enabled: value.profiling_enabled.unwrap_or(default.profiling_enabled),
-            }
+                env: value.env.to_owned(),
+                service: value.service.to_owned(),
+            })
         } else {
-            default
+            Ok(default)
         }
     }
 }
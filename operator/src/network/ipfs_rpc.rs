@@ -1,21 +1,65 @@
+use std::{future::Future, time::Duration};
+
 use anyhow::{anyhow, bail, Result};
 use async_trait::async_trait;
 use keramik_common::peer_info::IpfsPeerInfo;
 use multiaddr::{Multiaddr, Protocol};
 use multihash::Multihash;
 use serde::Deserialize;
+use tracing::debug;
 
 /// Define the behavior we consume from the IPFS RPC API.
 #[async_trait]
 pub trait IpfsRpcClient {
     async fn peer_info(&self, ipfs_rpc_addr: &str) -> Result<IpfsPeerInfo>;
     async fn peer_status(&self, ipfs_rpc_addr: &str) -> Result<PeerStatus>;
+    async fn repo_stat(&self, ipfs_rpc_addr: &str) -> Result<RepoStat>;
 }
 /// Status of the current peer
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct PeerStatus {
     /// Number of connected peers
     pub connected_peers: i32,
+    /// Peer IDs of the connected peers
+    pub connected_peer_ids: Vec<String>,
+}
+
+/// Size of the repo of the current peer
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct RepoStat {
+    /// Size of the repo in bytes
+    #[serde(rename = "RepoSize")]
+    pub repo_size: u64,
+    /// Number of objects stored in the repo
+    #[serde(rename = "NumObjects")]
+    pub num_objects: u64,
+}
+
+#[derive(Deserialize)]
+struct SwarmPeer {
+    #[serde(rename = "Peer")]
+    peer: String,
+}
+
+#[derive(Deserialize)]
+struct SwarmPeersResponse {
+    #[serde(rename = "Peers")]
+    peers: Option<Vec<SwarmPeer>>,
+}
+
+impl From<SwarmPeersResponse> for PeerStatus {
+    fn from(value: SwarmPeersResponse) -> Self {
+        let connected_peer_ids: Vec<String> = value
+            .peers
+            .unwrap_or_default()
+            .into_iter()
+            .map(|peer| peer.peer)
+            .collect();
+        Self {
+            connected_peers: connected_peer_ids.len() as i32,
+            connected_peer_ids,
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -24,19 +68,112 @@ struct ErrorResponse {
     message: String,
 }
 
-pub struct HttpRpcClient;
+/// Error the IPFS RPC API responded with, retrying the request does not help.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+struct RpcError(String);
 
-#[async_trait]
-impl IpfsRpcClient for HttpRpcClient {
-    async fn peer_info(&self, ipfs_rpc_addr: &str) -> Result<IpfsPeerInfo> {
-        let client = reqwest::Client::new();
-        let resp = client
+/// Whether a failed request may succeed when retried. Requests the peer could not be reached for
+/// are retried, while error responses and other failures of the HTTP client are terminal.
+fn is_retryable(err: &anyhow::Error) -> bool {
+    match err.downcast_ref::<reqwest::Error>() {
+        Some(err) => err.is_connect() || err.is_timeout(),
+        None => !err.is::<RpcError>(),
+    }
+}
+
+/// Bounded exponential backoff between attempts of a request.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub attempts: u32,
+    /// Delay before the second attempt, doubled for each following attempt
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between attempts
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay to wait after the given failed attempt, starting at 1.
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_backoff)
+    }
+
+    /// Run the request until it succeeds, fails with an error that is not retryable or the
+    /// attempts are exhausted.
+    async fn run<T, F, Fut>(&self, what: &str, mut request: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let attempts = self.attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            match request().await {
+                Ok(value) => return Ok(value),
+                Err(err) if !is_retryable(&err) => {
+                    return Err(err.context(format!("{what} failed")))
+                }
+                Err(err) if attempt >= attempts => {
+                    return Err(err.context(format!("{what} failed after {attempt} attempts")))
+                }
+                Err(err) => {
+                    let backoff = self.backoff(attempt);
+                    debug!(attempt, ?backoff, %err, "{what} failed, retrying");
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Default timeout of a single request to the IPFS RPC API.
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// IPFS RPC client that retries failed requests.
+pub struct HttpRpcClient {
+    client: reqwest::Client,
+    retry: RetryPolicy,
+}
+
+impl Default for HttpRpcClient {
+    fn default() -> Self {
+        Self::new(DEFAULT_RPC_TIMEOUT, RetryPolicy::default())
+            .expect("should be able to build http client")
+    }
+}
+
+impl HttpRpcClient {
+    /// Create a client where each request attempt times out after timeout.
+    pub fn new(timeout: Duration, retry: RetryPolicy) -> Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder().timeout(timeout).build()?,
+            retry,
+        })
+    }
+
+    async fn try_peer_info(&self, ipfs_rpc_addr: &str) -> Result<IpfsPeerInfo> {
+        let resp = self
+            .client
             .post(format!("{}/api/v0/id", ipfs_rpc_addr))
             .send()
             .await?;
         if !resp.status().is_success() {
             let data: ErrorResponse = resp.json().await?;
-            bail!("peer id failed: {}", data.message)
+            bail!(RpcError(format!("peer id failed: {}", data.message)))
         }
 
         #[derive(serde::Deserialize)]
@@ -75,6 +212,7 @@ impl IpfsRpcClient for HttpRpcClient {
                 peer_id: data.id,
                 ipfs_rpc_addr: ipfs_rpc_addr.to_owned(),
                 p2p_addrs,
+                connected_peers: None,
             })
         } else {
             Err(anyhow!(
@@ -82,31 +220,53 @@ impl IpfsRpcClient for HttpRpcClient {
             ))
         }
     }
-    async fn peer_status(&self, ipfs_rpc_addr: &str) -> Result<PeerStatus> {
-        let client = reqwest::Client::new();
-        let resp = client
+
+    async fn try_peer_status(&self, ipfs_rpc_addr: &str) -> Result<PeerStatus> {
+        let resp = self
+            .client
             .post(format!("{}/api/v0/swarm/peers", ipfs_rpc_addr))
             .send()
             .await?;
         if !resp.status().is_success() {
             let data: ErrorResponse = resp.json().await?;
-            bail!("peer id failed: {}", data.message)
+            bail!(RpcError(format!("peer id failed: {}", data.message)))
         }
 
-        #[derive(serde::Deserialize)]
-        struct Peer {
-            //We currently do not care about any of the information of connected peers,
-            // just the number of them. As a result this struct does not have any fields.
-        }
-        #[derive(serde::Deserialize)]
-        struct Response {
-            #[serde(rename = "Peers")]
-            peers: Option<Vec<Peer>>,
+        let data: SwarmPeersResponse = resp.json().await?;
+        Ok(data.into())
+    }
+
+    async fn try_repo_stat(&self, ipfs_rpc_addr: &str) -> Result<RepoStat> {
+        let resp = self
+            .client
+            .post(format!("{}/api/v0/repo/stat", ipfs_rpc_addr))
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let data: ErrorResponse = resp.json().await?;
+            bail!(RpcError(format!("repo stat failed: {}", data.message)))
         }
-        let data: Response = resp.json().await?;
-        Ok(PeerStatus {
-            connected_peers: data.peers.unwrap_or_default().len() as i32,
-        })
+
+        Ok(resp.json().await?)
+    }
+}
+
+#[async_trait]
+impl IpfsRpcClient for HttpRpcClient {
+    async fn peer_info(&self, ipfs_rpc_addr: &str) -> Result<IpfsPeerInfo> {
+        self.retry
+            .run("peer info", || self.try_peer_info(ipfs_rpc_addr))
+            .await
+    }
+    async fn peer_status(&self, ipfs_rpc_addr: &str) -> Result<PeerStatus> {
+        self.retry
+            .run("peer status", || self.try_peer_status(ipfs_rpc_addr))
+            .await
+    }
+    async fn repo_stat(&self, ipfs_rpc_addr: &str) -> Result<RepoStat> {
+        self.retry
+            .run("repo stat", || self.try_repo_stat(ipfs_rpc_addr))
+            .await
     }
 }
 
@@ -122,6 +282,171 @@ pub(crate) mod tests {
         impl IpfsRpcClient for IpfsRpcClientTest {
             async fn peer_info(&self, ipfs_rpc_addr: &str) -> Result<IpfsPeerInfo>;
             async fn peer_status(&self, ipfs_rpc_addr: &str) -> Result<PeerStatus>;
+            async fn repo_stat(&self, ipfs_rpc_addr: &str) -> Result<RepoStat>;
         }
     }
+
+    #[test]
+    fn parse_connected_peer_ids() {
+        let data: SwarmPeersResponse = serde_json::from_str(
+            r#"{
+                "Peers": [
+                    {
+                        "Addr": "/ip4/10.0.0.1/tcp/4001",
+                        "Peer": "12D3KooWJpFpF4YhYfKNjyX7XN7kXWjWcTpJV6BquRLJgNRa7Gxa"
+                    },
+                    {
+                        "Addr": "/ip4/10.0.0.2/tcp/4001",
+                        "Peer": "12D3KooWRJFy6kLpDw9rNjMKUvAaMVNpYeQXX4zyG6VXFnHZYcXj"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let status: PeerStatus = data.into();
+        assert_eq!(2, status.connected_peers);
+        assert_eq!(
+            vec![
+                "12D3KooWJpFpF4YhYfKNjyX7XN7kXWjWcTpJV6BquRLJgNRa7Gxa",
+                "12D3KooWRJFy6kLpDw9rNjMKUvAaMVNpYeQXX4zyG6VXFnHZYcXj",
+            ],
+            status.connected_peer_ids
+        );
+    }
+
+    #[test]
+    fn retry_backoff_bounded() {
+        let retry = RetryPolicy::default();
+        assert_eq!(Duration::from_millis(500), retry.backoff(1));
+        assert_eq!(Duration::from_secs(1), retry.backoff(2));
+        assert_eq!(Duration::from_secs(5), retry.backoff(10));
+        assert_eq!(Duration::from_secs(5), retry.backoff(u32::MAX));
+    }
+
+    #[tokio::test]
+    async fn retry_until_success() {
+        let retry = RetryPolicy {
+            initial_backoff: Duration::ZERO,
+            ..Default::default()
+        };
+        let mut calls = 0;
+        let value = retry
+            .run("test", || {
+                calls += 1;
+                let calls = calls;
+                async move {
+                    if calls < 3 {
+                        bail!("not ready")
+                    }
+                    Ok(calls)
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(3, value);
+    }
+
+    #[tokio::test]
+    async fn retry_exhausted() {
+        let retry = RetryPolicy {
+            initial_backoff: Duration::ZERO,
+            ..Default::default()
+        };
+        let mut calls = 0;
+        let err = retry
+            .run("test", || {
+                calls += 1;
+                async { Err::<(), _>(anyhow!("not ready")) }
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(3, calls);
+        assert_eq!("test failed after 3 attempts", err.to_string());
+        assert_eq!("not ready", err.root_cause().to_string());
+    }
+
+    #[tokio::test]
+    async fn retry_stops_at_error_responses() {
+        let retry = RetryPolicy {
+            initial_backoff: Duration::ZERO,
+            ..Default::default()
+        };
+        let mut calls = 0;
+        let err = retry
+            .run("test", || {
+                calls += 1;
+                async { Err::<(), _>(RpcError("peer id failed: not ready".to_owned()).into()) }
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(1, calls);
+        assert_eq!("test failed", err.to_string());
+        assert_eq!("peer id failed: not ready", err.root_cause().to_string());
+    }
+
+    #[tokio::test]
+    async fn retry_unreachable_peer() {
+        let client = HttpRpcClient::new(
+            Duration::from_secs(1),
+            RetryPolicy {
+                attempts: 2,
+                initial_backoff: Duration::ZERO,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        // Nothing listens on port 1, so the connection is refused
+        let err = client.peer_info("http://127.0.0.1:1").await.unwrap_err();
+        assert_eq!("peer info failed after 2 attempts", err.to_string());
+    }
+
+    #[tokio::test]
+    async fn retry_slow_peer() {
+        let client = HttpRpcClient::new(
+            Duration::from_millis(100),
+            RetryPolicy {
+                attempts: 2,
+                initial_backoff: Duration::ZERO,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        // The listener accepts connections into its backlog but never responds
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        let err = tokio::time::timeout(Duration::from_secs(5), client.peer_info(&addr))
+            .await
+            .expect("slow peer should time out")
+            .unwrap_err();
+        assert_eq!("peer info failed after 2 attempts", err.to_string());
+    }
+
+    #[test]
+    fn parse_repo_stat() {
+        let stat: RepoStat = serde_json::from_str(
+            r#"{
+                "NumObjects": 1024,
+                "RepoPath": "/data/ipfs",
+                "RepoSize": 52428800,
+                "StorageMax": 10000000000,
+                "Version": "fs-repo@14"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            RepoStat {
+                repo_size: 52428800,
+                num_objects: 1024,
+            },
+            stat
+        );
+    }
+
+    #[test]
+    fn parse_no_connected_peers() {
+        let data: SwarmPeersResponse = serde_json::from_str(r#"{"Peers": null}"#).unwrap();
+        let status: PeerStatus = data.into();
+        assert_eq!(0, status.connected_peers);
+        assert!(status.connected_peer_ids.is_empty());
+    }
 }
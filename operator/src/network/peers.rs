@@ -1,12 +1,207 @@
 use std::collections::BTreeMap;
 
-use keramik_common::peer_info::Peer;
+use anyhow::{anyhow, Result};
+use k8s_openapi::api::core::v1::{
+    ConfigMapProjection, ProjectedVolumeSource, Volume, VolumeProjection,
+};
+use keramik_common::peer_info::{compress_peers, Peer};
+
+use crate::network::controller::PEERS_CONFIG_MAP_NAME;
 
 pub const PEERS_MAP_KEY: &str = "peers.json";
+/// Key of the gzipped and base64 encoded peers, only present when the peers are compressed.
+pub const PEERS_COMPRESSED_MAP_KEY: &str = "peers.json.gz";
+/// Key of the number of config maps the peers are sharded across, only present when sharded.
+pub const PEERS_SHARDS_KEY: &str = "shards";
+/// Maximum number of config maps the peers can be sharded across.
+pub const MAX_PEERS_SHARDS: usize = 8;
+/// Size in bytes above which the peers are sharded across config maps.
+/// Leaves headroom below the 1MiB size limit of a config map.
+pub const DEFAULT_PEERS_SHARD_BYTES: usize = 768 * 1024;
+
+/// Name of the config map holding the given shard of peers.
+pub fn peers_shard_name(shard: usize) -> String {
+    if shard == 0 {
+        PEERS_CONFIG_MAP_NAME.to_owned()
+    } else {
+        format!("{PEERS_CONFIG_MAP_NAME}-{shard}")
+    }
+}
+
+/// Key within its config map of the given shard of peers.
+/// Keys are unique across shards so all shards can be projected into a single directory.
+pub fn peers_shard_key(shard: usize) -> String {
+    if shard == 0 {
+        PEERS_MAP_KEY.to_owned()
+    } else {
+        format!("peers-{shard}.json")
+    }
+}
+
+/// Split the peers into config maps no larger than max_bytes, returned as pairs of the config
+/// map name and its data. Consumers reassemble the peers by concatenating the shards in order.
+///
+/// When compress is set the peers are stored compressed in a single config map instead,
+/// unless they are still larger than max_bytes in which case they are sharded uncompressed.
+pub fn peer_config_maps_data(
+    peers: &[Peer],
+    max_bytes: usize,
+    compress: bool,
+) -> Result<Vec<(String, BTreeMap<String, String>)>> {
+    if compress {
+        let compressed = compress_peers(peers)?;
+        if compressed.len() <= max_bytes {
+            return Ok(vec![(
+                peers_shard_name(0),
+                BTreeMap::from_iter(vec![(PEERS_COMPRESSED_MAP_KEY.to_owned(), compressed)]),
+            )]);
+        }
+    }
+    let mut shards: Vec<Vec<String>> = vec![vec![]];
+    // Size of the current shard when serialized as a JSON array
+    let mut shard_bytes = 2;
+    for peer in peers {
+        let peer = serde_json::to_string(peer)?;
+        let shard = shards.last_mut().expect("there should always be a shard");
+        // Account for the separating comma
+        let peer_bytes = peer.len() + usize::from(!shard.is_empty());
+        if !shard.is_empty() && shard_bytes + peer_bytes > max_bytes {
+            shard_bytes = 2 + peer.len();
+            shards.push(vec![peer]);
+        } else {
+            shard_bytes += peer_bytes;
+            shard.push(peer);
+        }
+    }
+    if shards.len() > MAX_PEERS_SHARDS {
+        return Err(anyhow!(
+            "peers need {} config maps of at most {max_bytes} bytes, maximum {MAX_PEERS_SHARDS}",
+            shards.len()
+        ));
+    }
+
+    let sharded = shards.len() > 1;
+    Ok(shards
+        .iter()
+        .enumerate()
+        .map(|(i, shard)| {
+            let mut data =
+                BTreeMap::from_iter(vec![(peers_shard_key(i), format!("[{}]", shard.join(",")))]);
+            if i == 0 && sharded {
+                data.insert(PEERS_SHARDS_KEY.to_owned(), shards.len().to_string());
+            }
+            (peers_shard_name(i), data)
+        })
+        .collect())
+}
+
+/// Volume exposing every shard of peers in a single directory.
+pub fn peers_volume() -> Volume {
+    Volume {
+        name: "keramik-peers".to_owned(),
+        projected: Some(ProjectedVolumeSource {
+            default_mode: Some(0o755),
+            sources: Some(
+                (0..MAX_PEERS_SHARDS)
+                    .map(|shard| VolumeProjection {
+                        config_map: Some(ConfigMapProjection {
+                            name: Some(peers_shard_name(shard)),
+                            // Only the first shard always exists
+                            optional: (shard > 0).then_some(true),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    })
+                    .collect(),
+            ),
+        }),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use keramik_common::peer_info::{decompress_peers, CeramicPeerInfo};
+
+    fn peers(n: usize) -> Vec<Peer> {
+        (0..n)
+            .map(|i| {
+                Peer::Ceramic(CeramicPeerInfo {
+                    peer_id: format!("peer_id_{i}"),
+                    ipfs_rpc_addr: format!(
+                        "http://ceramic-0-{i}.ceramic-0.keramik-test.svc.cluster.local:5001"
+                    ),
+                    ceramic_addr: format!(
+                        "http://ceramic-0-{i}.ceramic-0.keramik-test.svc.cluster.local:7007"
+                    ),
+                    p2p_addrs: vec![format!("/ip4/10.0.0.{}/tcp/4001/p2p/peer_id_{i}", i % 256)],
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn small_peers_not_sharded() {
+        let peers = peers(2);
+        let maps = peer_config_maps_data(&peers, DEFAULT_PEERS_SHARD_BYTES, false).unwrap();
+        assert_eq!(1, maps.len());
+        let (name, data) = &maps[0];
+        assert_eq!(PEERS_CONFIG_MAP_NAME, name);
+        assert_eq!(
+            BTreeMap::from_iter(vec![(
+                PEERS_MAP_KEY.to_owned(),
+                serde_json::to_string(&peers).unwrap()
+            )]),
+            *data
+        );
+    }
+
+    #[test]
+    fn large_peers_sharded() {
+        let peers = peers(1000);
+        let max_bytes = 64 * 1024;
+        let maps = peer_config_maps_data(&peers, max_bytes, false).unwrap();
+        assert!(maps.len() > 1);
+        assert_eq!(
+            Some(&maps.len().to_string()),
+            maps[0].1.get(PEERS_SHARDS_KEY)
+        );
+
+        let mut reassembled: Vec<Peer> = Vec::new();
+        for (i, (name, data)) in maps.iter().enumerate() {
+            assert_eq!(peers_shard_name(i), *name);
+            let shard = data.get(&peers_shard_key(i)).unwrap();
+            assert!(shard.len() <= max_bytes);
+            reassembled.extend(serde_json::from_str::<Vec<Peer>>(shard).unwrap());
+        }
+        assert_eq!(peers, reassembled);
+    }
+
+    #[test]
+    fn too_many_shards() {
+        assert!(peer_config_maps_data(&peers(1000), 1024, false).is_err());
+    }
+
+    #[test]
+    fn compressed_peers_round_trip() {
+        let peers = peers(1000);
+        let maps = peer_config_maps_data(&peers, DEFAULT_PEERS_SHARD_BYTES, true).unwrap();
+        assert_eq!(1, maps.len());
+        let (name, data) = &maps[0];
+        assert_eq!(PEERS_CONFIG_MAP_NAME, name);
+        assert_eq!(None, data.get(PEERS_MAP_KEY));
+        let compressed = data.get(PEERS_COMPRESSED_MAP_KEY).unwrap();
+        assert!(compressed.len() < serde_json::to_string(&peers).unwrap().len());
+        assert_eq!(peers, decompress_peers(compressed).unwrap());
+    }
 
-pub fn peer_config_map_data(peers: &[Peer]) -> BTreeMap<String, String> {
-    BTreeMap::from_iter(vec![(
-        PEERS_MAP_KEY.to_owned(),
-        serde_json::to_string(peers).unwrap(),
-    )])
+    #[test]
+    fn compressed_peers_too_large() {
+        // The compressed peers do not fit so they are sharded uncompressed,
+        // which needs too many shards
+        assert!(peer_config_maps_data(&peers(1000), 16 * 1024, true).is_err());
+    }
 }
@@ -0,0 +1,43 @@
+use k8s_openapi::api::core::v1::Probe;
+
+use crate::network::ProbeSpec;
+
+#[derive(Clone)]
+pub struct ProbeConfig {
+    /// Seconds to wait after the container starts before probing
+    pub initial_delay_seconds: i32,
+    /// Seconds between probes
+    pub period_seconds: i32,
+    /// Seconds after which a probe times out
+    pub timeout_seconds: i32,
+    /// Consecutive failures before the probe is considered failed
+    pub failure_threshold: Option<i32>,
+}
+
+impl ProbeConfig {
+    pub fn from_spec(spec: Option<ProbeSpec>, defaults: Self) -> Self {
+        if let Some(spec) = spec {
+            Self {
+                initial_delay_seconds: spec
+                    .initial_delay_seconds
+                    .unwrap_or(defaults.initial_delay_seconds),
+                period_seconds: spec.period_seconds.unwrap_or(defaults.period_seconds),
+                timeout_seconds: spec.timeout_seconds.unwrap_or(defaults.timeout_seconds),
+                failure_threshold: spec.failure_threshold.or(defaults.failure_threshold),
+            }
+        } else {
+            defaults
+        }
+    }
+
+    /// Applies the configured timing to the probe action.
+    pub fn probe(&self, action: Probe) -> Probe {
+        Probe {
+            initial_delay_seconds: Some(self.initial_delay_seconds),
+            period_seconds: Some(self.period_seconds),
+            timeout_seconds: Some(self.timeout_seconds),
+            failure_threshold: self.failure_threshold,
+            ..action
+        }
+    }
+}
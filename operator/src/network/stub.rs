@@ -5,7 +5,7 @@ use expect_test::{expect_file, ExpectFile};
 use k8s_openapi::api::{
     apps::v1::StatefulSet,
     batch::v1::Job,
-    core::v1::{Pod, Secret},
+    core::v1::{ConfigMap, Pod, Secret},
 };
 
 use crate::{
@@ -54,32 +54,45 @@ impl WithStatus for Network {
 pub struct Stub {
     network: Network,
     pub delete: Option<ExpectPatch<ExpectFile>>,
+    /// Status patch reporting an invalid spec, no other requests are expected when enabled.
+    pub error: (ExpectPatch<ExpectFile>, bool),
     pub namespace: ExpectPatch<ExpectFile>,
     pub status: ExpectPatch<ExpectFile>,
+    /// When false the status is unchanged by reconciliation and is not patched.
+    pub status_patched: bool,
     pub postgres_auth_secret: (ExpectPatch<ExpectFile>, Secret, bool),
     pub ceramic_admin_secret_missing: (ExpectPatch<ExpectFile>, Option<Secret>),
     pub ceramic_admin_secret_source: Option<(ExpectPatch<ExpectFile>, Option<Secret>, bool)>,
     pub ceramic_admin_secret: Option<(ExpectPatch<ExpectFile>, Option<Secret>)>,
+    /// Creation of the admin secret that fails because it already exists,
+    /// e.g. a concurrent reconcile created it first.
+    pub ceramic_admin_secret_conflict: Option<ExpectPatch<ExpectFile>>,
     pub ceramic_deletes: Vec<ExpectPatch<ExpectFile>>,
     pub ceramic_pod_status: Vec<(ExpectPatch<ExpectFile>, Option<Pod>)>,
     pub keramik_peers_configmap: ExpectPatch<ExpectFile>,
+    /// Deletion of the peers shards left over from a larger set of peers.
+    pub keramik_peers_shard_deletes: Vec<ExpectFile>,
     pub ceramics: Vec<CeramicStub>,
     pub cas_service: ExpectPatch<ExpectFile>,
     pub cas_ipfs_service: ExpectPatch<ExpectFile>,
     pub ganache_service: ExpectPatch<ExpectFile>,
     pub cas_postgres_service: ExpectPatch<ExpectFile>,
-    pub localstack_service: ExpectPatch<ExpectFile>,
+    pub localstack_service: Option<ExpectPatch<ExpectFile>>,
     pub cas_stateful_set: ExpectPatch<ExpectFile>,
     pub cas_ipfs_stateful_set: ExpectPatch<ExpectFile>,
     pub ganache_stateful_set: ExpectPatch<ExpectFile>,
     pub cas_postgres_stateful_set: ExpectPatch<ExpectFile>,
-    pub localstack_stateful_set: ExpectPatch<ExpectFile>,
+    pub localstack_stateful_set: Option<ExpectPatch<ExpectFile>>,
     pub bootstrap_job: Vec<(ExpectFile, Option<Job>)>,
 }
 
 #[derive(Debug)]
 pub struct CeramicStub {
     pub configmaps: Vec<ExpectPatch<ExpectFile>>,
+    /// Lookup, and deletion when changed, of an existing init job.
+    pub init_job_reset: Vec<(ExpectFile, Option<Job>)>,
+    /// Account, role, role binding and job of the init job, in order.
+    pub init_job: Vec<ExpectPatch<ExpectFile>>,
     pub stateful_set: ExpectPatch<ExpectFile>,
     pub service: ExpectPatch<ExpectFile>,
 }
@@ -88,9 +101,14 @@ impl Default for Stub {
     fn default() -> Self {
         Self {
             delete: None,
+            error: (
+                expect_file!["./testdata/default_stubs/error_status"].into(),
+                false,
+            ),
             network: Network::test(),
             namespace: expect_file!["./testdata/default_stubs/namespace"].into(),
             status: expect_file!["./testdata/default_stubs/status"].into(),
+            status_patched: true,
             postgres_auth_secret: (
                 expect_file!["./testdata/default_stubs/postgres_auth_secret"].into(),
                 k8s_openapi::api::core::v1::Secret {
@@ -116,6 +134,7 @@ impl Default for Stub {
             ),
             ceramic_admin_secret_source: None,
             ceramic_admin_secret: None,
+            ceramic_admin_secret_conflict: None,
             ceramic_deletes: vec![
                 expect_file!["./testdata/default_stubs/delete_ceramic_ss_1"].into(),
                 expect_file!["./testdata/default_stubs/delete_ceramic_svc_1"].into(),
@@ -141,6 +160,8 @@ impl Default for Stub {
                 configmaps: vec![
                     expect_file!["./testdata/default_stubs/ceramic_init_configmap"].into(),
                 ],
+                init_job_reset: vec![],
+                init_job: vec![],
                 stateful_set: expect_file!["./testdata/default_stubs/ceramic_stateful_set"].into(),
                 service: expect_file!["./testdata/default_stubs/ceramic_service"].into(),
             }],
@@ -148,12 +169,15 @@ impl Default for Stub {
                 "./testdata/default_stubs/keramik_peers_configmap"
             ]
             .into(),
+            keramik_peers_shard_deletes: vec![],
             cas_service: expect_file!["./testdata/default_stubs/cas_service"].into(),
             cas_ipfs_service: expect_file!["./testdata/default_stubs/cas_ipfs_service"].into(),
             ganache_service: expect_file!["./testdata/default_stubs/ganache_service"].into(),
             cas_postgres_service: expect_file!["./testdata/default_stubs/cas_postgres_service"]
                 .into(),
-            localstack_service: expect_file!["./testdata/default_stubs/localstack_service"].into(),
+            localstack_service: Some(
+                expect_file!["./testdata/default_stubs/localstack_service"].into(),
+            ),
             cas_stateful_set: expect_file!["./testdata/default_stubs/cas_stateful_set"].into(),
             cas_ipfs_stateful_set: expect_file!["./testdata/default_stubs/cas_ipfs_stateful_set"]
                 .into(),
@@ -163,10 +187,9 @@ impl Default for Stub {
                 "./testdata/default_stubs/cas_postgres_stateful_set"
             ]
             .into(),
-            localstack_stateful_set: expect_file![
-                "./testdata/default_stubs/localstack_stateful_set"
-            ]
-            .into(),
+            localstack_stateful_set: Some(
+                expect_file!["./testdata/default_stubs/localstack_stateful_set"].into(),
+            ),
             bootstrap_job: vec![],
         }
     }
@@ -193,6 +216,13 @@ impl Stub {
     async fn _run(self, mut fakeserver: ApiServerVerifier) -> Network {
         // We need to handle each expected call in sequence
 
+        if self.error.1 {
+            fakeserver
+                .handle_request_response(self.error.0, Some(&self.network))
+                .await
+                .expect("should be able to report the error");
+            return self.network;
+        }
         if let Some(delete) = self.delete {
             fakeserver
                 .handle_request_response(delete, Some(&self.network))
@@ -230,10 +260,12 @@ impl Stub {
                 .handle_apply(self.cas_postgres_service)
                 .await
                 .expect("cas-postgres service should apply");
-            fakeserver
-                .handle_apply(self.localstack_service)
-                .await
-                .expect("localstack service should apply");
+            if let Some(localstack_service) = self.localstack_service {
+                fakeserver
+                    .handle_apply(localstack_service)
+                    .await
+                    .expect("localstack service should apply");
+            }
             fakeserver
                 .handle_apply(self.cas_stateful_set)
                 .await
@@ -250,10 +282,12 @@ impl Stub {
                 .handle_apply(self.cas_postgres_stateful_set)
                 .await
                 .expect("cas-postgres stateful set should apply");
-            fakeserver
-                .handle_apply(self.localstack_stateful_set)
-                .await
-                .expect("localstack stateful set should apply");
+            if let Some(localstack_stateful_set) = self.localstack_stateful_set {
+                fakeserver
+                    .handle_apply(localstack_stateful_set)
+                    .await
+                    .expect("localstack stateful set should apply");
+            }
         }
         fakeserver
             .handle_request_response(
@@ -278,6 +312,12 @@ impl Stub {
                 .await
                 .expect("ceramic-admin secret should be created");
         }
+        if let Some(conflict) = self.ceramic_admin_secret_conflict {
+            fakeserver
+                .handle_request_error(conflict, 409, "AlreadyExists")
+                .await
+                .expect("ceramic-admin secret should already exist");
+        }
         for ceramic_delete in self.ceramic_deletes {
             fakeserver
                 .handle_request_response(ceramic_delete, None::<&StatefulSet>)
@@ -291,6 +331,18 @@ impl Stub {
                     .await
                     .expect("ceramic configmap should apply");
             }
+            for (req, resp) in c.init_job_reset {
+                fakeserver
+                    .handle_request_response(req, resp.as_ref())
+                    .await
+                    .expect("ceramic init job should be looked up");
+            }
+            for resource in c.init_job {
+                fakeserver
+                    .handle_apply(resource)
+                    .await
+                    .expect("ceramic init job resource should apply");
+            }
             fakeserver
                 .handle_apply(c.service)
                 .await
@@ -310,12 +362,21 @@ impl Stub {
             .handle_apply(self.keramik_peers_configmap)
             .await
             .expect("keramik-peers configmap should apply");
+        for shard_delete in self.keramik_peers_shard_deletes {
+            fakeserver
+                .handle_request_response(shard_delete, None::<&ConfigMap>)
+                .await
+                .expect("keramik-peers shard should delete");
+        }
         for (req, resp) in self.bootstrap_job {
             fakeserver
                 .handle_request_response(req, resp.as_ref())
                 .await
                 .expect("bootstrap job should apply");
         }
+        if !self.status_patched {
+            return self.network;
+        }
         fakeserver
             .handle_patch_status(self.status, self.network.clone())
             .await
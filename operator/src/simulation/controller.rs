@@ -1,14 +1,15 @@
-use std::{sync::Arc, time::Duration};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
-use futures::stream::StreamExt;
+use anyhow::anyhow;
+use futures::stream::{self, StreamExt, TryStreamExt};
 use k8s_openapi::api::{
-    apps::v1::StatefulSet,
+    apps::v1::{Deployment, StatefulSet},
     batch::v1::Job,
     core::v1::{ConfigMap, Namespace, Pod, Service},
 };
 
 use kube::{
-    api::{Patch, PatchParams},
+    api::{ListParams, Patch, PatchParams},
     client::Client,
     core::object::HasSpec,
     runtime::Controller,
@@ -21,32 +22,43 @@ use kube::{
     },
     Resource, ResourceExt,
 };
-use rand::{thread_rng, Rng, RngCore};
+use multiaddr::Multiaddr;
+use rand::{rngs::StdRng, thread_rng, Rng, RngCore, SeedableRng};
 
 use tracing::{debug, error};
 
 use crate::{
     labels::MANAGED_BY_LABEL_SELECTOR,
     simulation::{
-        job::JobImageConfig, manager, manager::ManagerConfig, redis, worker, worker::WorkerConfig,
-        Simulation, SimulationStatus,
+        manager::{self, ManagerConfig},
+        redis,
+        worker::{self, WorkerConfig},
+        CleanupPolicy, ManagerKind, Simulation, SimulationPhase, SimulationSpec, SimulationStatus,
     },
-    utils::Clock,
+    utils::{Clock, ConfigError},
 };
 
-use crate::monitoring::{jaeger, opentelemetry, prometheus};
+use crate::monitoring::{
+    grafana::{self, GrafanaConfig},
+    jaeger, opentelemetry,
+    prometheus::{self, PrometheusConfig},
+};
 
 use crate::network::{
-    ipfs_rpc::{HttpRpcClient, IpfsRpcClient},
-    peers::PEERS_MAP_KEY,
-    Network, PEERS_CONFIG_MAP_NAME,
+    ipfs_rpc::{HttpRpcClient, IpfsRpcClient, RetryPolicy},
+    peers::{
+        peers_shard_key, peers_shard_name, PEERS_COMPRESSED_MAP_KEY, PEERS_MAP_KEY,
+        PEERS_SHARDS_KEY,
+    },
+    Network, NetworkStatus, PEERS_CONFIG_MAP_NAME,
 };
 
-use keramik_common::peer_info::Peer;
+use keramik_common::peer_info::{decompress_peers, Peer};
 
 use crate::utils::{
-    apply_account, apply_cluster_role, apply_cluster_role_binding, apply_config_map, apply_job,
-    apply_service, apply_stateful_set, Context,
+    apply_account, apply_cluster_role, apply_cluster_role_binding, apply_config_map,
+    apply_deployment, apply_job, apply_labeled_config_map, apply_service, apply_stateful_set,
+    delete_job, Context,
 };
 
 /// Handle errors during reconciliation.
@@ -73,11 +85,13 @@ enum Error {
     },
 }
 
-/// Start a controller for the Simulation CRD.
-pub async fn run() {
+/// Start a controller for the Simulation CRD, timing out IPFS RPC requests after rpc_timeout.
+pub async fn run(rpc_timeout: Duration) {
     let k_client = Client::try_default().await.unwrap();
+    let rpc_client = HttpRpcClient::new(rpc_timeout, RetryPolicy::default())
+        .expect("should be able to build IPFS RPC client");
     let context = Arc::new(
-        Context::new(k_client.clone(), HttpRpcClient).expect("should be able to create context"),
+        Context::new(k_client.clone(), rpc_client).expect("should be able to create context"),
     );
 
     // Add api for other resources, ie ceramic nodes
@@ -87,6 +101,7 @@ pub async fn run() {
     let services = Api::<Service>::all(k_client.clone());
     let config_maps = Api::<ConfigMap>::all(k_client.clone());
     let jobs = Api::<Job>::all(k_client.clone());
+    let deployments = Api::<Deployment>::all(k_client.clone());
     let pods = Api::<Pod>::all(k_client.clone());
 
     Controller::new(simulations.clone(), Config::default())
@@ -110,6 +125,10 @@ pub async fn run() {
             jobs,
             watcher::Config::default().labels(MANAGED_BY_LABEL_SELECTOR),
         )
+        .owns(
+            deployments,
+            watcher::Config::default().labels(MANAGED_BY_LABEL_SELECTOR),
+        )
         .owns(
             pods,
             watcher::Config::default().labels(MANAGED_BY_LABEL_SELECTOR),
@@ -128,6 +147,65 @@ pub async fn run() {
         .await;
 }
 
+/// Record the status of the simulation.
+async fn patch_status(
+    cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
+    ns: &str,
+    simulation: Arc<Simulation>,
+    status: &SimulationStatus,
+) -> Result<(), kube::error::Error> {
+    let simulations: Api<Simulation> = Api::namespaced(cx.k_client.clone(), ns);
+    simulations
+        .patch_status(
+            &simulation.name_any(),
+            &PatchParams::default(),
+            &Patch::Merge(serde_json::json!({ "status": status })),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Configs of the components of a valid simulation spec.
+struct SimulationConfigs {
+    manager: ManagerConfig,
+    prometheus: PrometheusConfig,
+    grafana: Option<GrafanaConfig>,
+}
+
+fn validate_spec(spec: &SimulationSpec) -> Result<SimulationConfigs, ConfigError> {
+    Ok(SimulationConfigs {
+        manager: ManagerConfig::try_from(spec)?,
+        prometheus: PrometheusConfig::try_from(spec)?,
+        grafana: spec
+            .grafana
+            .as_ref()
+            .map(GrafanaConfig::try_from)
+            .transpose()?,
+    })
+}
+
+/// Fail the simulation, recording the error in its status.
+async fn fail_simulation(
+    cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
+    ns: &str,
+    simulation: Arc<Simulation>,
+    mut status: SimulationStatus,
+    err: anyhow::Error,
+) -> Result<Action, Error> {
+    status.phase = SimulationPhase::Failed;
+    status.error = Some(err.to_string());
+    patch_status(cx, ns, simulation, &status).await?;
+    Err(Error::App { source: err })
+}
+
+/// Nonce of a new simulation, derived from the seed of the simulation when set.
+fn initial_nonce(seed: Option<u64>) -> u32 {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed).gen(),
+        None => thread_rng().gen(),
+    }
+}
+
 /// Perform a reconile pass for the Simulation CRD
 async fn reconcile(
     simulation: Arc<Simulation>,
@@ -136,26 +214,98 @@ async fn reconcile(
     let spec = simulation.spec();
     debug!(?spec, "reconcile");
 
-    let status = if let Some(status) = &simulation.status {
+    let mut status = if let Some(status) = &simulation.status {
         status.clone()
     } else {
         // Generate new status with random nonce
         SimulationStatus {
-            nonce: thread_rng().gen(),
+            nonce: initial_nonce(spec.seed),
+            ..Default::default()
         }
     };
-
     let ns = simulation.namespace().unwrap();
-    let num_peers = get_num_peers(cx.clone(), &ns).await?;
+    if finished(status.phase) {
+        // The jobs are only deleted once the finished phase is persisted,
+        // otherwise a later pass would apply them again and rerun the simulation.
+        if cleanup_jobs(spec, status.phase) {
+            debug!(phase = ?status.phase, "deleting simulation jobs");
+            delete_job(cx.clone(), &ns, MANAGER_JOB_NAME).await?;
+            for i in 0..status.total_workers {
+                delete_job(cx.clone(), &ns, &format!("{WORKER_JOB_NAME}-{i}")).await?;
+            }
+        }
+        debug!(phase = ?status.phase, "simulation finished");
+        return Ok(Action::await_change());
+    }
+
+    // An invalid spec fails the simulation before any resources are applied
+    let SimulationConfigs {
+        manager: mut manager_config,
+        prometheus: prometheus_config,
+        grafana: grafana_config,
+    } = match validate_spec(spec) {
+        Ok(configs) => configs,
+        Err(err) => {
+            return fail_simulation(cx, &ns, simulation.clone(), status, err.into()).await;
+        }
+    };
+    manager_config.nonce = status.nonce;
+    let validate = spec.validate_peers.unwrap_or(false);
+    let peers = match get_peers(cx.clone(), &ns).await {
+        Ok(peers) => peers,
+        Err(Error::App { source }) if validate => {
+            return fail_simulation(cx, &ns, simulation.clone(), status, source).await;
+        }
+        Err(err) => return Err(err),
+    };
+    if validate {
+        if let Err(err) = validate_peers(&peers) {
+            return fail_simulation(cx, &ns, simulation.clone(), status, err).await;
+        }
+    }
+    let num_peers = target_peers(&peers, spec.target_spec.as_deref()).len() as u32;
+    debug!(num_peers, "target peers");
+    // A network that is still starting has no ceramic peers yet, so wait for them
+    if validate && num_peers == 0 {
+        debug!("waiting for ceramic peers to simulate against");
+        return Ok(Action::requeue(Duration::from_secs(10)));
+    }
 
-    apply_jaeger(cx.clone(), &ns, simulation.clone()).await?;
-    apply_prometheus(cx.clone(), &ns, simulation.clone()).await?;
-    apply_opentelemetry(cx.clone(), &ns, simulation.clone()).await?;
+    let collector_selector = spec
+        .monitoring
+        .as_ref()
+        .and_then(|monitoring| monitoring.collector_selector.as_ref());
+    let collector_endpoint = if let Some(selector) = collector_selector {
+        let Some(endpoint) = discover_collector(cx.clone(), selector).await? else {
+            debug!(?selector, "waiting for the collector to be discovered");
+            return Ok(Action::requeue(Duration::from_secs(10)));
+        };
+        Some(endpoint)
+    } else {
+        apply_jaeger(cx.clone(), &ns, simulation.clone()).await?;
+        apply_prometheus(cx.clone(), &ns, simulation.clone(), &prometheus_config).await?;
+        apply_opentelemetry(cx.clone(), &ns, simulation.clone()).await?;
+        None
+    };
+    if let Some(grafana_config) = grafana_config {
+        apply_grafana(cx.clone(), &ns, simulation.clone(), grafana_config).await?;
+    }
+    if spec
+        .monitoring
+        .as_ref()
+        .and_then(|monitoring| monitoring.emit_grafana_dashboard)
+        .unwrap_or(false)
+    {
+        apply_grafana_dashboard(cx.clone(), &ns, simulation.clone()).await?;
+    }
 
-    let ready = monitoring_ready(cx.clone(), &ns).await?;
+    // A discovered collector is part of a monitoring stack that is already running
+    if collector_endpoint.is_none() {
+        let ready = monitoring_ready(cx.clone(), &ns, spec.grafana.is_some()).await?;
 
-    if !ready {
-        return Ok(Action::requeue(Duration::from_secs(10)));
+        if !ready {
+            return Ok(Action::requeue(Duration::from_secs(10)));
+        }
     }
 
     apply_redis(cx.clone(), &ns, simulation.clone()).await?;
@@ -164,69 +314,123 @@ async fn reconcile(
         return Ok(Action::requeue(Duration::from_secs(10)));
     }
 
-    let job_image_config = JobImageConfig::from(spec);
+    if spec.wait_for_bootstrap.unwrap_or(false) && !network_mesh_ready(cx.clone(), &ns).await? {
+        debug!("waiting for the network to bootstrap");
+        return Ok(Action::requeue(Duration::from_secs(10)));
+    }
 
-    let manager_config = ManagerConfig {
-        scenario: spec.scenario.to_owned(),
-        users: spec.users.to_owned(),
-        run_time: spec.run_time.to_owned(),
-        nonce: status.nonce,
-        job_image_config: job_image_config.clone(),
-        throttle_requests: spec.throttle_requests,
-    };
+    manager_config.otlp_endpoint = spec.otlp_endpoint.clone().or(collector_endpoint);
 
-    apply_manager(cx.clone(), &ns, simulation.clone(), manager_config).await?;
+    let manager_deployment = spec.manager_kind.unwrap_or_default() == ManagerKind::Deployment;
+    apply_manager(
+        cx.clone(),
+        &ns,
+        simulation.clone(),
+        manager_config.clone(),
+        manager_deployment,
+    )
+    .await?;
 
-    let jobs: Api<Job> = Api::namespaced(cx.k_client.clone(), &ns);
-    let manager_job = jobs.get_status(MANAGER_JOB_NAME).await?;
-    let manager_ready = manager_job.status.unwrap().ready.unwrap_or_default();
+    // A manager deployment keeps running, so the simulation never has an outcome
+    let (manager_ready, manager_outcome) = if manager_deployment {
+        let deployments: Api<Deployment> = Api::namespaced(cx.k_client.clone(), &ns);
+        let manager = deployments.get_status(MANAGER_JOB_NAME).await?;
+        (
+            manager
+                .status
+                .and_then(|status| status.ready_replicas)
+                .unwrap_or_default(),
+            None,
+        )
+    } else {
+        let jobs: Api<Job> = Api::namespaced(cx.k_client.clone(), &ns);
+        let manager_job = jobs.get_status(MANAGER_JOB_NAME).await?;
+        let manager_outcome = job_outcome(&manager_job);
+        (
+            manager_job.status.unwrap().ready.unwrap_or_default(),
+            manager_outcome,
+        )
+    };
 
-    if manager_ready > 0 {
+    let workers = if manager_ready > 0 {
         //for loop n peers
         apply_n_workers(
             cx.clone(),
             &ns,
             num_peers,
-            status.nonce,
             simulation.clone(),
-            job_image_config.clone(),
+            &manager_config,
         )
-        .await?;
+        .await?
+    } else {
+        list_workers(cx.clone(), &ns, num_peers).await?.0
+    };
+    status.total_workers = num_peers;
+    status.completed_workers = workers
+        .iter()
+        .filter(|job| job_outcome(job) == Some(SimulationPhase::Succeeded))
+        .count() as u32;
+
+    let outcome = manager_outcome.and_then(|manager_outcome| {
+        workers_outcome(&workers).map(|workers_outcome| {
+            if manager_outcome == SimulationPhase::Failed {
+                manager_outcome
+            } else {
+                workers_outcome
+            }
+        })
+    });
+    if let Some(outcome) = outcome {
+        status.phase = outcome;
+    } else if manager_ready > 0 {
+        status.phase = SimulationPhase::Running;
     }
 
-    let simulations: Api<Simulation> = Api::namespaced(cx.k_client.clone(), &ns);
-    let _patched = simulations
-        .patch_status(
-            &simulation.name_any(),
-            &PatchParams::default(),
-            &Patch::Merge(serde_json::json!({ "status": status })),
-        )
-        .await?;
+    patch_status(cx.clone(), &ns, simulation.clone(), &status).await?;
 
-    //TODO jobs done/fail cleanup, post process
+    if finished(status.phase) {
+        debug!(phase = ?status.phase, "simulation finished");
+        if cleanup_jobs(spec, status.phase) {
+            // Delete the jobs on the next pass, which sees the persisted finished phase
+            return Ok(Action::requeue(Duration::from_secs(10)));
+        }
+        return Ok(Action::await_change());
+    }
 
     Ok(Action::requeue(Duration::from_secs(10)))
 }
 
 pub const MANAGER_SERVICE_NAME: &str = "goose";
+pub const MANAGER_HOSTNAME: &str = "manager";
 pub const MANAGER_JOB_NAME: &str = "simulate-manager";
 pub const WORKER_JOB_NAME: &str = "simulate-worker";
+/// Maximum number of worker jobs applied at the same time.
+const WORKER_APPLY_CONCURRENCY: usize = 16;
 
 pub const JAEGER_SERVICE_NAME: &str = "jaeger";
 pub const OTEL_SERVICE_NAME: &str = "otel";
+/// Standard port of the OTLP gRPC receiver of a collector.
+pub const OTLP_GRPC_PORT: i32 = 4317;
 
 pub const OTEL_CR_BINDING: &str = "monitoring-cluster-role-binding";
 pub const OTEL_CR: &str = "monitoring-cluster-role";
 pub const OTEL_ACCOUNT: &str = "monitoring-service-account";
 
+pub const PROM_SERVICE_NAME: &str = "prometheus";
+pub const GRAFANA_SERVICE_NAME: &str = "grafana";
+
 pub const OTEL_CONFIG_MAP_NAME: &str = "otel-config";
 pub const PROM_CONFIG_MAP_NAME: &str = "prom-config";
+pub const GRAFANA_CONFIG_MAP_NAME: &str = "grafana-config";
+pub const GRAFANA_DASHBOARDS_CONFIG_MAP_NAME: &str = "grafana-dashboards";
+pub const GRAFANA_DASHBOARD_CONFIG_MAP_NAME: &str = "keramik-grafana-dashboard";
 
 async fn apply_manager(
     cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
     ns: &str,
     simulation: Arc<Simulation>,
     config: ManagerConfig,
+    deployment: bool,
 ) -> Result<(), kube::error::Error> {
     let orefs = simulation
         .controller_owner_ref(&())
@@ -241,34 +445,111 @@ async fn apply_manager(
         manager::service_spec(),
     )
     .await?;
-    apply_job(
-        cx.clone(),
-        ns,
-        orefs.clone(),
-        MANAGER_JOB_NAME,
-        manager::manager_job_spec(config),
-    )
-    .await?;
+    if deployment {
+        apply_deployment(
+            cx.clone(),
+            ns,
+            orefs.clone(),
+            MANAGER_JOB_NAME,
+            manager::manager_deployment_spec(config),
+        )
+        .await?;
+    } else {
+        apply_job(
+            cx.clone(),
+            ns,
+            orefs.clone(),
+            MANAGER_JOB_NAME,
+            manager::manager_job_spec(config),
+        )
+        .await?;
+    }
 
     Ok(())
 }
 
-async fn get_num_peers(
+/// Read the peers of the network deployed in the namespace,
+/// reassembling them when they are compressed or sharded across config maps.
+async fn get_peers(
     cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
     ns: &str,
-) -> Result<u32, kube::error::Error> {
+) -> Result<Vec<Peer>, Error> {
     let config_maps: Api<ConfigMap> = Api::namespaced(cx.k_client.clone(), ns);
     let map = config_maps.get(PEERS_CONFIG_MAP_NAME).await?;
-    let data = map.data.unwrap();
-    let value = data.get(PEERS_MAP_KEY).unwrap();
-    let peers: Vec<Peer> = serde_json::from_str::<Vec<Peer>>(value)
-        .unwrap()
-        .into_iter()
+    let data = map.data.unwrap_or_default();
+    if let Some(compressed) = data.get(PEERS_COMPRESSED_MAP_KEY) {
+        return Ok(decompress_peers(compressed)?);
+    }
+    let mut values = vec![data
+        .get(PEERS_MAP_KEY)
+        .ok_or_else(|| anyhow!("{PEERS_CONFIG_MAP_NAME} has no {PEERS_MAP_KEY}"))?
+        .to_owned()];
+    // Reassemble the peers when they are sharded across config maps
+    let shards: usize = match data.get(PEERS_SHARDS_KEY) {
+        Some(shards) => shards
+            .parse()
+            .map_err(|err| anyhow!("invalid {PEERS_SHARDS_KEY} {shards}: {err}"))?,
+        None => 1,
+    };
+    for shard in 1..shards {
+        let name = peers_shard_name(shard);
+        let key = peers_shard_key(shard);
+        let map = config_maps.get(&name).await?;
+        let value = map
+            .data
+            .and_then(|mut data| data.remove(&key))
+            .ok_or_else(|| anyhow!("{name} has no {key}"))?;
+        values.push(value);
+    }
+    let mut peers = Vec::new();
+    for value in values {
+        peers.extend(
+            serde_json::from_str::<Vec<Peer>>(&value)
+                .map_err(|err| anyhow!("malformed {PEERS_MAP_KEY}: {err}"))?,
+        );
+    }
+    Ok(peers)
+}
+
+/// Ceramic peers targeted by the simulation.
+fn target_peers<'a>(peers: &'a [Peer], target_spec: Option<&'a str>) -> Vec<&'a Peer> {
+    peers
+        .iter()
         .filter(|peer| matches!(peer, Peer::Ceramic(_)))
-        .collect();
+        .filter(|peer| {
+            target_spec.map_or(true, |target_spec| peer.spec_name() == Some(target_spec))
+        })
+        .collect()
+}
 
-    debug!(peers = peers.len(), "get_num_peers");
-    Ok(peers.len() as u32)
+/// Check that every peer is well formed, so the workers do not fail deep into the run.
+fn validate_peers(peers: &[Peer]) -> Result<(), anyhow::Error> {
+    for peer in peers {
+        if peer.id().is_empty() {
+            return Err(anyhow!("peer has an empty peer id"));
+        }
+        let mut addrs = vec![("ipfs rpc", peer.ipfs_rpc_addr())];
+        if let Peer::Ceramic(info) = peer {
+            addrs.push(("ceramic", info.ceramic_addr.as_str()));
+        }
+        for (kind, addr) in addrs {
+            reqwest::Url::parse(addr).map_err(|err| {
+                anyhow!(
+                    "peer {} has an invalid {kind} address {addr}: {err}",
+                    peer.id()
+                )
+            })?;
+        }
+        for addr in peer.p2p_addrs() {
+            addr.parse::<Multiaddr>().map_err(|err| {
+                anyhow!(
+                    "peer {} has an invalid p2p address {addr}: {err}",
+                    peer.id()
+                )
+            })?;
+        }
+    }
+    Ok(())
 }
 
 async fn redis_ready(
@@ -286,9 +567,78 @@ async fn redis_ready(
     Ok(redis_ready)
 }
 
+// Reports if the network deployed in the namespace has formed its mesh.
+async fn network_mesh_ready(
+    cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
+    ns: &str,
+) -> Result<bool, kube::error::Error> {
+    let networks: Api<Network> = Api::all(cx.k_client.clone());
+    let ready = networks
+        .list(&ListParams::default())
+        .await?
+        .into_iter()
+        .filter_map(|network| network.status)
+        .find(|status| status.namespace.as_deref() == Some(ns))
+        .is_some_and(|status| mesh_ready(&status));
+    Ok(ready)
+}
+
+// Reports if all peers of the network are ready and, when there are several, each ceramic peer
+// is connected to at least one other peer.
+fn mesh_ready(status: &NetworkStatus) -> bool {
+    status.replicas > 0
+        && status.ready_replicas == status.replicas
+        && (status.ready_replicas < 2
+            || status
+                .peers
+                .iter()
+                .filter(|peer| matches!(peer, Peer::Ceramic(_)))
+                .all(|peer| peer.connected_peers().unwrap_or_default() > 0))
+}
+
+/// OTLP endpoint of the first collector service with the labels, in any namespace.
+/// The standard OTLP gRPC port is used when the service exposes it, its first port otherwise.
+async fn discover_collector(
+    cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
+    selector: &BTreeMap<String, String>,
+) -> Result<Option<String>, kube::error::Error> {
+    let services: Api<Service> = Api::all(cx.k_client.clone());
+    let labels = selector
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(",");
+    let endpoint = services
+        .list(&ListParams::default().labels(&labels))
+        .await?
+        .into_iter()
+        .next()
+        .map(|service| {
+            let ports = service
+                .spec
+                .as_ref()
+                .and_then(|spec| spec.ports.as_ref())
+                .map(|ports| ports.iter().map(|port| port.port).collect::<Vec<_>>())
+                .unwrap_or_default();
+            let port = if ports.contains(&OTLP_GRPC_PORT) {
+                OTLP_GRPC_PORT
+            } else {
+                ports.first().copied().unwrap_or(OTLP_GRPC_PORT)
+            };
+            format!(
+                "http://{}.{}:{port}",
+                service.name_any(),
+                service.namespace().unwrap_or_default()
+            )
+        });
+    Ok(endpoint)
+}
+
+/// Reports if the monitoring stack is ready, including Grafana when it is deployed.
 async fn monitoring_ready(
     cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
     ns: &str,
+    grafana: bool,
 ) -> Result<bool, kube::error::Error> {
     let stateful_sets: Api<StatefulSet> = Api::namespaced(cx.k_client.clone(), ns);
     let jaeger = stateful_sets.get_status("jaeger").await?;
@@ -308,42 +658,166 @@ async fn monitoring_ready(
         .map(|status| status.ready_replicas.unwrap_or_default() > 0)
         .unwrap_or_default();
 
-    Ok(jaeger_ready && prom_ready && otel_ready)
+    let grafana_ready = if grafana {
+        stateful_sets
+            .get_status("grafana")
+            .await?
+            .status
+            .map(|status| status.ready_replicas.unwrap_or_default() > 0)
+            .unwrap_or_default()
+    } else {
+        true
+    };
+
+    Ok(jaeger_ready && prom_ready && otel_ready && grafana_ready)
 }
 
 async fn apply_n_workers(
     cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
     ns: &str,
     peers: u32,
-    nonce: u32,
     simulation: Arc<Simulation>,
-    job_image_config: JobImageConfig,
-) -> Result<(), kube::error::Error> {
+    manager_config: &ManagerConfig,
+) -> Result<Vec<Job>, kube::error::Error> {
     let spec = simulation.spec();
     let orefs = simulation
         .controller_owner_ref(&())
         .map(|oref| vec![oref])
         .unwrap_or_default();
 
-    for i in 0..peers {
-        let config = WorkerConfig {
-            scenario: spec.scenario.to_owned(),
-            target_peer: i,
-            nonce,
-            job_image_config: job_image_config.clone(),
-        };
+    // Apply the workers concurrently so large networks do not stall the reconcile, stopping at
+    // the first error.
+    stream::iter(0..peers)
+        .map(|i| {
+            let config = WorkerConfig {
+                scenario: spec.scenario.to_owned(),
+                target_peer: i,
+                users: spec
+                    .worker_users
+                    .as_ref()
+                    .and_then(|worker_users| worker_users.get(&i))
+                    .copied()
+                    .unwrap_or(spec.users / peers),
+                nonce: manager_config.nonce,
+                manager_host: manager_host(ns),
+                job_image_config: manager_config.job_image_config.clone(),
+                job_retention_config: manager_config.job_retention_config,
+                job_deadline_config: manager_config.job_deadline_config,
+                resource_limits: manager_config.resource_limits.clone(),
+                seed_documents: spec.seed_documents,
+                seed_models: spec.seed_models.clone(),
+                target_spec: spec.target_spec.clone(),
+                catalog_size: spec.catalog_size,
+                catalog_config_map: spec.catalog_config_map.clone(),
+                large_model_size: spec.large_model_size,
+                read_ratio: spec.read_ratio,
+                did: spec.did.clone(),
+                seed: spec.seed,
+                wait_time_min: spec.wait_time_min,
+                wait_time_max: spec.wait_time_max,
+                think_time: spec.think_time.clone(),
+                think_time_shape: spec.think_time_shape,
+                tag_peer: spec.tag_peer.unwrap_or(false),
+                revision: spec.revision.clone(),
+                otlp_endpoint: manager_config.otlp_endpoint.clone(),
+            };
+            let cx = cx.clone();
+            let orefs = orefs.clone();
+            async move {
+                apply_job(
+                    cx,
+                    ns,
+                    orefs,
+                    &(WORKER_JOB_NAME.to_owned() + "-" + &i.to_string()),
+                    worker::worker_job_spec(config),
+                )
+                .await
+            }
+        })
+        .buffer_unordered(WORKER_APPLY_CONCURRENCY)
+        .try_collect::<Vec<_>>()
+        .await?;
 
-        apply_job(
-            cx.clone(),
-            ns,
-            orefs.clone(),
-            &(WORKER_JOB_NAME.to_owned() + "-" + &i.to_string()),
-            worker::worker_job_spec(config),
-        )
+    // Remove any workers left over from a previous pass that targeted more peers.
+    let (workers, stale) = list_workers(cx.clone(), ns, peers).await?;
+    for job in stale {
+        let name = job.name_any();
+        debug!(name, "deleting stale worker job");
+        delete_job(cx.clone(), ns, &name).await?;
+    }
+
+    Ok(workers)
+}
+
+// Worker jobs of the simulation, split into the workers of the peers and stale workers of peers
+// targeted by a previous pass.
+async fn list_workers(
+    cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
+    ns: &str,
+    peers: u32,
+) -> Result<(Vec<Job>, Vec<Job>), kube::error::Error> {
+    let jobs: Api<Job> = Api::namespaced(cx.k_client.clone(), ns);
+    let existing = jobs
+        .list(&ListParams::default().labels(MANAGED_BY_LABEL_SELECTOR))
         .await?;
+    Ok(existing
+        .into_iter()
+        .filter(|job| worker_index(&job.name_any()).is_some())
+        .partition(|job| worker_index(&job.name_any()).is_some_and(|i| i < peers)))
+}
+
+// Phase of a job once it has completed or failed.
+fn job_outcome(job: &Job) -> Option<SimulationPhase> {
+    job.status
+        .as_ref()?
+        .conditions
+        .as_ref()?
+        .iter()
+        .filter(|condition| condition.status == "True")
+        .find_map(|condition| match condition.type_.as_str() {
+            "Complete" => Some(SimulationPhase::Succeeded),
+            "Failed" => Some(SimulationPhase::Failed),
+            _ => None,
+        })
+}
+
+// Phase of the worker jobs once all of them are done, failed when any of them failed.
+fn workers_outcome(workers: &[Job]) -> Option<SimulationPhase> {
+    let mut outcome = SimulationPhase::Succeeded;
+    for job in workers {
+        if job_outcome(job)? == SimulationPhase::Failed {
+            outcome = SimulationPhase::Failed;
+        }
+    }
+    Some(outcome)
+}
+
+// Reports if the simulation is done and no longer needs to be reconciled.
+fn finished(phase: SimulationPhase) -> bool {
+    matches!(phase, SimulationPhase::Succeeded | SimulationPhase::Failed)
+}
+
+/// Whether the cleanup policy deletes the jobs of a simulation finished with the phase.
+fn cleanup_jobs(spec: &SimulationSpec, phase: SimulationPhase) -> bool {
+    match spec.cleanup_policy.unwrap_or_default() {
+        CleanupPolicy::Never => false,
+        CleanupPolicy::OnSuccess => phase == SimulationPhase::Succeeded,
+        CleanupPolicy::Always => true,
     }
+}
 
-    Ok(())
+// Fully qualified host name of the manager pod of the simulation in the namespace, so workers
+// do not depend on the DNS search path to reach it.
+fn manager_host(ns: &str) -> String {
+    format!("{MANAGER_HOSTNAME}.{MANAGER_SERVICE_NAME}.{ns}.svc.cluster.local")
+}
+
+// Parse the peer index from a worker job name.
+fn worker_index(name: &str) -> Option<u32> {
+    name.strip_prefix(WORKER_JOB_NAME)?
+        .strip_prefix('-')?
+        .parse()
+        .ok()
 }
 
 async fn apply_redis(
@@ -410,6 +884,7 @@ async fn apply_prometheus(
     cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
     ns: &str,
     simulation: Arc<Simulation>,
+    config: &PrometheusConfig,
 ) -> Result<(), kube::error::Error> {
     let orefs = simulation
         .controller_owner_ref(&())
@@ -421,7 +896,7 @@ async fn apply_prometheus(
         ns,
         orefs.clone(),
         PROM_CONFIG_MAP_NAME,
-        prometheus::config_map_data(),
+        prometheus::config_map_data(config),
     )
     .await?;
     apply_stateful_set(
@@ -429,7 +904,7 @@ async fn apply_prometheus(
         ns,
         orefs.clone(),
         "prometheus",
-        prometheus::stateful_set_spec(),
+        prometheus::stateful_set_spec(config),
     )
     .await?;
     Ok(())
@@ -444,6 +919,7 @@ async fn apply_opentelemetry(
         .controller_owner_ref(&())
         .map(|oref| vec![oref])
         .unwrap_or_default();
+    let open_metrics = simulation.spec().open_metrics.unwrap_or(false);
 
     apply_account(cx.clone(), ns, orefs.clone(), OTEL_ACCOUNT).await?;
     apply_cluster_role(
@@ -466,7 +942,7 @@ async fn apply_opentelemetry(
         ns,
         orefs.clone(),
         OTEL_CONFIG_MAP_NAME,
-        opentelemetry::config_map_data(),
+        opentelemetry::config_map_data(open_metrics),
     )
     .await?;
     apply_service(
@@ -488,25 +964,146 @@ async fn apply_opentelemetry(
 
     Ok(())
 }
+
+async fn apply_grafana(
+    cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
+    ns: &str,
+    simulation: Arc<Simulation>,
+    config: GrafanaConfig,
+) -> Result<(), kube::error::Error> {
+    let orefs = simulation
+        .controller_owner_ref(&())
+        .map(|oref| vec![oref])
+        .unwrap_or_default();
+
+    // Grafana reaches Prometheus through its service
+    apply_service(
+        cx.clone(),
+        ns,
+        orefs.clone(),
+        PROM_SERVICE_NAME,
+        prometheus::service_spec(),
+    )
+    .await?;
+    apply_config_map(
+        cx.clone(),
+        ns,
+        orefs.clone(),
+        GRAFANA_CONFIG_MAP_NAME,
+        grafana::config_map_data(),
+    )
+    .await?;
+    apply_config_map(
+        cx.clone(),
+        ns,
+        orefs.clone(),
+        GRAFANA_DASHBOARDS_CONFIG_MAP_NAME,
+        grafana::dashboards_config_map_data(),
+    )
+    .await?;
+    apply_service(
+        cx.clone(),
+        ns,
+        orefs.clone(),
+        GRAFANA_SERVICE_NAME,
+        grafana::service_spec(),
+    )
+    .await?;
+    apply_stateful_set(
+        cx.clone(),
+        ns,
+        orefs.clone(),
+        "grafana",
+        grafana::stateful_set_spec(&config),
+    )
+    .await?;
+
+    Ok(())
+}
+/// Apply the dashboards as a config map the Grafana sidecar of kube-prometheus-stack imports.
+async fn apply_grafana_dashboard(
+    cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
+    ns: &str,
+    simulation: Arc<Simulation>,
+) -> Result<(), kube::error::Error> {
+    let orefs = simulation
+        .controller_owner_ref(&())
+        .map(|oref| vec![oref])
+        .unwrap_or_default();
+
+    apply_labeled_config_map(
+        cx,
+        ns,
+        orefs,
+        GRAFANA_DASHBOARD_CONFIG_MAP_NAME,
+        grafana::dashboard_labels(),
+        grafana::dashboards_config_map_data(),
+    )
+    .await?;
+
+    Ok(())
+}
+
 // Stub tests relying on stub.rs and its apiserver stubs
 #[cfg(test)]
 mod tests {
-    use super::{reconcile, Simulation};
+    use super::{initial_nonce, manager_host, mesh_ready, reconcile, Action, Simulation};
 
     use crate::{
-        network::ipfs_rpc::tests::MockIpfsRpcClientTest,
-        simulation::{stub::Stub, SimulationSpec},
-        utils::{test::ApiServerVerifier, Context},
+        network::{
+            ipfs_rpc::tests::MockIpfsRpcClientTest, Network, NetworkStatus, ResourceLimitsSpec,
+            SecretKeySpec,
+        },
+        simulation::{
+            stub::Stub, CleanupPolicy, DidSpec, GrafanaSpec, ManagerKind, MonitoringSpec,
+            SimulationPhase, SimulationSpec, SimulationStatus,
+        },
+        utils::{
+            test::{ApiServerVerifier, WithStatus},
+            Context,
+        },
     };
 
     use crate::utils::test::timeout_after_1s;
 
     use expect_test::{expect, expect_file};
-    use k8s_openapi::api::core::v1::ConfigMap;
+    use k8s_openapi::{
+        api::{
+            apps::v1::{Deployment, DeploymentStatus, StatefulSet, StatefulSetStatus},
+            batch::v1::{Job, JobCondition, JobStatus},
+            core::v1::{ConfigMap, Service, ServiceList, ServicePort, ServiceSpec},
+        },
+        apimachinery::pkg::api::resource::Quantity,
+    };
     use keramik_common::peer_info::{CeramicPeerInfo, Peer};
-    use std::{collections::BTreeMap, sync::Arc};
+    use kube::{core::ObjectMeta, Resource};
+    use std::{collections::BTreeMap, sync::Arc, time::Duration};
     use tracing_test::traced_test;
 
+    fn completed_job(name: &str) -> Job {
+        finished_job(name, "Complete")
+    }
+
+    fn failed_job(name: &str) -> Job {
+        finished_job(name, "Failed")
+    }
+
+    fn finished_job(name: &str, condition: &str) -> Job {
+        let mut job = Job {
+            status: Some(JobStatus {
+                conditions: Some(vec![JobCondition {
+                    type_: condition.to_owned(),
+                    status: "True".to_owned(),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        job.meta_mut().name = Some(name.to_owned());
+        job
+    }
+
     // This tests defines the default stubs,
     // meaning the default stubs are the request response pairs
     // that occur when reconiling a default spec and status.
@@ -525,34 +1122,147 @@ mod tests {
         timeout_after_1s(mocksrv).await;
     }
 
+    fn network_status(connected_peers: &[i32]) -> NetworkStatus {
+        let peers: Vec<Peer> = connected_peers
+            .iter()
+            .enumerate()
+            .map(|(i, connected_peers)| {
+                Peer::Ceramic(CeramicPeerInfo {
+                    peer_id: i.to_string(),
+                    connected_peers: Some(*connected_peers),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        NetworkStatus {
+            replicas: peers.len() as i32,
+            ready_replicas: peers.len() as i32,
+            namespace: Some("test".to_owned()),
+            peers,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn mesh_ready_when_peers_connected() {
+        assert!(mesh_ready(&network_status(&[1, 2])));
+        assert!(!mesh_ready(&network_status(&[1, 0])));
+        // A single peer has nothing to connect to
+        assert!(mesh_ready(&network_status(&[0])));
+        assert!(!mesh_ready(&network_status(&[])));
+    }
+
+    #[test]
+    fn mesh_not_ready_with_missing_peers() {
+        let status = NetworkStatus {
+            replicas: 3,
+            ..network_status(&[1, 1])
+        };
+        assert!(!mesh_ready(&status));
+    }
+
+    #[test]
+    fn mesh_not_ready_with_unknown_connections() {
+        let mut status = network_status(&[1, 1]);
+        status.peers[1].set_connected_peers(None);
+        assert!(!mesh_ready(&status));
+    }
+
     #[tokio::test]
     #[traced_test]
-    async fn reconcile_scenario() {
+    async fn reconcile_wait_for_bootstrap() {
         let mock_rpc_client = MockIpfsRpcClientTest::new();
         let (testctx, api_handle) = Context::test(mock_rpc_client);
         let fakeserver = ApiServerVerifier::new(api_handle);
         let simulation = Simulation::test().with_spec(SimulationSpec {
-            scenario: "test-scenario".to_owned(),
+            wait_for_bootstrap: Some(true),
             ..Default::default()
         });
         let mut stub = Stub::default();
-        stub.manager_job.patch(expect![[r#"
-            --- original
-            +++ modified
-            @@ -41,7 +41,7 @@
-                               },
-                               {
-                                 "name": "SIMULATE_SCENARIO",
-            -                    "value": ""
-            +                    "value": "test-scenario"
-                               },
-                               {
-                                 "name": "SIMULATE_MANAGER",
-        "#]]);
-        stub.worker_jobs[0].patch(expect![[r#"
+        // The peers are not connected yet so no jobs are applied
+        stub.networks = Some((
+            expect_file!["./testdata/default_stubs/networks_list"].into(),
+            vec![Network::test().with_status(network_status(&[1, 0]))],
+        ));
+        stub.mesh_ready = false;
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_wait_for_bootstrap_mesh_ready() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            wait_for_bootstrap: Some(true),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.networks = Some((
+            expect_file!["./testdata/default_stubs/networks_list"].into(),
+            vec![
+                // Networks in other namespaces are ignored
+                Network::test().with_status(NetworkStatus {
+                    namespace: Some("other".to_owned()),
+                    ..network_status(&[0, 0])
+                }),
+                Network::test().with_status(network_status(&[1, 1])),
+            ],
+        ));
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[test]
+    fn manager_host_scoped_to_namespace() {
+        assert_eq!(
+            "manager.goose.keramik-small.svc.cluster.local",
+            manager_host("keramik-small")
+        );
+    }
+
+    #[test]
+    fn seeded_nonce_is_deterministic() {
+        assert_eq!(initial_nonce(Some(1234)), initial_nonce(Some(1234)));
+        assert_ne!(initial_nonce(Some(1234)), initial_nonce(Some(4321)));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_scenario() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            scenario: "test-scenario".to_owned(),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -42,7 +42,7 @@
+                               },
+                               {
+                                 "name": "SIMULATE_SCENARIO",
+            -                    "value": ""
+            +                    "value": "test-scenario"
+                               },
+                               {
+                                 "name": "SIMULATE_MANAGER",
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
             --- original
             +++ modified
-            @@ -49,7 +49,7 @@
+            @@ -50,7 +50,7 @@
                                },
                                {
                                  "name": "SIMULATE_SCENARIO",
@@ -565,7 +1275,7 @@ mod tests {
         stub.worker_jobs[1].patch(expect![[r#"
             --- original
             +++ modified
-            @@ -49,7 +49,7 @@
+            @@ -50,7 +50,7 @@
                                },
                                {
                                  "name": "SIMULATE_SCENARIO",
@@ -595,7 +1305,65 @@ mod tests {
         stub.manager_job.patch(expect![[r#"
             --- original
             +++ modified
-            @@ -61,7 +61,7 @@
+            @@ -62,7 +62,7 @@
+                               },
+                               {
+                                 "name": "SIMULATE_USERS",
+            -                    "value": "0"
+            +                    "value": "10"
+                               },
+                               {
+                                 "name": "SIMULATE_RUN_TIME",
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -58,7 +58,7 @@
+                               },
+                               {
+                                 "name": "SIMULATE_USERS",
+            -                    "value": "0"
+            +                    "value": "5"
+                               },
+                               {
+                                 "name": "SIMULATE_PEERS_PATH",
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -58,7 +58,7 @@
+                               },
+                               {
+                                 "name": "SIMULATE_USERS",
+            -                    "value": "0"
+            +                    "value": "5"
+                               },
+                               {
+                                 "name": "SIMULATE_PEERS_PATH",
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_worker_users() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            users: 10,
+            // Hammer the first peer
+            worker_users: Some(BTreeMap::from_iter([(0, 40)])),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -62,7 +62,7 @@
                                },
                                {
                                  "name": "SIMULATE_USERS",
@@ -605,6 +1373,32 @@ mod tests {
                                {
                                  "name": "SIMULATE_RUN_TIME",
         "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -58,7 +58,7 @@
+                               },
+                               {
+                                 "name": "SIMULATE_USERS",
+            -                    "value": "0"
+            +                    "value": "40"
+                               },
+                               {
+                                 "name": "SIMULATE_PEERS_PATH",
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -58,7 +58,7 @@
+                               },
+                               {
+                                 "name": "SIMULATE_USERS",
+            -                    "value": "0"
+            +                    "value": "5"
+                               },
+                               {
+                                 "name": "SIMULATE_PEERS_PATH",
+        "#]]);
         let mocksrv = stub.run(fakeserver);
         reconcile(Arc::new(simulation), testctx)
             .await
@@ -625,7 +1419,16 @@ mod tests {
         stub.manager_job.patch(expect![[r#"
             --- original
             +++ modified
-            @@ -65,7 +65,7 @@
+            @@ -16,7 +16,7 @@
+                     "ownerReferences": []
+                   },
+                   "spec": {
+            -        "activeDeadlineSeconds": 300,
+            +        "activeDeadlineSeconds": 900,
+                     "backoffLimit": 4,
+                     "template": {
+                       "metadata": {
+            @@ -66,7 +66,7 @@
                                },
                                {
                                  "name": "SIMULATE_RUN_TIME",
@@ -635,6 +1438,165 @@ mod tests {
                                {
                                  "name": "DID_KEY",
         "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -16,7 +16,7 @@
+                     "ownerReferences": []
+                   },
+                   "spec": {
+            -        "activeDeadlineSeconds": 300,
+            +        "activeDeadlineSeconds": 900,
+                     "backoffLimit": 4,
+                     "template": {
+                       "metadata": {
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -16,7 +16,7 @@
+                     "ownerReferences": []
+                   },
+                   "spec": {
+            -        "activeDeadlineSeconds": 300,
+            +        "activeDeadlineSeconds": 900,
+                     "backoffLimit": 4,
+                     "template": {
+                       "metadata": {
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_deadline_buffer() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            deadline_buffer: Some(10),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -16,7 +16,7 @@
+                     "ownerReferences": []
+                   },
+                   "spec": {
+            -        "activeDeadlineSeconds": 300,
+            +        "activeDeadlineSeconds": 600,
+                     "backoffLimit": 4,
+                     "template": {
+                       "metadata": {
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -16,7 +16,7 @@
+                     "ownerReferences": []
+                   },
+                   "spec": {
+            -        "activeDeadlineSeconds": 300,
+            +        "activeDeadlineSeconds": 600,
+                     "backoffLimit": 4,
+                     "template": {
+                       "metadata": {
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -16,7 +16,7 @@
+                     "ownerReferences": []
+                   },
+                   "spec": {
+            -        "activeDeadlineSeconds": 300,
+            +        "activeDeadlineSeconds": 600,
+                     "backoffLimit": 4,
+                     "template": {
+                       "metadata": {
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_resource_limits() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            resource_limits: Some(ResourceLimitsSpec {
+                cpu: Some(Quantity("4".to_owned())),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -82,12 +82,12 @@
+                             "name": "manager",
+                             "resources": {
+                               "limits": {
+            -                    "cpu": "2",
+            +                    "cpu": "4",
+                                 "ephemeral-storage": "1Gi",
+                                 "memory": "2Gi"
+                               },
+                               "requests": {
+            -                    "cpu": "2",
+            +                    "cpu": "4",
+                                 "ephemeral-storage": "1Gi",
+                                 "memory": "2Gi"
+                               }
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -86,12 +86,12 @@
+                             "name": "worker",
+                             "resources": {
+                               "limits": {
+            -                    "cpu": "2",
+            +                    "cpu": "4",
+                                 "ephemeral-storage": "1Gi",
+                                 "memory": "2Gi"
+                               },
+                               "requests": {
+            -                    "cpu": "2",
+            +                    "cpu": "4",
+                                 "ephemeral-storage": "1Gi",
+                                 "memory": "2Gi"
+                               }
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -86,12 +86,12 @@
+                             "name": "worker",
+                             "resources": {
+                               "limits": {
+            -                    "cpu": "2",
+            +                    "cpu": "4",
+                                 "ephemeral-storage": "1Gi",
+                                 "memory": "2Gi"
+                               },
+                               "requests": {
+            -                    "cpu": "2",
+            +                    "cpu": "4",
+                                 "ephemeral-storage": "1Gi",
+                                 "memory": "2Gi"
+                               }
+        "#]]);
         let mocksrv = stub.run(fakeserver);
         reconcile(Arc::new(simulation), testctx)
             .await
@@ -658,18 +1620,21 @@ mod tests {
                     ipfs_rpc_addr: "ipfs_rpc_addr_0".to_owned(),
                     ceramic_addr: "ceramic_addr_0".to_owned(),
                     p2p_addrs: vec!["p2p_addr_0".to_owned(), "p2p_addr_1".to_owned()],
+                    ..Default::default()
                 }),
                 Peer::Ceramic(CeramicPeerInfo {
                     peer_id: "1".to_owned(),
                     ipfs_rpc_addr: "ipfs_rpc_addr_1".to_owned(),
                     ceramic_addr: "ceramic_addr_1".to_owned(),
                     p2p_addrs: vec!["p2p_addr_0".to_owned(), "p2p_addr_1".to_owned()],
+                    ..Default::default()
                 }),
                 Peer::Ceramic(CeramicPeerInfo {
                     peer_id: "2".to_owned(),
                     ipfs_rpc_addr: "ipfs_rpc_addr_2".to_owned(),
                     ceramic_addr: "ceramic_addr_2".to_owned(),
                     p2p_addrs: vec!["p2p_addr_0".to_owned(), "p2p_addr_1".to_owned()],
+                    ..Default::default()
                 }),
             ];
 
@@ -682,6 +1647,19 @@ mod tests {
         };
         stub.worker_jobs
             .push(expect_file!["./testdata/worker_job_2"].into());
+        stub.status.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -10,7 +10,7 @@
+                     "nonce": 42,
+                     "phase": "running",
+                     "completedWorkers": 0,
+            -        "totalWorkers": 2
+            +        "totalWorkers": 3
+                   }
+                 },
+             }
+        "#]]);
 
         let mocksrv = stub.run(fakeserver);
         reconcile(Arc::new(simulation), testctx)
@@ -691,46 +1669,198 @@ mod tests {
     }
     #[tokio::test]
     #[traced_test]
-    async fn reconcile_scenario_custom_images() {
+    async fn reconcile_removes_stale_workers() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test();
+        let mut stub = Stub::default();
+        // Shrink the network down to a single peer.
+        stub.peers_config_map.1 = {
+            let peers = vec![Peer::Ceramic(CeramicPeerInfo {
+                peer_id: "0".to_owned(),
+                ipfs_rpc_addr: "ipfs_rpc_addr_0".to_owned(),
+                ceramic_addr: "ceramic_addr_0".to_owned(),
+                p2p_addrs: vec!["p2p_addr_0".to_owned(), "p2p_addr_1".to_owned()],
+                ..Default::default()
+            })];
+
+            let json_bytes =
+                serde_json::to_string(&peers).expect("should be able to serialize PeerInfo");
+            ConfigMap {
+                data: Some(BTreeMap::from_iter([("peers.json".to_owned(), json_bytes)])),
+                ..Default::default()
+            }
+        };
+        stub.worker_jobs.truncate(1);
+        // Workers from previous passes are still present.
+        stub.worker_jobs_list.1.items = [
+            "simulate-manager",
+            "simulate-worker-0",
+            "simulate-worker-1",
+            "simulate-worker-2",
+        ]
+        .into_iter()
+        .map(|name| {
+            let mut job = Job::default();
+            job.meta_mut().name = Some(name.to_owned());
+            job
+        })
+        .collect();
+        stub.worker_job_deletes = vec![
+            expect_file!["./testdata/delete_worker_job_1"].into(),
+            expect_file!["./testdata/delete_worker_job_2"].into(),
+        ];
+        stub.status.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -10,7 +10,7 @@
+                     "nonce": 42,
+                     "phase": "running",
+                     "completedWorkers": 0,
+            -        "totalWorkers": 2
+            +        "totalWorkers": 1
+                   }
+                 },
+             }
+        "#]]);
+
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_target_spec() {
         let mock_rpc_client = MockIpfsRpcClientTest::new();
         let (testctx, api_handle) = Context::test(mock_rpc_client);
         let fakeserver = ApiServerVerifier::new(api_handle);
         let simulation = Simulation::test().with_spec(SimulationSpec {
-            scenario: "test-scenario".to_owned(),
-            image: Some("image:dev".to_owned()),
-            image_pull_policy: Some("IfNotPresent".to_owned()),
+            target_spec: Some("ceramic-1".to_owned()),
             ..Default::default()
         });
         let mut stub = Stub::default();
+        // Two peers of the first ceramic spec and one of the second
+        stub.peers_config_map.1 = {
+            let peers: Vec<Peer> = ["ceramic-0", "ceramic-0", "ceramic-1"]
+                .into_iter()
+                .enumerate()
+                .map(|(i, spec_name)| {
+                    Peer::Ceramic(CeramicPeerInfo {
+                        peer_id: i.to_string(),
+                        ipfs_rpc_addr: format!("ipfs_rpc_addr_{i}"),
+                        ceramic_addr: format!("ceramic_addr_{i}"),
+                        p2p_addrs: vec![format!("p2p_addr_{i}")],
+                        spec_name: Some(spec_name.to_owned()),
+                        ..Default::default()
+                    })
+                })
+                .collect();
+
+            let json_bytes =
+                serde_json::to_string(&peers).expect("should be able to serialize PeerInfo");
+            ConfigMap {
+                data: Some(BTreeMap::from_iter([("peers.json".to_owned(), json_bytes)])),
+                ..Default::default()
+            }
+        };
         stub.manager_job.patch(expect![[r#"
             --- original
             +++ modified
-            @@ -41,7 +41,7 @@
-                               },
-                               {
-                                 "name": "SIMULATE_SCENARIO",
-            -                    "value": ""
-            +                    "value": "test-scenario"
-                               },
+            @@ -75,6 +75,10 @@
                                {
-                                 "name": "SIMULATE_MANAGER",
-            @@ -76,8 +76,8 @@
+                                 "name": "DID_PRIVATE_KEY",
                                  "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_TARGET_SPEC",
+            +                    "value": "ceramic-1"
                                }
                              ],
-            -                "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
-            -                "imagePullPolicy": "Always",
-            +                "image": "image:dev",
-            +                "imagePullPolicy": "IfNotPresent",
-                             "name": "manager",
-                             "volumeMounts": [
-                               {
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
         "#]]);
+        // Only a single worker targets the peer of the second spec
+        stub.worker_jobs.truncate(1);
         stub.worker_jobs[0].patch(expect![[r#"
             --- original
             +++ modified
-            @@ -49,7 +49,7 @@
-                               },
+            @@ -79,6 +79,10 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_TARGET_SPEC",
+            +                    "value": "ceramic-1"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_job_deletes = vec![expect_file!["./testdata/delete_worker_job_1"].into()];
+        stub.status.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -10,7 +10,7 @@
+                     "nonce": 42,
+                     "phase": "running",
+                     "completedWorkers": 0,
+            -        "totalWorkers": 2
+            +        "totalWorkers": 1
+                   }
+                 },
+             }
+        "#]]);
+
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_scenario_custom_images() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            scenario: "test-scenario".to_owned(),
+            image: Some("image:dev".to_owned()),
+            image_pull_policy: Some("IfNotPresent".to_owned()),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -42,7 +42,7 @@
+                               },
+                               {
+                                 "name": "SIMULATE_SCENARIO",
+            -                    "value": ""
+            +                    "value": "test-scenario"
+                               },
+                               {
+                                 "name": "SIMULATE_MANAGER",
+            @@ -77,8 +77,8 @@
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+                               }
+                             ],
+            -                "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+            -                "imagePullPolicy": "Always",
+            +                "image": "image:dev",
+            +                "imagePullPolicy": "IfNotPresent",
+                             "name": "manager",
+                             "resources": {
+                               "limits": {
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -50,7 +50,7 @@
+                               },
                                {
                                  "name": "SIMULATE_SCENARIO",
             -                    "value": ""
@@ -738,7 +1868,7 @@ mod tests {
                                },
                                {
                                  "name": "SIMULATE_TARGET_PEER",
-            @@ -72,8 +72,8 @@
+            @@ -81,8 +81,8 @@
                                  "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
                                }
                              ],
@@ -747,13 +1877,13 @@ mod tests {
             +                "image": "image:dev",
             +                "imagePullPolicy": "IfNotPresent",
                              "name": "worker",
-                             "volumeMounts": [
-                               {
+                             "resources": {
+                               "limits": {
         "#]]);
         stub.worker_jobs[1].patch(expect![[r#"
             --- original
             +++ modified
-            @@ -49,7 +49,7 @@
+            @@ -50,7 +50,7 @@
                                },
                                {
                                  "name": "SIMULATE_SCENARIO",
@@ -762,7 +1892,7 @@ mod tests {
                                },
                                {
                                  "name": "SIMULATE_TARGET_PEER",
-            @@ -72,8 +72,8 @@
+            @@ -81,8 +81,8 @@
                                  "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
                                }
                              ],
@@ -771,8 +1901,8 @@ mod tests {
             +                "image": "image:dev",
             +                "imagePullPolicy": "IfNotPresent",
                              "name": "worker",
-                             "volumeMounts": [
-                               {
+                             "resources": {
+                               "limits": {
         "#]]);
         let mocksrv = stub.run(fakeserver);
         reconcile(Arc::new(simulation), testctx)
@@ -794,7 +1924,7 @@ mod tests {
         stub.manager_job.patch(expect![[r#"
             --- original
             +++ modified
-            @@ -74,6 +74,10 @@
+            @@ -75,6 +75,10 @@
                                {
                                  "name": "DID_PRIVATE_KEY",
                                  "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
@@ -812,4 +1942,1544 @@ mod tests {
             .expect("reconciler");
         timeout_after_1s(mocksrv).await;
     }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_read_ratio() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            read_ratio: Some(50),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -75,6 +75,10 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_READ_RATIO",
+            +                    "value": "50"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,10 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_READ_RATIO",
+            +                    "value": "50"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,10 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_READ_RATIO",
+            +                    "value": "50"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_otlp_endpoint() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            otlp_endpoint: Some("http://collector.monitoring:4317".to_owned()),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -34,7 +34,7 @@
+                             "env": [
+                               {
+                                 "name": "RUNNER_OTLP_ENDPOINT",
+            -                    "value": "http://otel:4317"
+            +                    "value": "http://collector.monitoring:4317"
+                               },
+                               {
+                                 "name": "RUST_LOG",
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -38,7 +38,7 @@
+                               },
+                               {
+                                 "name": "RUNNER_OTLP_ENDPOINT",
+            -                    "value": "http://otel:4317"
+            +                    "value": "http://collector.monitoring:4317"
+                               },
+                               {
+                                 "name": "RUST_LOG",
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -38,7 +38,7 @@
+                               },
+                               {
+                                 "name": "RUNNER_OTLP_ENDPOINT",
+            -                    "value": "http://otel:4317"
+            +                    "value": "http://collector.monitoring:4317"
+                               },
+                               {
+                                 "name": "RUST_LOG",
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_existing_collector() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            monitoring: Some(MonitoringSpec {
+                collector_selector: Some(BTreeMap::from_iter([(
+                    "app".to_owned(),
+                    "otel-collector".to_owned(),
+                )])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        // The collector is discovered instead of deploying the monitoring stack
+        stub.collector = Some((
+            expect_file!["./testdata/collector_services"].into(),
+            ServiceList {
+                items: vec![Service {
+                    metadata: ObjectMeta {
+                        name: Some("otel-collector".to_owned()),
+                        namespace: Some("monitoring".to_owned()),
+                        ..Default::default()
+                    },
+                    spec: Some(ServiceSpec {
+                        ports: Some(vec![
+                            ServicePort {
+                                port: 8889,
+                                ..Default::default()
+                            },
+                            ServicePort {
+                                port: 4317,
+                                ..Default::default()
+                            },
+                        ]),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ));
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -34,7 +34,7 @@
+                             "env": [
+                               {
+                                 "name": "RUNNER_OTLP_ENDPOINT",
+            -                    "value": "http://otel:4317"
+            +                    "value": "http://otel-collector.monitoring:4317"
+                               },
+                               {
+                                 "name": "RUST_LOG",
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -38,7 +38,7 @@
+                               },
+                               {
+                                 "name": "RUNNER_OTLP_ENDPOINT",
+            -                    "value": "http://otel:4317"
+            +                    "value": "http://otel-collector.monitoring:4317"
+                               },
+                               {
+                                 "name": "RUST_LOG",
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -38,7 +38,7 @@
+                               },
+                               {
+                                 "name": "RUNNER_OTLP_ENDPOINT",
+            -                    "value": "http://otel:4317"
+            +                    "value": "http://otel-collector.monitoring:4317"
+                               },
+                               {
+                                 "name": "RUST_LOG",
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_wait_time() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            wait_time_min: Some(0),
+            wait_time_max: Some(100),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -75,6 +75,14 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_WAIT_TIME_MIN",
+            +                    "value": "0"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_WAIT_TIME_MAX",
+            +                    "value": "100"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,14 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_WAIT_TIME_MIN",
+            +                    "value": "0"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_WAIT_TIME_MAX",
+            +                    "value": "100"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,14 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_WAIT_TIME_MIN",
+            +                    "value": "0"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_WAIT_TIME_MAX",
+            +                    "value": "100"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_think_time() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            think_time: Some("pareto".to_owned()),
+            think_time_shape: Some(1.5),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -75,6 +75,14 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_THINK_TIME",
+            +                    "value": "pareto"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_THINK_TIME_SHAPE",
+            +                    "value": "1.5"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,14 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_THINK_TIME",
+            +                    "value": "pareto"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_THINK_TIME_SHAPE",
+            +                    "value": "1.5"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,14 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_THINK_TIME",
+            +                    "value": "pareto"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_THINK_TIME_SHAPE",
+            +                    "value": "1.5"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_tag_peer() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            tag_peer: Some(true),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,10 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_TAG_PEER",
+            +                    "value": "true"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,10 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_TAG_PEER",
+            +                    "value": "true"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_revision() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            revision: Some("0a1b2c3".to_owned()),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -21,6 +21,7 @@
+                     "template": {
+                       "metadata": {
+                         "labels": {
+            +              "keramik.3box.io/revision": "0a1b2c3",
+                           "name": "goose"
+                         }
+                       },
+            @@ -75,6 +76,10 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_REVISION",
+            +                    "value": "0a1b2c3"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -21,6 +21,7 @@
+                     "template": {
+                       "metadata": {
+                         "labels": {
+            +              "keramik.3box.io/revision": "0a1b2c3",
+                           "name": "goose"
+                         }
+                       },
+            @@ -79,6 +80,10 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_REVISION",
+            +                    "value": "0a1b2c3"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -21,6 +21,7 @@
+                     "template": {
+                       "metadata": {
+                         "labels": {
+            +              "keramik.3box.io/revision": "0a1b2c3",
+                           "name": "goose"
+                         }
+                       },
+            @@ -79,6 +80,10 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_REVISION",
+            +                    "value": "0a1b2c3"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_random_seed() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            seed: Some(1234),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -75,6 +75,10 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_SEED",
+            +                    "value": "1234"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,10 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_SEED",
+            +                    "value": "1234"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,10 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_SEED",
+            +                    "value": "1234"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_did() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            did: Some(DidSpec {
+                key: "did:key:z6MkeeMsR3fWbnFkZzLBmQKqHR5h2vDDNLXjNc7Fz6nXJa8H".to_owned(),
+                private_key_secret: SecretKeySpec {
+                    name: "load-test-did".to_owned(),
+                    key: "private-key".to_owned(),
+                },
+            }),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -70,11 +70,16 @@
+                               },
+                               {
+                                 "name": "DID_KEY",
+            -                    "value": "did:key:z6Mkqn5jbycThHcBtakJZ8fHBQ2oVRQhXQEdQk5ZK2NDtNZA"
+            +                    "value": "did:key:z6MkeeMsR3fWbnFkZzLBmQKqHR5h2vDDNLXjNc7Fz6nXJa8H"
+                               },
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+            -                    "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                    "valueFrom": {
+            +                      "secretKeyRef": {
+            +                        "key": "private-key",
+            +                        "name": "load-test-did"
+            +                      }
+            +                    }
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -74,11 +74,16 @@
+                               },
+                               {
+                                 "name": "DID_KEY",
+            -                    "value": "did:key:z6Mkqn5jbycThHcBtakJZ8fHBQ2oVRQhXQEdQk5ZK2NDtNZA"
+            +                    "value": "did:key:z6MkeeMsR3fWbnFkZzLBmQKqHR5h2vDDNLXjNc7Fz6nXJa8H"
+                               },
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+            -                    "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                    "valueFrom": {
+            +                      "secretKeyRef": {
+            +                        "key": "private-key",
+            +                        "name": "load-test-did"
+            +                      }
+            +                    }
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -74,11 +74,16 @@
+                               },
+                               {
+                                 "name": "DID_KEY",
+            -                    "value": "did:key:z6Mkqn5jbycThHcBtakJZ8fHBQ2oVRQhXQEdQk5ZK2NDtNZA"
+            +                    "value": "did:key:z6MkeeMsR3fWbnFkZzLBmQKqHR5h2vDDNLXjNc7Fz6nXJa8H"
+                               },
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+            -                    "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                    "valueFrom": {
+            +                      "secretKeyRef": {
+            +                        "key": "private-key",
+            +                        "name": "load-test-did"
+            +                      }
+            +                    }
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_thresholds() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            min_throughput: Some(50.0),
+            max_error_rate: Some(0.01),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -75,6 +75,14 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_MIN_THROUGHPUT",
+            +                    "value": "50"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_MAX_ERROR_RATE",
+            +                    "value": "0.01"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_seed() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            seed_documents: Some(100),
+            seed_models: Some(vec!["small".to_owned(), "large".to_owned()]),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,14 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_SEED_DOCUMENTS",
+            +                    "value": "100"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_SEED_MODELS",
+            +                    "value": "small,large"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,14 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_SEED_DOCUMENTS",
+            +                    "value": "100"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_SEED_MODELS",
+            +                    "value": "small,large"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_keep_failed_pods() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            keep_failed_pods: Some(true),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -17,7 +17,7 @@
+                   },
+                   "spec": {
+                     "activeDeadlineSeconds": 300,
+            -        "backoffLimit": 4,
+            +        "backoffLimit": 0,
+                     "template": {
+                       "metadata": {
+                         "labels": {
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -17,7 +17,7 @@
+                   },
+                   "spec": {
+                     "activeDeadlineSeconds": 300,
+            -        "backoffLimit": 4,
+            +        "backoffLimit": 0,
+                     "template": {
+                       "metadata": {
+                         "labels": {
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -17,7 +17,7 @@
+                   },
+                   "spec": {
+                     "activeDeadlineSeconds": 300,
+            -        "backoffLimit": 4,
+            +        "backoffLimit": 0,
+                     "template": {
+                       "metadata": {
+                         "labels": {
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_backoff_limit() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            backoff_limit: Some(1),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_job.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -17,7 +17,7 @@
+                   },
+                   "spec": {
+                     "activeDeadlineSeconds": 300,
+            -        "backoffLimit": 4,
+            +        "backoffLimit": 1,
+                     "template": {
+                       "metadata": {
+                         "labels": {
+        "#]]);
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -17,7 +17,7 @@
+                   },
+                   "spec": {
+                     "activeDeadlineSeconds": 300,
+            -        "backoffLimit": 4,
+            +        "backoffLimit": 1,
+                     "template": {
+                       "metadata": {
+                         "labels": {
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -17,7 +17,7 @@
+                   },
+                   "spec": {
+                     "activeDeadlineSeconds": 300,
+            -        "backoffLimit": 4,
+            +        "backoffLimit": 1,
+                     "template": {
+                       "metadata": {
+                         "labels": {
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_grafana() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            grafana: Some(GrafanaSpec::default()),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.grafana = vec![
+            expect_file!["./testdata/grafana_prom_service"].into(),
+            expect_file!["./testdata/grafana_config"].into(),
+            expect_file!["./testdata/grafana_dashboards"].into(),
+            expect_file!["./testdata/grafana_service"].into(),
+            expect_file!["./testdata/grafana_stateful_set"].into(),
+        ];
+        stub.grafana_status = Some((
+            expect_file!["./testdata/grafana_status"].into(),
+            StatefulSet {
+                status: Some(StatefulSetStatus {
+                    ready_replicas: Some(1),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ));
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_finished() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test();
+        let mut stub = Stub::default();
+        // The manager and both workers have completed
+        stub.manager_status.1 = completed_job("simulate-manager");
+        stub.worker_jobs = vec![];
+        stub.worker_jobs_list.1.items = vec![
+            completed_job("simulate-manager"),
+            completed_job("simulate-worker-0"),
+            completed_job("simulate-worker-1"),
+        ];
+        stub.status.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -8,8 +8,8 @@
+                 body: {
+                   "status": {
+                     "nonce": 42,
+            -        "phase": "running",
+            -        "completedWorkers": 0,
+            +        "phase": "succeeded",
+            +        "completedWorkers": 2,
+                     "totalWorkers": 2
+                   }
+                 },
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        let action = reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        assert_eq!(Action::await_change(), action);
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_worker_failed() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test();
+        let mut stub = Stub::default();
+        // The manager and one of the workers have completed, the other worker failed
+        stub.manager_status.1 = completed_job("simulate-manager");
+        stub.worker_jobs = vec![];
+        stub.worker_jobs_list.1.items = vec![
+            completed_job("simulate-manager"),
+            completed_job("simulate-worker-0"),
+            failed_job("simulate-worker-1"),
+        ];
+        stub.status.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -8,8 +8,8 @@
+                 body: {
+                   "status": {
+                     "nonce": 42,
+            -        "phase": "running",
+            -        "completedWorkers": 0,
+            +        "phase": "failed",
+            +        "completedWorkers": 1,
+                     "totalWorkers": 2
+                   }
+                 },
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        let action = reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        assert_eq!(Action::await_change(), action);
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_finished_cleanup() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            cleanup_policy: Some(CleanupPolicy::OnSuccess),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        // The manager and both workers have completed
+        stub.manager_status.1 = completed_job("simulate-manager");
+        stub.worker_jobs = vec![];
+        stub.worker_jobs_list.1.items = vec![
+            completed_job("simulate-manager"),
+            completed_job("simulate-worker-0"),
+            completed_job("simulate-worker-1"),
+        ];
+        // The jobs are kept until the finished phase is persisted
+        stub.status.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -8,8 +8,8 @@
+                 body: {
+                   "status": {
+                     "nonce": 42,
+            -        "phase": "running",
+            -        "completedWorkers": 0,
+            +        "phase": "succeeded",
+            +        "completedWorkers": 2,
+                     "totalWorkers": 2
+                   }
+                 },
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        let action = reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        assert_eq!(Action::requeue(Duration::from_secs(10)), action);
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_finished_cleanup_persisted() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test()
+            .with_spec(SimulationSpec {
+                cleanup_policy: Some(CleanupPolicy::OnSuccess),
+                ..Default::default()
+            })
+            .with_status(SimulationStatus {
+                nonce: 42,
+                phase: SimulationPhase::Succeeded,
+                completed_workers: 2,
+                total_workers: 2,
+                ..Default::default()
+            });
+        let mut stub = Stub::default();
+        // Only the jobs are deleted once the simulation is known to be finished
+        stub.finished_job_deletes = Some(vec![
+            expect_file!["./testdata/delete_manager_job"].into(),
+            expect_file!["./testdata/delete_worker_job_0"].into(),
+            expect_file!["./testdata/delete_worker_job_1"].into(),
+        ]);
+        let mocksrv = stub.run(fakeserver);
+        let action = reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        assert_eq!(Action::await_change(), action);
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_manager_deployment() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            manager_kind: Some(ManagerKind::Deployment),
+            // The manager runs until the simulation is deleted regardless of the run time
+            run_time: 10,
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.manager_deployment = Some((
+            expect_file!["./testdata/manager_deployment"].into(),
+            expect_file!["./testdata/manager_deployment_status"].into(),
+            Deployment {
+                status: Some(DeploymentStatus {
+                    ready_replicas: Some(1),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ));
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_grafana_dashboard() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            monitoring: Some(MonitoringSpec {
+                emit_grafana_dashboard: Some(true),
+            }),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.grafana_dashboard = Some(expect_file!["./testdata/grafana_dashboard"].into());
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_open_metrics() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            open_metrics: Some(true),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.prom_config.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -9,7 +9,7 @@
+                   "apiVersion": "v1",
+                   "kind": "ConfigMap",
+                   "data": {
+            -        "prom-config.yaml": "\n        global:\n          scrape_interval: 10s\n          scrape_timeout: 5s\n        \n        scrape_configs:\n          - job_name: services\n            metrics_path: /metrics\n            honor_labels: true\n            static_configs:\n              - targets:\n                - 'localhost:9090'\n                - 'otel:9090'\n                - 'otel:8888'"
+            +        "prom-config.yaml": "\n        global:\n          scrape_interval: 10s\n          scrape_timeout: 5s\n          scrape_protocols:\n            - OpenMetricsText1.0.0\n            - OpenMetricsText0.0.1\n            - PrometheusText0.0.4\n        \n        scrape_configs:\n          - job_name: services\n            metrics_path: /metrics\n            honor_labels: true\n            static_configs:\n              - targets:\n                - 'localhost:9090'\n                - 'otel:9090'\n                - 'otel:8888'"
+                   },
+                   "metadata": {
+                     "labels": {
+        "#]]);
+        stub.prom_stateful_set.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -35,7 +35,8 @@
+                             "command": [
+                               "/bin/prometheus",
+                               "--web.enable-lifecycle",
+            -                  "--config.file=/config/prom-config.yaml"
+            +                  "--config.file=/config/prom-config.yaml",
+            +                  "--enable-feature=exemplar-storage"
+                             ],
+                             "image": "prom/prometheus:v2.49.1",
+                             "name": "prometheus",
+        "#]]);
+        stub.otel_config.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -9,7 +9,7 @@
+                   "apiVersion": "v1",
+                   "kind": "ConfigMap",
+                   "data": {
+            -        "otel-config.yaml": "\n    receivers:\n      # Push based metrics\n      otlp:\n        protocols:\n          grpc:\n            endpoint: 0.0.0.0:4317\n      # Pull based metrics\n      prometheus:\n        config:\n          scrape_configs:\n            - job_name: 'kubernetes-service-endpoints'\n              scrape_interval: 10s\n              scrape_timeout: 1s\n    \n              kubernetes_sd_configs:\n              - role: pod\n    \n              # Only container ports named `metrics` will be considered valid targets.\n              #\n              # Setup relabel rules to give meaning to the following k8s annotations:\n              #   prometheus/path - URL path of the metrics endpoint\n              #\n              # Example:\n              #   annotations:\n              #      prometheus/path: \"/api/v0/metrics\"\n              relabel_configs:\n              - source_labels: [__meta_kubernetes_pod_container_port_name]\n                action: keep\n                regex: \"metrics\"\n              - source_labels: [__meta_kubernetes_pod_annotation_prometheus_path]\n                action: replace\n                target_label: __metrics_path__\n                regex: (.+)\n              - source_labels: [__meta_kubernetes_namespace]\n                action: replace\n                target_label: kubernetes_namespace\n              - source_labels: [__meta_kubernetes_pod_name]\n                action: replace\n                target_label: kubernetes_pod\n              - source_labels: [__meta_kubernetes_pod_container_name]\n                action: replace\n                target_label: kubernetes_container\n    \n    processors:\n      batch:\n    \n    exporters:\n      # This is unused but can be easily added for debugging.\n      logging:\n        # can be one of detailed | normal | basic\n        verbosity: detailed\n        # Log all messages, do not sample\n        sampling_initial: 1\n        sampling_thereafter: 1\n      otlp/jaeger:\n        endpoint: jaeger:4317\n        tls:\n          insecure: true\n      prometheus:\n        endpoint: 0.0.0.0:9090\n        # Keep stale metrics around for 1h before dropping\n        # This helps as simulation metrics are stale once the simulation stops.\n        metric_expiration: 1h\n        resource_to_telemetry_conversion: \n          enabled: true\n        enable_open_metrics: false\n      parquet:\n        path: /data/\n      # Grafana Cloud export\n      # TODO: Remove, this work however its not possible to\n      # namespace the metrics from other Grafana metrics which makes\n      # it hard to consume and polutes the normal metrics namespace.\n      #\n      # For now leaving this here as an example of how to enable,\n      # but will rely on local prometheus metrics in the short term.\n      #otlphttp/grafana:\n      #  auth:\n      #    authenticator: basicauth/grafana\n      #  endpoint: https://otlp-gateway-prod-us-central-0.grafana.net/otlp\n    \n            #extensions:\n            #  basicauth/grafana:\n            #    client_auth:\n            #      username: \"user\" # replace with Grafana instance id\n            #      password: \"password\" # replace with Grafana API token (via a secret)\n    \n    service:\n      #extensions: [basicauth/grafana]\n      pipelines:\n        traces:\n          receivers: [otlp]\n          processors: [batch]\n          exporters: [otlp/jaeger]\n        metrics:\n          receivers: [otlp,prometheus]\n          processors: [batch]\n          exporters: [parquet, prometheus]\n      # Enable telemetry on the collector itself\n      telemetry:\n        logs:\n          level: info\n        metrics:\n          level: detailed\n          address: 0.0.0.0:8888"
+            +        "otel-config.yaml": "\n    receivers:\n      # Push based metrics\n      otlp:\n        protocols:\n          grpc:\n            endpoint: 0.0.0.0:4317\n      # Pull based metrics\n      prometheus:\n        config:\n          scrape_configs:\n            - job_name: 'kubernetes-service-endpoints'\n              scrape_interval: 10s\n              scrape_timeout: 1s\n    \n              kubernetes_sd_configs:\n              - role: pod\n    \n              # Only container ports named `metrics` will be considered valid targets.\n              #\n              # Setup relabel rules to give meaning to the following k8s annotations:\n              #   prometheus/path - URL path of the metrics endpoint\n              #\n              # Example:\n              #   annotations:\n              #      prometheus/path: \"/api/v0/metrics\"\n              relabel_configs:\n              - source_labels: [__meta_kubernetes_pod_container_port_name]\n                action: keep\n                regex: \"metrics\"\n              - source_labels: [__meta_kubernetes_pod_annotation_prometheus_path]\n                action: replace\n                target_label: __metrics_path__\n                regex: (.+)\n              - source_labels: [__meta_kubernetes_namespace]\n                action: replace\n                target_label: kubernetes_namespace\n              - source_labels: [__meta_kubernetes_pod_name]\n                action: replace\n                target_label: kubernetes_pod\n              - source_labels: [__meta_kubernetes_pod_container_name]\n                action: replace\n                target_label: kubernetes_container\n    \n    processors:\n      batch:\n    \n    exporters:\n      # This is unused but can be easily added for debugging.\n      logging:\n        # can be one of detailed | normal | basic\n        verbosity: detailed\n        # Log all messages, do not sample\n        sampling_initial: 1\n        sampling_thereafter: 1\n      otlp/jaeger:\n        endpoint: jaeger:4317\n        tls:\n          insecure: true\n      prometheus:\n        endpoint: 0.0.0.0:9090\n        # Keep stale metrics around for 1h before dropping\n        # This helps as simulation metrics are stale once the simulation stops.\n        metric_expiration: 1h\n        resource_to_telemetry_conversion: \n          enabled: true\n        enable_open_metrics: true\n      parquet:\n        path: /data/\n      # Grafana Cloud export\n      # TODO: Remove, this work however its not possible to\n      # namespace the metrics from other Grafana metrics which makes\n      # it hard to consume and polutes the normal metrics namespace.\n      #\n      # For now leaving this here as an example of how to enable,\n      # but will rely on local prometheus metrics in the short term.\n      #otlphttp/grafana:\n      #  auth:\n      #    authenticator: basicauth/grafana\n      #  endpoint: https://otlp-gateway-prod-us-central-0.grafana.net/otlp\n    \n            #extensions:\n            #  basicauth/grafana:\n            #    client_auth:\n            #      username: \"user\" # replace with Grafana instance id\n            #      password: \"password\" # replace with Grafana API token (via a secret)\n    \n    service:\n      #extensions: [basicauth/grafana]\n      pipelines:\n        traces:\n          receivers: [otlp]\n          processors: [batch]\n          exporters: [otlp/jaeger]\n        metrics:\n          receivers: [otlp,prometheus]\n          processors: [batch]\n          exporters: [parquet, prometheus]\n      # Enable telemetry on the collector itself\n      telemetry:\n        logs:\n          level: info\n        metrics:\n          level: detailed\n          address: 0.0.0.0:8888"
+                   },
+                   "metadata": {
+                     "labels": {
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_prometheus_retention() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            monitoring: Some(MonitoringSpec {
+                scrape_interval: Some("30s".to_owned()),
+                retention: Some("7d".to_owned()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.prom_config.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -9,7 +9,7 @@
+                   "apiVersion": "v1",
+                   "kind": "ConfigMap",
+                   "data": {
+            -        "prom-config.yaml": "\n        global:\n          scrape_interval: 10s\n          scrape_timeout: 5s\n        \n        scrape_configs:\n          - job_name: services\n            metrics_path: /metrics\n            honor_labels: true\n            static_configs:\n              - targets:\n                - 'localhost:9090'\n                - 'otel:9090'\n                - 'otel:8888'"
+            +        "prom-config.yaml": "\n        global:\n          scrape_interval: 30s\n        \n        scrape_configs:\n          - job_name: services\n            metrics_path: /metrics\n            honor_labels: true\n            static_configs:\n              - targets:\n                - 'localhost:9090'\n                - 'otel:9090'\n                - 'otel:8888'"
+                   },
+                   "metadata": {
+                     "labels": {
+        "#]]);
+        stub.prom_stateful_set.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -35,7 +35,8 @@
+                             "command": [
+                               "/bin/prometheus",
+                               "--web.enable-lifecycle",
+            -                  "--config.file=/config/prom-config.yaml"
+            +                  "--config.file=/config/prom-config.yaml",
+            +                  "--storage.tsdb.retention.time=7d"
+                             ],
+                             "image": "prom/prometheus:v2.49.1",
+                             "name": "prometheus",
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_large_model_size() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            large_model_size: Some(262_144),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,10 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_LARGE_MODEL_SIZE",
+            +                    "value": "262144"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,10 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_LARGE_MODEL_SIZE",
+            +                    "value": "262144"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_catalog() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            catalog_size: Some(20),
+            catalog_config_map: Some("ceramic-catalog".to_owned()),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.worker_jobs[0].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,14 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_CATALOG_SIZE",
+            +                    "value": "20"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_CATALOG_PATH",
+            +                    "value": "/catalog/catalog.json"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+            @@ -100,6 +108,11 @@
+                               {
+                                 "mountPath": "/keramik-peers",
+                                 "name": "keramik-peers"
+            +                  },
+            +                  {
+            +                    "mountPath": "/catalog",
+            +                    "name": "catalog",
+            +                    "readOnly": true
+                               }
+                             ]
+                           }
+            @@ -160,6 +173,12 @@
+                                 }
+                               ]
+                             }
+            +              },
+            +              {
+            +                "configMap": {
+            +                  "name": "ceramic-catalog"
+            +                },
+            +                "name": "catalog"
+                           }
+                         ]
+                       }
+        "#]]);
+        stub.worker_jobs[1].patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -79,6 +79,14 @@
+                               {
+                                 "name": "DID_PRIVATE_KEY",
+                                 "value": "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_CATALOG_SIZE",
+            +                    "value": "20"
+            +                  },
+            +                  {
+            +                    "name": "SIMULATE_CATALOG_PATH",
+            +                    "value": "/catalog/catalog.json"
+                               }
+                             ],
+                             "image": "public.ecr.aws/r5b3e0r5/3box/keramik-runner:latest",
+            @@ -100,6 +108,11 @@
+                               {
+                                 "mountPath": "/keramik-peers",
+                                 "name": "keramik-peers"
+            +                  },
+            +                  {
+            +                    "mountPath": "/catalog",
+            +                    "name": "catalog",
+            +                    "readOnly": true
+                               }
+                             ]
+                           }
+            @@ -160,6 +173,12 @@
+                                 }
+                               ]
+                             }
+            +              },
+            +              {
+            +                "configMap": {
+            +                  "name": "ceramic-catalog"
+            +                },
+            +                "name": "catalog"
+                           }
+                         ]
+                       }
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_invalid_spec() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            read_ratio: Some(101),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.error.1 = true;
+        let mocksrv = stub.run(fakeserver);
+        assert!(reconcile(Arc::new(simulation), testctx).await.is_err());
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_malformed_peers() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            validate_peers: Some(true),
+            ..Default::default()
+        });
+        let mut stub = Stub::default();
+        stub.peers_config_map.1 = ConfigMap {
+            data: Some(BTreeMap::from_iter(vec![(
+                "peers.json".to_owned(),
+                "not json".to_owned(),
+            )])),
+            ..Default::default()
+        };
+        stub.invalid_peers = true;
+        stub.error.0.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -11,7 +11,7 @@
+                     "phase": "failed",
+                     "completedWorkers": 0,
+                     "totalWorkers": 0,
+            -        "error": "invalid readRatio: 101 is not a percentage"
+            +        "error": "malformed peers.json: expected ident at line 1 column 2"
+                   }
+                 },
+             }
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        assert!(reconcile(Arc::new(simulation), testctx).await.is_err());
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_invalid_peer_addrs() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            validate_peers: Some(true),
+            ..Default::default()
+        });
+        // The addresses of the default peers are placeholders
+        let mut stub = Stub::default();
+        stub.invalid_peers = true;
+        stub.error.0.patch(expect![[r#"
+            --- original
+            +++ modified
+            @@ -11,7 +11,7 @@
+                     "phase": "failed",
+                     "completedWorkers": 0,
+                     "totalWorkers": 0,
+            -        "error": "invalid readRatio: 101 is not a percentage"
+            +        "error": "peer 0 has an invalid ipfs rpc address ipfs_rpc_addr_0: relative URL without a base"
+                   }
+                 },
+             }
+        "#]]);
+        let mocksrv = stub.run(fakeserver);
+        assert!(reconcile(Arc::new(simulation), testctx).await.is_err());
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn reconcile_waits_for_peers() {
+        let mock_rpc_client = MockIpfsRpcClientTest::new();
+        let (testctx, api_handle) = Context::test(mock_rpc_client);
+        let fakeserver = ApiServerVerifier::new(api_handle);
+        let simulation = Simulation::test().with_spec(SimulationSpec {
+            validate_peers: Some(true),
+            ..Default::default()
+        });
+        // The network is still starting and has not published any peers
+        let mut stub = Stub::default();
+        stub.peers_config_map.1 = ConfigMap {
+            data: Some(BTreeMap::from_iter(vec![(
+                "peers.json".to_owned(),
+                "[]".to_owned(),
+            )])),
+            ..Default::default()
+        };
+        stub.no_peers = true;
+        let mocksrv = stub.run(fakeserver);
+        let action = reconcile(Arc::new(simulation), testctx)
+            .await
+            .expect("reconciler");
+        assert_eq!(Action::requeue(Duration::from_secs(10)), action);
+        timeout_after_1s(mocksrv).await;
+    }
+
+    #[test]
+    fn validate_peers() {
+        let peer = |spec_name: &str| {
+            Peer::Ceramic(CeramicPeerInfo {
+                peer_id: "12D3KooWJ3pYwMovR5vzM3ahU5URuTXX8QLKsqEMmKnbZHDjx8dT".to_owned(),
+                ipfs_rpc_addr: "http://ceramic-0-0.ceramic-0.keramik-test.svc.cluster.local:5001"
+                    .to_owned(),
+                ceramic_addr: "http://ceramic-0-0.ceramic-0.keramik-test.svc.cluster.local:7007"
+                    .to_owned(),
+                p2p_addrs: vec!["/ip4/10.0.0.1/tcp/4001".to_owned()],
+                spec_name: Some(spec_name.to_owned()),
+                ..Default::default()
+            })
+        };
+        let mut peers = vec![peer("ceramic-0")];
+        assert!(super::validate_peers(&peers).is_ok());
+        // No peers yet is not an error, the simulation waits for them
+        assert!(super::validate_peers(&[]).is_ok());
+
+        if let Peer::Ceramic(info) = &mut peers[0] {
+            info.p2p_addrs = vec!["p2p_addr_0".to_owned()];
+        }
+        assert!(super::validate_peers(&peers).is_err());
+    }
+
+    #[test]
+    fn invalid_monitoring_specs() {
+        let error = |monitoring: MonitoringSpec| {
+            super::validate_spec(&SimulationSpec {
+                monitoring: Some(monitoring),
+                ..Default::default()
+            })
+            .err()
+            .unwrap()
+            .field
+        };
+        assert_eq!(
+            "monitoring.scrapeInterval",
+            error(MonitoringSpec {
+                scrape_interval: Some("10 seconds".to_owned()),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            "monitoring.retention",
+            error(MonitoringSpec {
+                retention: Some("".to_owned()),
+                ..Default::default()
+            })
+        );
+        assert!(super::validate_spec(&SimulationSpec {
+            monitoring: Some(MonitoringSpec {
+                scrape_interval: Some("30s".to_owned()),
+                retention: Some("1h30m".to_owned()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .is_ok());
+    }
 }
@@ -1,4 +1,16 @@
-use crate::simulation::SimulationSpec;
+use k8s_openapi::{
+    api::core::v1::{EnvVar, EnvVarSource, SecretKeySelector},
+    apimachinery::pkg::api::resource::Quantity,
+};
+
+use crate::{
+    network::{resource_limits::ResourceLimitsConfig, ResourceLimitsSpec},
+    simulation::{DidSpec, SimulationSpec},
+    utils::ConfigError,
+};
+
+/// Collector the manager and workers export their telemetry to unless the simulation overrides it.
+pub const DEFAULT_OTLP_ENDPOINT: &str = "http://otel:4317";
 
 /// Configuration for job images.
 #[derive(Clone, Debug)]
@@ -18,15 +30,158 @@ impl Default for JobImageConfig {
     }
 }
 
-impl From<&SimulationSpec> for JobImageConfig {
-    fn from(value: &SimulationSpec) -> Self {
+impl TryFrom<&SimulationSpec> for JobImageConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: &SimulationSpec) -> Result<Self, Self::Error> {
         let default = Self::default();
-        Self {
+        Ok(Self {
             image: value.image.to_owned().unwrap_or(default.image),
             image_pull_policy: value
                 .image_pull_policy
                 .to_owned()
                 .unwrap_or(default.image_pull_policy),
+        })
+    }
+}
+
+/// Configuration of how failed pods of jobs are retried and retained.
+#[derive(Clone, Copy, Debug)]
+pub struct JobRetentionConfig {
+    /// Keep the pod of a failed job for inspection.
+    pub keep_failed_pods: bool,
+    /// Number of retries of a failed pod before the job fails.
+    pub backoff_limit: i32,
+}
+
+impl Default for JobRetentionConfig {
+    fn default() -> Self {
+        Self {
+            keep_failed_pods: false,
+            backoff_limit: 4,
+        }
+    }
+}
+
+impl TryFrom<&SimulationSpec> for JobRetentionConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: &SimulationSpec) -> Result<Self, Self::Error> {
+        if let Some(backoff_limit) = value.backoff_limit {
+            if backoff_limit < 0 {
+                return Err(ConfigError::new(
+                    "backoffLimit",
+                    format!("{backoff_limit} must not be negative"),
+                ));
+            }
+        }
+        let default = Self::default();
+        Ok(Self {
+            keep_failed_pods: value.keep_failed_pods.unwrap_or(default.keep_failed_pods),
+            backoff_limit: value.backoff_limit.unwrap_or(default.backoff_limit),
+        })
+    }
+}
+
+impl JobRetentionConfig {
+    /// Number of retries of a failed pod. Retries are disabled when failed pods are kept so the
+    /// job fails with its first failed pod, which is never replaced or cleaned up by the Job
+    /// controller.
+    pub fn backoff_limit(&self) -> i32 {
+        if self.keep_failed_pods {
+            0
+        } else {
+            self.backoff_limit
         }
     }
 }
+
+/// Configuration of how long jobs may be active before they are failed.
+#[derive(Clone, Copy, Debug)]
+pub struct JobDeadlineConfig {
+    /// Minutes the simulation runs for.
+    pub run_time: u32,
+    /// Minutes a job may be active beyond the run time, covering its setup.
+    pub deadline_buffer: u32,
+}
+
+impl Default for JobDeadlineConfig {
+    fn default() -> Self {
+        Self {
+            run_time: 0,
+            deadline_buffer: 5,
+        }
+    }
+}
+
+impl TryFrom<&SimulationSpec> for JobDeadlineConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: &SimulationSpec) -> Result<Self, Self::Error> {
+        let default = Self::default();
+        Ok(Self {
+            run_time: value.run_time,
+            deadline_buffer: value.deadline_buffer.unwrap_or(default.deadline_buffer),
+        })
+    }
+}
+
+impl JobDeadlineConfig {
+    /// Seconds a job may be active before it is failed, so jobs with hung pods terminate.
+    pub fn active_deadline_seconds(&self) -> i64 {
+        (i64::from(self.run_time) + i64::from(self.deadline_buffer)) * 60
+    }
+}
+
+/// Resource limits of the manager and worker containers. The defaults are generous so the load
+/// generator is not throttled by the default limits of the namespace.
+pub fn job_resource_limits(spec: Option<ResourceLimitsSpec>) -> ResourceLimitsConfig {
+    ResourceLimitsConfig::from_spec(
+        spec,
+        ResourceLimitsConfig {
+            cpu: Quantity("2".to_owned()),
+            memory: Quantity("2Gi".to_owned()),
+            storage: Quantity("1Gi".to_owned()),
+        },
+    )
+}
+
+/// Env vars of the DID the users of the simulation sign their events with.
+/// The private key is read from its secret, the fixed default identity is used when unset.
+pub fn did_env_vars(did: Option<&DidSpec>) -> Vec<EnvVar> {
+    match did {
+        Some(did) => vec![
+            EnvVar {
+                name: "DID_KEY".to_owned(),
+                value: Some(did.key.to_owned()),
+                ..Default::default()
+            },
+            EnvVar {
+                name: "DID_PRIVATE_KEY".to_owned(),
+                value_from: Some(EnvVarSource {
+                    secret_key_ref: Some(SecretKeySelector {
+                        key: did.private_key_secret.key.to_owned(),
+                        name: Some(did.private_key_secret.name.to_owned()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ],
+        None => vec![
+            EnvVar {
+                name: "DID_KEY".to_owned(),
+                value: Some("did:key:z6Mkqn5jbycThHcBtakJZ8fHBQ2oVRQhXQEdQk5ZK2NDtNZA".to_owned()),
+                ..Default::default()
+            },
+            EnvVar {
+                name: "DID_PRIVATE_KEY".to_owned(),
+                value: Some(
+                    "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a".to_owned(),
+                ),
+                ..Default::default()
+            },
+        ],
+    }
+}
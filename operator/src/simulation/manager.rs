@@ -1,15 +1,34 @@
 use std::collections::BTreeMap;
 
-use k8s_openapi::api::{
-    batch::v1::JobSpec,
-    core::v1::{
-        ConfigMapVolumeSource, Container, EnvVar, PodSpec, PodTemplateSpec, ServicePort,
-        ServiceSpec, Volume, VolumeMount,
+use k8s_openapi::{
+    api::{
+        apps::v1::DeploymentSpec,
+        batch::v1::JobSpec,
+        core::v1::{
+            Container, EnvVar, PodSpec, PodTemplateSpec, ResourceRequirements, ServicePort,
+            ServiceSpec, VolumeMount,
+        },
     },
+    apimachinery::pkg::apis::meta::v1::LabelSelector,
 };
 use kube::core::ObjectMeta;
 
-use crate::{network::PEERS_CONFIG_MAP_NAME, simulation::job::JobImageConfig};
+use crate::{
+    labels::{insert_revision_label, validate_revision},
+    network::{peers::peers_volume, resource_limits::ResourceLimitsConfig},
+    simulation::{
+        controller::{MANAGER_HOSTNAME, MANAGER_SERVICE_NAME},
+        job::{
+            did_env_vars, job_resource_limits, JobDeadlineConfig, JobImageConfig,
+            JobRetentionConfig, DEFAULT_OTLP_ENDPOINT,
+        },
+        DidSpec, SimulationSpec,
+    },
+    utils::ConfigError,
+};
+
+/// Think time distributions supported by the runner.
+const THINK_TIMES: [&str; 3] = ["uniform", "constant", "pareto"];
 
 pub fn service_spec() -> ServiceSpec {
     ServiceSpec {
@@ -28,20 +47,181 @@ pub fn service_spec() -> ServiceSpec {
 }
 
 // ManagerConfig defines which properties of the JobSpec can be customized.
+#[derive(Clone)]
 pub struct ManagerConfig {
     pub scenario: String,
     pub users: u32,
     pub run_time: u32,
     pub throttle_requests: Option<usize>,
+    pub min_throughput: Option<f64>,
+    pub max_error_rate: Option<f64>,
+    pub target_spec: Option<String>,
+    pub read_ratio: Option<u32>,
+    pub did: Option<DidSpec>,
+    pub seed: Option<u64>,
+    pub wait_time_min: Option<u64>,
+    pub wait_time_max: Option<u64>,
+    pub think_time: Option<String>,
+    pub think_time_shape: Option<f64>,
+    pub revision: Option<String>,
+    pub otlp_endpoint: Option<String>,
     pub nonce: u32,
     pub job_image_config: JobImageConfig,
+    pub job_retention_config: JobRetentionConfig,
+    pub job_deadline_config: JobDeadlineConfig,
+    pub resource_limits: ResourceLimitsConfig,
+}
+
+/// Converts the spec into a manager config with a zero nonce,
+/// the nonce is part of the simulation status and is set by the controller.
+impl TryFrom<&SimulationSpec> for ManagerConfig {
+    type Error = ConfigError;
+
+    fn try_from(value: &SimulationSpec) -> Result<Self, Self::Error> {
+        if let Some(read_ratio) = value.read_ratio {
+            if read_ratio > 100 {
+                return Err(ConfigError::new(
+                    "readRatio",
+                    format!("{read_ratio} is not a percentage"),
+                ));
+            }
+        }
+        if let Some(min_throughput) = value.min_throughput {
+            if min_throughput < 0.0 {
+                return Err(ConfigError::new(
+                    "minThroughput",
+                    format!("{min_throughput} must not be negative"),
+                ));
+            }
+        }
+        if let Some(max_error_rate) = value.max_error_rate {
+            if !(0.0..=1.0).contains(&max_error_rate) {
+                return Err(ConfigError::new(
+                    "maxErrorRate",
+                    format!("{max_error_rate} is not between 0.0 and 1.0"),
+                ));
+            }
+        }
+        if let Some(think_time) = &value.think_time {
+            if !THINK_TIMES.contains(&think_time.as_str()) {
+                return Err(ConfigError::new(
+                    "thinkTime",
+                    format!(
+                        "unknown think time {think_time}, expected uniform, constant or pareto"
+                    ),
+                ));
+            }
+        }
+        if let Some(think_time_shape) = value.think_time_shape {
+            if think_time_shape <= 0.0 {
+                return Err(ConfigError::new(
+                    "thinkTimeShape",
+                    format!("{think_time_shape} must be positive"),
+                ));
+            }
+        }
+        if let Some(revision) = &value.revision {
+            validate_revision(revision)?;
+        }
+        if let Some(did) = &value.did {
+            if !did.key.starts_with("did:key:") {
+                return Err(ConfigError::new(
+                    "did",
+                    format!("{} is not a did:key", did.key),
+                ));
+            }
+            if did.private_key_secret.name.is_empty() || did.private_key_secret.key.is_empty() {
+                return Err(ConfigError::new(
+                    "did",
+                    "privateKeySecret must name a secret and its key",
+                ));
+            }
+        }
+        if let Some(otlp_endpoint) = &value.otlp_endpoint {
+            if let Err(err) = reqwest::Url::parse(otlp_endpoint) {
+                return Err(ConfigError::new(
+                    "otlpEndpoint",
+                    format!("{otlp_endpoint} is not a valid URL: {err}"),
+                ));
+            }
+        }
+        if let (Some(min), Some(max)) = (value.wait_time_min, value.wait_time_max) {
+            if min > max {
+                return Err(ConfigError::new(
+                    "waitTimeMin",
+                    format!("{min} is greater than waitTimeMax {max}"),
+                ));
+            }
+        }
+        Ok(Self {
+            scenario: value.scenario.to_owned(),
+            users: value.users,
+            run_time: value.run_time,
+            throttle_requests: value.throttle_requests,
+            min_throughput: value.min_throughput,
+            max_error_rate: value.max_error_rate,
+            target_spec: value.target_spec.clone(),
+            read_ratio: value.read_ratio,
+            did: value.did.clone(),
+            seed: value.seed,
+            wait_time_min: value.wait_time_min,
+            wait_time_max: value.wait_time_max,
+            think_time: value.think_time.clone(),
+            think_time_shape: value.think_time_shape,
+            revision: value.revision.clone(),
+            otlp_endpoint: value.otlp_endpoint.clone(),
+            nonce: 0,
+            job_image_config: JobImageConfig::try_from(value)?,
+            job_retention_config: JobRetentionConfig::try_from(value)?,
+            job_deadline_config: JobDeadlineConfig::try_from(value)?,
+            resource_limits: job_resource_limits(value.resource_limits.clone()),
+        })
+    }
 }
 
 pub fn manager_job_spec(config: ManagerConfig) -> JobSpec {
+    let run_time = format!("{}m", config.run_time);
+    JobSpec {
+        active_deadline_seconds: Some(config.job_deadline_config.active_deadline_seconds()),
+        backoff_limit: Some(config.job_retention_config.backoff_limit()),
+        template: pod_template_spec(config, "Never", run_time),
+        ..Default::default()
+    }
+}
+
+/// Spec of a deployment keeping the manager running until the simulation is deleted.
+pub fn manager_deployment_spec(config: ManagerConfig) -> DeploymentSpec {
+    DeploymentSpec {
+        replicas: Some(1),
+        selector: LabelSelector {
+            match_labels: Some(manager_labels()),
+            ..Default::default()
+        },
+        // Goose runs without a time limit when the run time is zero, otherwise the manager
+        // would exit after the run time and be restarted over and over.
+        template: pod_template_spec(config, "Always", "0".to_owned()),
+        ..Default::default()
+    }
+}
+
+fn manager_labels() -> BTreeMap<String, String> {
+    BTreeMap::from_iter(vec![("name".to_owned(), "goose".to_owned())])
+}
+
+fn pod_template_spec(
+    config: ManagerConfig,
+    restart_policy: &str,
+    run_time: String,
+) -> PodTemplateSpec {
     let mut env_vars = vec![
         EnvVar {
             name: "RUNNER_OTLP_ENDPOINT".to_owned(),
-            value: Some("http://otel:4317".to_owned()),
+            value: Some(
+                config
+                    .otlp_endpoint
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_OTLP_ENDPOINT.to_owned()),
+            ),
             ..Default::default()
         },
         EnvVar {
@@ -81,22 +261,11 @@ pub fn manager_job_spec(config: ManagerConfig) -> JobSpec {
         },
         EnvVar {
             name: "SIMULATE_RUN_TIME".to_owned(),
-            value: Some(format!("{}m", config.run_time)),
-            ..Default::default()
-        },
-        EnvVar {
-            name: "DID_KEY".to_owned(),
-            value: Some("did:key:z6Mkqn5jbycThHcBtakJZ8fHBQ2oVRQhXQEdQk5ZK2NDtNZA".to_owned()),
-            ..Default::default()
-        },
-        EnvVar {
-            name: "DID_PRIVATE_KEY".to_owned(),
-            value: Some(
-                "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a".to_owned(),
-            ),
+            value: Some(run_time),
             ..Default::default()
         },
     ];
+    env_vars.extend(did_env_vars(config.did.as_ref()));
     if let Some(throttle_requests) = config.throttle_requests {
         env_vars.push(EnvVar {
             name: "SIMULATE_THROTTLE_REQUESTS".to_owned(),
@@ -104,48 +273,228 @@ pub fn manager_job_spec(config: ManagerConfig) -> JobSpec {
             ..Default::default()
         })
     }
-    JobSpec {
-        backoff_limit: Some(4),
-        template: PodTemplateSpec {
-            metadata: Some(ObjectMeta {
-                labels: Some(BTreeMap::from_iter(vec![(
-                    "name".to_owned(),
-                    "goose".to_owned(),
-                )])),
-                ..Default::default()
-            }),
-            spec: Some(PodSpec {
-                hostname: Some("manager".to_owned()),
-                subdomain: Some("goose".to_owned()),
-                containers: vec![Container {
-                    name: "manager".to_owned(),
-                    image: Some(config.job_image_config.image),
-                    image_pull_policy: Some(config.job_image_config.image_pull_policy),
-                    command: Some(vec![
-                        "/usr/bin/keramik-runner".to_owned(),
-                        "simulate".to_owned(),
-                    ]),
-                    env: Some(env_vars),
-                    volume_mounts: Some(vec![VolumeMount {
-                        mount_path: "/keramik-peers".to_owned(),
-                        name: "keramik-peers".to_owned(),
-                        ..Default::default()
-                    }]),
+    if let Some(min_throughput) = config.min_throughput {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_MIN_THROUGHPUT".to_owned(),
+            value: Some(min_throughput.to_string()),
+            ..Default::default()
+        })
+    }
+    if let Some(max_error_rate) = config.max_error_rate {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_MAX_ERROR_RATE".to_owned(),
+            value: Some(max_error_rate.to_string()),
+            ..Default::default()
+        })
+    }
+    if let Some(target_spec) = config.target_spec {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_TARGET_SPEC".to_owned(),
+            value: Some(target_spec),
+            ..Default::default()
+        })
+    }
+    if let Some(wait_time_min) = config.wait_time_min {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_WAIT_TIME_MIN".to_owned(),
+            value: Some(wait_time_min.to_string()),
+            ..Default::default()
+        })
+    }
+    if let Some(wait_time_max) = config.wait_time_max {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_WAIT_TIME_MAX".to_owned(),
+            value: Some(wait_time_max.to_string()),
+            ..Default::default()
+        })
+    }
+    if let Some(think_time) = config.think_time {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_THINK_TIME".to_owned(),
+            value: Some(think_time),
+            ..Default::default()
+        })
+    }
+    if let Some(think_time_shape) = config.think_time_shape {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_THINK_TIME_SHAPE".to_owned(),
+            value: Some(think_time_shape.to_string()),
+            ..Default::default()
+        })
+    }
+    if let Some(seed) = config.seed {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_SEED".to_owned(),
+            value: Some(seed.to_string()),
+            ..Default::default()
+        })
+    }
+    if let Some(read_ratio) = config.read_ratio {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_READ_RATIO".to_owned(),
+            value: Some(read_ratio.to_string()),
+            ..Default::default()
+        })
+    }
+    if let Some(revision) = &config.revision {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_REVISION".to_owned(),
+            value: Some(revision.clone()),
+            ..Default::default()
+        })
+    }
+    let mut labels = manager_labels();
+    insert_revision_label(&mut labels, config.revision.as_deref());
+    PodTemplateSpec {
+        metadata: Some(ObjectMeta {
+            labels: Some(labels),
+            ..Default::default()
+        }),
+        spec: Some(PodSpec {
+            hostname: Some(MANAGER_HOSTNAME.to_owned()),
+            subdomain: Some(MANAGER_SERVICE_NAME.to_owned()),
+            containers: vec![Container {
+                name: "manager".to_owned(),
+                image: Some(config.job_image_config.image),
+                image_pull_policy: Some(config.job_image_config.image_pull_policy),
+                command: Some(vec![
+                    "/usr/bin/keramik-runner".to_owned(),
+                    "simulate".to_owned(),
+                ]),
+                env: Some(env_vars),
+                resources: Some(ResourceRequirements {
+                    limits: Some(config.resource_limits.clone().into()),
+                    requests: Some(config.resource_limits.into()),
                     ..Default::default()
-                }],
-                volumes: Some(vec![Volume {
-                    config_map: Some(ConfigMapVolumeSource {
-                        default_mode: Some(0o755),
-                        name: Some(PEERS_CONFIG_MAP_NAME.to_owned()),
-                        ..Default::default()
-                    }),
+                }),
+                volume_mounts: Some(vec![VolumeMount {
+                    mount_path: "/keramik-peers".to_owned(),
                     name: "keramik-peers".to_owned(),
                     ..Default::default()
                 }]),
-                restart_policy: Some("Never".to_owned()),
                 ..Default::default()
-            }),
-        },
-        ..Default::default()
+            }],
+            volumes: Some(vec![peers_volume()]),
+            restart_policy: Some(restart_policy.to_owned()),
+            ..Default::default()
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{network::SecretKeySpec, simulation::ManagerKind};
+
+    #[test]
+    fn manager_config_from_spec() {
+        let config = ManagerConfig::try_from(&SimulationSpec {
+            scenario: "ceramic-read-write".to_owned(),
+            users: 10,
+            run_time: 5,
+            read_ratio: Some(50),
+            wait_time_min: Some(1000),
+            wait_time_max: Some(1000),
+            manager_kind: Some(ManagerKind::Deployment),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!("ceramic-read-write", config.scenario);
+        assert_eq!(10, config.users);
+        assert_eq!(Some(50), config.read_ratio);
+        assert_eq!(600, config.job_deadline_config.active_deadline_seconds());
+    }
+
+    #[test]
+    fn invalid_manager_specs() {
+        let error = |spec: SimulationSpec| ManagerConfig::try_from(&spec).err().unwrap().field;
+        assert_eq!(
+            "readRatio",
+            error(SimulationSpec {
+                read_ratio: Some(101),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            "minThroughput",
+            error(SimulationSpec {
+                min_throughput: Some(-1.0),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            "maxErrorRate",
+            error(SimulationSpec {
+                max_error_rate: Some(1.5),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            "backoffLimit",
+            error(SimulationSpec {
+                backoff_limit: Some(-1),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            "waitTimeMin",
+            error(SimulationSpec {
+                wait_time_min: Some(2000),
+                wait_time_max: Some(1000),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            "thinkTime",
+            error(SimulationSpec {
+                think_time: Some("normal".to_owned()),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            "thinkTimeShape",
+            error(SimulationSpec {
+                think_time_shape: Some(0.0),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            "revision",
+            error(SimulationSpec {
+                revision: Some("0a1b2c3/dirty".to_owned()),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            "did",
+            error(SimulationSpec {
+                did: Some(DidSpec {
+                    key: "z6Mkqn5jbycThHcBtakJZ8fHBQ2oVRQhXQEdQk5ZK2NDtNZA".to_owned(),
+                    private_key_secret: SecretKeySpec {
+                        name: "simulation-did".to_owned(),
+                        key: "private-key".to_owned(),
+                    },
+                }),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            "did",
+            error(SimulationSpec {
+                did: Some(DidSpec {
+                    key: "did:key:z6Mkqn5jbycThHcBtakJZ8fHBQ2oVRQhXQEdQk5ZK2NDtNZA".to_owned(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            "otlpEndpoint",
+            error(SimulationSpec {
+                otlp_endpoint: Some("collector".to_owned()),
+                ..Default::default()
+            })
+        );
     }
 }
@@ -1,7 +1,11 @@
+use std::collections::BTreeMap;
+
 use kube::CustomResource;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::network::{ResourceLimitsSpec, SecretKeySpec};
+
 /// Primary CRD for creating and managing a Ceramic Simulation.
 #[derive(CustomResource, Serialize, Deserialize, Debug, Default, PartialEq, Clone, JsonSchema)]
 #[kube(
@@ -27,13 +31,213 @@ pub struct SimulationSpec {
     pub image_pull_policy: Option<String>,
     /// Throttle requests (per second) for a simulation
     pub throttle_requests: Option<usize>,
+    /// OpenTelemetry collector endpoint the manager and workers export their telemetry to,
+    /// e.g. an external collector. Defaults to the collector of the simulation, http://otel:4317.
+    pub otlp_endpoint: Option<String>,
+    /// Minimum time in milliseconds users of the `ceramic-write-only` scenario wait for after
+    /// each transaction. Defaults to 9000.
+    pub wait_time_min: Option<u64>,
+    /// Maximum time in milliseconds users of the `ceramic-write-only` scenario wait for after
+    /// each transaction. Defaults to 11000.
+    pub wait_time_max: Option<u64>,
+    /// Distribution of the time users think for after each transaction, between the wait times
+    /// of the scenario. One of `uniform`, `constant` to always think for the minimum and
+    /// `pareto` for long tailed think times starting at the minimum. Defaults to `uniform`.
+    pub think_time: Option<String>,
+    /// Shape of the `pareto` think time, lower shapes give longer tails. Must be positive,
+    /// defaults to 1.16 following the 80-20 rule.
+    pub think_time_shape: Option<f64>,
+    /// Tag the requests of each worker with its target peer, so the report of the simulation
+    /// breaks the latency of the requests down per peer. Defaults to false.
+    pub tag_peer: Option<bool>,
+    /// Revision of the code under test, e.g. a git SHA, stamped on the manager and worker pods
+    /// as the `keramik.3box.io/revision` label and passed to the runner, which adds it to the
+    /// attributes of its metrics.
+    pub revision: Option<String>,
+    /// Seed of the randomness of the simulation, i.e. its nonce and the data generated by the
+    /// scenario, making a simulation reproducible. The simulation is random when unset.
+    pub seed: Option<u64>,
+    /// Percentage of the transactions of the `ceramic-read-write` scenario that read model
+    /// instances, the others update them. Defaults to 80.
+    pub read_ratio: Option<u32>,
+    /// Minimum throughput (requests per second) the simulation must reach to succeed.
+    /// Throughput is not checked when unset.
+    pub min_throughput: Option<f64>,
+    /// Maximum ratio of failed requests (0.0 - 1.0) the simulation may have to succeed.
+    /// Error rate is not checked when unset.
+    pub max_error_rate: Option<f64>,
+    /// Deploy Grafana with preloaded dashboards alongside the simulation.
+    /// Grafana is not deployed when unset.
+    pub grafana: Option<GrafanaSpec>,
+    /// Number of documents each user creates before the measured phase of the scenario.
+    /// The network is not seeded when unset.
+    pub seed_documents: Option<u32>,
+    /// Models of the seeded documents, any of `small` and `large`.
+    /// Documents are spread evenly across the models. Defaults to `small`.
+    pub seed_models: Option<Vec<String>>,
+    /// Keep the pods of failed manager and worker jobs for inspection instead of retrying them.
+    /// Defaults to false.
+    pub keep_failed_pods: Option<bool>,
+    /// Number of retries of failed manager and worker pods before their job fails, e.g. 0 to
+    /// fail fast. Defaults to 4, retries are always disabled when `keepFailedPods` is set.
+    pub backoff_limit: Option<i32>,
+    /// Minutes the manager and worker jobs may be active beyond `runTime` before they are
+    /// failed, so a run with hung pods fails instead of never finishing. Defaults to 5.
+    pub deadline_buffer: Option<u32>,
+    /// Resource limits of the manager and worker pods, also used as their requests.
+    /// Defaults to 2 cpu, 2Gi of memory and 1Gi of ephemeral storage.
+    pub resource_limits: Option<ResourceLimitsSpec>,
+    /// Wait for the peers of the network to be ready and connected to each other before
+    /// starting the manager and worker jobs. Defaults to false.
+    pub wait_for_bootstrap: Option<bool>,
+    /// Validate the peers of the network before starting the manager and worker jobs, failing
+    /// the simulation when the peers are malformed and waiting while none are targeted.
+    /// Defaults to false.
+    pub validate_peers: Option<bool>,
+    /// Only target the peers created from the ceramic spec with this stateful set name,
+    /// e.g. `ceramic-1` for the second ceramic spec of the network. Targets all peers when unset.
+    pub target_spec: Option<String>,
+    /// Scrape metrics in the OpenMetrics format and store their exemplars, linking the metrics
+    /// to the traces collected by Jaeger. Defaults to false.
+    pub open_metrics: Option<bool>,
+    /// Number of models registered by the `ceramic-catalog` scenario. Limits the models of the
+    /// catalog definition when one is given. Defaults to 10 generated models.
+    pub catalog_size: Option<u32>,
+    /// Name of a config map holding the catalog definition of the `ceramic-catalog` scenario
+    /// under the `catalog.json` key. A catalog is generated when unset.
+    pub catalog_config_map: Option<String>,
+    /// Length of the description of the large model instances written by the scenarios, e.g.
+    /// to probe the block size limits of IPFS. Defaults to 1000.
+    pub large_model_size: Option<u32>,
+    /// Number of users of the workers targeting specific peers, keyed by the index of the peer.
+    /// Workers without an override get an even split of the users.
+    pub worker_users: Option<BTreeMap<u32, u32>>,
+    /// Configuration of the monitoring of the simulation.
+    pub monitoring: Option<MonitoringSpec>,
+    /// Kind of the resource running the manager. A deployment keeps the manager running
+    /// indefinitely, e.g. for soak simulations. Defaults to `job`.
+    pub manager_kind: Option<ManagerKind>,
+    /// Whether the manager and worker jobs are deleted once the simulation has an outcome.
+    /// Defaults to `never` so the logs of the jobs remain available.
+    pub cleanup_policy: Option<CleanupPolicy>,
+    /// Identity the users of the simulation create their streams with. Simulations sharing a
+    /// network need distinct identities to avoid stream ownership collisions.
+    /// Defaults to a fixed identity shared by all simulations.
+    pub did: Option<DidSpec>,
+}
+
+/// Describes the DID the simulation users sign their events with.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DidSpec {
+    /// The did:key of the identity.
+    pub key: String,
+    /// Reference to the hex encoded private key of the identity within a secret of the
+    /// simulation namespace.
+    pub private_key_secret: SecretKeySpec,
+}
+
+/// Describes how Grafana should be deployed.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GrafanaSpec {
+    /// Image of the Grafana container.
+    pub image: Option<String>,
+    /// Pull policy for the Grafana image.
+    pub image_pull_policy: Option<String>,
+    /// Role of anonymous users within the Grafana organization. Defaults to `Viewer`, so anyone
+    /// reaching the service can browse but not edit the datasources and dashboards.
+    pub anonymous_role: Option<GrafanaRole>,
+}
+
+/// Organization role granted to the anonymous users of Grafana.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy, JsonSchema)]
+pub enum GrafanaRole {
+    /// Browse dashboards.
+    #[default]
+    Viewer,
+    /// Browse and edit dashboards.
+    Editor,
+    /// Manage the whole organization, including its datasources.
+    Admin,
+}
+
+/// Describes how the simulation is monitored.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitoringSpec {
+    /// Create a config map holding the simulation dashboards, labeled with `grafana_dashboard: "1"`
+    /// so the Grafana sidecar of kube-prometheus-stack imports them. Defaults to false.
+    pub emit_grafana_dashboard: Option<bool>,
+    /// Labels of the service of an existing OpenTelemetry collector, e.g. of a cluster-wide
+    /// monitoring stack. When set the collector is discovered in any namespace instead of
+    /// deploying Jaeger, Prometheus and a collector for the simulation, and the manager and
+    /// workers export their telemetry to it unless `otlpEndpoint` is set.
+    pub collector_selector: Option<BTreeMap<String, String>>,
+    /// Interval between the scrapes of Prometheus, e.g. `30s`. Defaults to `10s`.
+    pub scrape_interval: Option<String>,
+    /// How long Prometheus keeps its samples, e.g. `7d` to cover a long soak test.
+    /// Defaults to `15d`, the default of Prometheus.
+    pub retention: Option<String>,
+}
+
+/// Kind of the resource running the simulation manager.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ManagerKind {
+    /// Run the manager as a job, completing with the simulation after its run time.
+    #[default]
+    Job,
+    /// Run the manager as a deployment without a run time, until the simulation is deleted.
+    Deployment,
+}
+
+/// Describes which finished simulations have their jobs deleted.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum CleanupPolicy {
+    /// Keep the jobs of every simulation.
+    #[default]
+    Never,
+    /// Delete the jobs of succeeded simulations, keeping failed jobs for inspection.
+    OnSuccess,
+    /// Delete the jobs of every simulation.
+    Always,
 }
 
 /// Current status of a simulation.
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SimulationStatus {
     /// Unique value for this simulation.
     /// Used to enable determisitically psuedo-random values during any simulation logic.
     pub nonce: u32,
+    /// Phase of the simulation. Once it has succeeded or failed, the simulation is only
+    /// reconciled to delete its jobs under the cleanup policy.
+    #[serde(default)]
+    pub phase: SimulationPhase,
+    /// Number of workers that completed successfully.
+    #[serde(default)]
+    pub completed_workers: u32,
+    /// Number of workers of the simulation, one for each targeted peer.
+    #[serde(default)]
+    pub total_workers: u32,
+    /// Error that failed the simulation before it started, e.g. an invalid spec.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Phase of a simulation.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum SimulationPhase {
+    /// The manager is not running yet.
+    #[default]
+    Pending,
+    /// The manager is running and the workers are started.
+    Running,
+    /// The manager and all workers completed successfully.
+    Succeeded,
+    /// The manager or any of the workers failed.
+    Failed,
 }
@@ -5,15 +5,16 @@ use std::collections::BTreeMap;
 use expect_patch::ExpectPatch;
 use expect_test::{expect_file, ExpectFile};
 use k8s_openapi::api::{
-    apps::v1::{StatefulSet, StatefulSetStatus},
-    batch::v1::{Job, JobStatus},
-    core::v1::ConfigMap,
+    apps::v1::{Deployment, StatefulSet, StatefulSetStatus},
+    batch::v1::{Job, JobList, JobStatus},
+    core::v1::{ConfigMap, ServiceList},
 };
 use keramik_common::peer_info::{CeramicPeerInfo, Peer};
 use kube::Resource;
 use tokio::task::JoinHandle;
 
 use crate::{
+    network::Network,
     simulation::{Simulation, SimulationSpec, SimulationStatus},
     utils::test::{ApiServerVerifier, WithStatus},
 };
@@ -25,7 +26,10 @@ impl Simulation {
         let mut sim = Simulation::new("test", SimulationSpec::default());
         let meta = sim.meta_mut();
         meta.namespace = Some("test".to_owned());
-        sim.with_status(SimulationStatus { nonce: 42 })
+        sim.with_status(SimulationStatus {
+            nonce: 42,
+            ..Default::default()
+        })
     }
     /// Modify a network to have an expected spec
     pub fn with_spec(self, spec: SimulationSpec) -> Self {
@@ -68,23 +72,49 @@ pub struct Stub {
     pub otel_config: ExpectPatch<ExpectFile>,
     pub otel_service: ExpectPatch<ExpectFile>,
     pub otel_stateful_set: ExpectPatch<ExpectFile>,
+    pub grafana: Vec<ExpectPatch<ExpectFile>>,
+    pub grafana_dashboard: Option<ExpectPatch<ExpectFile>>,
 
     pub jaeger_status: (ExpectPatch<ExpectFile>, StatefulSet),
     pub prom_status: (ExpectPatch<ExpectFile>, StatefulSet),
     pub otel_status: (ExpectPatch<ExpectFile>, StatefulSet),
+    /// Services listed when discovering an existing collector, expected instead of the
+    /// monitoring stack and its statuses when set.
+    pub collector: Option<(ExpectPatch<ExpectFile>, ServiceList)>,
+    /// Grafana status request and response, only expected when Grafana is deployed.
+    pub grafana_status: Option<(ExpectPatch<ExpectFile>, StatefulSet)>,
 
     pub redis_service: ExpectPatch<ExpectFile>,
     pub redis_stateful_set: ExpectPatch<ExpectFile>,
     pub redis_status: (ExpectPatch<ExpectFile>, StatefulSet),
 
+    /// Networks listed when the simulation waits for the network to bootstrap.
+    pub networks: Option<(ExpectPatch<ExpectFile>, Vec<Network>)>,
+    /// When false reconciliation stops after listing the networks.
+    pub mesh_ready: bool,
+
     pub goose_service: ExpectPatch<ExpectFile>,
     pub manager_job: ExpectPatch<ExpectFile>,
 
     pub manager_status: (ExpectPatch<ExpectFile>, Job),
+    /// Manager deployment, its status request and response, expected instead of the manager job
+    /// when set.
+    pub manager_deployment: Option<(ExpectPatch<ExpectFile>, ExpectPatch<ExpectFile>, Deployment)>,
 
     pub worker_jobs: Vec<ExpectPatch<ExpectFile>>,
+    pub worker_jobs_list: (ExpectPatch<ExpectFile>, JobList),
+    pub worker_job_deletes: Vec<ExpectPatch<ExpectFile>>,
 
     pub status: ExpectPatch<ExpectFile>,
+    /// Status reporting an invalid spec, when set it is the only expected request.
+    pub error: (ExpectPatch<ExpectFile>, bool),
+    /// Whether the peers fail validation, reporting the error right after reading the peers.
+    pub invalid_peers: bool,
+    /// Whether no peers are targeted yet, stopping reconciliation right after reading the peers.
+    pub no_peers: bool,
+    /// Deletion of the jobs of an already finished simulation, when set these are the only
+    /// expected requests.
+    pub finished_job_deletes: Option<Vec<ExpectPatch<ExpectFile>>>,
 }
 
 // Implement default stub that defines two peers and all statuses are immediately ready.
@@ -101,12 +131,14 @@ impl Default for Stub {
                             ipfs_rpc_addr: "ipfs_rpc_addr_0".to_owned(),
                             ceramic_addr: "ceramic_addr_0".to_owned(),
                             p2p_addrs: vec!["p2p_addr_0".to_owned(), "p2p_addr_1".to_owned()],
+                            ..Default::default()
                         }),
                         Peer::Ceramic(CeramicPeerInfo {
                             peer_id: "1".to_owned(),
                             ipfs_rpc_addr: "ipfs_rpc_addr_1".to_owned(),
                             ceramic_addr: "ceramic_addr_1".to_owned(),
                             p2p_addrs: vec!["p2p_addr_0".to_owned(), "p2p_addr_1".to_owned()],
+                            ..Default::default()
                         }),
                     ];
 
@@ -140,6 +172,8 @@ impl Default for Stub {
             otel_config: expect_file!["./testdata/default_stubs/otel_config"].into(),
             otel_service: expect_file!["./testdata/default_stubs/otel_service"].into(),
             otel_stateful_set: expect_file!["./testdata/default_stubs/otel_stateful_set"].into(),
+            grafana: Vec::new(),
+            grafana_dashboard: None,
             jaeger_status: (
                 expect_file!["./testdata/default_stubs/jaeger_status"].into(),
                 StatefulSet {
@@ -170,6 +204,8 @@ impl Default for Stub {
                     ..Default::default()
                 },
             ),
+            collector: None,
+            grafana_status: None,
             redis_status: (
                 expect_file!["./testdata/default_stubs/redis_status"].into(),
                 StatefulSet {
@@ -180,6 +216,8 @@ impl Default for Stub {
                     ..Default::default()
                 },
             ),
+            networks: None,
+            mesh_ready: true,
             goose_service: expect_file!["./testdata/default_stubs/goose_service"].into(),
             manager_job: expect_file!["./testdata/default_stubs/manager_job"].into(),
             manager_status: (
@@ -192,11 +230,34 @@ impl Default for Stub {
                     ..Default::default()
                 },
             ),
+            manager_deployment: None,
             worker_jobs: vec![
                 expect_file!["./testdata/default_stubs/worker_job_0"].into(),
                 expect_file!["./testdata/default_stubs/worker_job_1"].into(),
             ],
+            worker_jobs_list: (
+                expect_file!["./testdata/default_stubs/worker_jobs_list"].into(),
+                JobList {
+                    items: vec!["simulate-manager", "simulate-worker-0", "simulate-worker-1"]
+                        .into_iter()
+                        .map(|name| {
+                            let mut job = Job::default();
+                            job.meta_mut().name = Some(name.to_owned());
+                            job
+                        })
+                        .collect(),
+                    ..Default::default()
+                },
+            ),
+            worker_job_deletes: Vec::new(),
             status: expect_file!["./testdata/default_stubs/status"].into(),
+            error: (
+                expect_file!["./testdata/default_stubs/error_status"].into(),
+                false,
+            ),
+            invalid_peers: false,
+            no_peers: false,
+            finished_job_deletes: None,
         }
     }
 }
@@ -218,67 +279,125 @@ impl Stub {
         tokio::spawn(async move {
             // We need to handle each expected call in sequence
 
+            if let Some(deletes) = self.finished_job_deletes {
+                for d in deletes {
+                    fakeserver
+                        .handle_request_response(d, Some(&Job::default()))
+                        .await
+                        .expect("job should delete");
+                }
+                return;
+            }
+
+            if self.error.1 {
+                fakeserver
+                    .handle_patch_status(self.error.0, self.simulation.clone())
+                    .await
+                    .expect("should be able to report the error");
+                return;
+            }
+
             // First we handle the call to get the peers config map.
             fakeserver
                 .handle_request_response(self.peers_config_map.0, Some(&self.peers_config_map.1))
                 .await
                 .expect("peers_config_map should be reported");
 
-            // Next we handle a sequence of apply calls
-            fakeserver
-                .handle_apply(self.jaeger_service)
-                .await
-                .expect("jaeger service should apply");
-            fakeserver
-                .handle_apply(self.jaeger_stateful_set)
-                .await
-                .expect("jaeger stateful set should apply");
-            fakeserver
-                .handle_apply(self.prom_config)
-                .await
-                .expect("prom-config configmap should apply");
-            fakeserver
-                .handle_apply(self.prom_stateful_set)
-                .await
-                .expect("prom stateful set should apply");
-            fakeserver
-                .handle_apply(self.monitoring_service_account)
-                .await
-                .expect("monitoring service account should apply");
-            fakeserver
-                .handle_apply(self.monitoring_cluster_role)
-                .await
-                .expect("monitoring cluster role should apply");
-            fakeserver
-                .handle_apply(self.monitoring_cluster_role_binding)
-                .await
-                .expect("monitoring cluster role binding should apply");
-            fakeserver
-                .handle_apply(self.otel_config)
-                .await
-                .expect("otel config map should apply");
-            fakeserver
-                .handle_apply(self.otel_service)
-                .await
-                .expect("otel service should apply");
-            fakeserver
-                .handle_apply(self.otel_stateful_set)
-                .await
-                .expect("otel stateful set should apply");
+            if self.invalid_peers {
+                fakeserver
+                    .handle_patch_status(self.error.0, self.simulation.clone())
+                    .await
+                    .expect("should be able to report the invalid peers");
+                return;
+            }
+            if self.no_peers {
+                return;
+            }
 
-            // Next we handle a sequence of status calls for various services
-            fakeserver
-                .handle_request_response(self.jaeger_status.0, Some(&self.jaeger_status.1))
-                .await
-                .expect("should report jaeger status");
-            fakeserver
-                .handle_request_response(self.prom_status.0, Some(&self.prom_status.1))
-                .await
-                .expect("should report jaeger status");
-            fakeserver
-                .handle_request_response(self.otel_status.0, Some(&self.otel_status.1))
-                .await
-                .expect("should report jaeger status");
+            let discover_collector = self.collector.is_some();
+            if let Some(collector) = self.collector {
+                // An existing collector is discovered instead of deploying the monitoring stack
+                fakeserver
+                    .handle_request_response(collector.0, Some(&collector.1))
+                    .await
+                    .expect("collector services should be listed");
+            } else {
+                // Next we handle a sequence of apply calls
+                fakeserver
+                    .handle_apply(self.jaeger_service)
+                    .await
+                    .expect("jaeger service should apply");
+                fakeserver
+                    .handle_apply(self.jaeger_stateful_set)
+                    .await
+                    .expect("jaeger stateful set should apply");
+                fakeserver
+                    .handle_apply(self.prom_config)
+                    .await
+                    .expect("prom-config configmap should apply");
+                fakeserver
+                    .handle_apply(self.prom_stateful_set)
+                    .await
+                    .expect("prom stateful set should apply");
+                fakeserver
+                    .handle_apply(self.monitoring_service_account)
+                    .await
+                    .expect("monitoring service account should apply");
+                fakeserver
+                    .handle_apply(self.monitoring_cluster_role)
+                    .await
+                    .expect("monitoring cluster role should apply");
+                fakeserver
+                    .handle_apply(self.monitoring_cluster_role_binding)
+                    .await
+                    .expect("monitoring cluster role binding should apply");
+                fakeserver
+                    .handle_apply(self.otel_config)
+                    .await
+                    .expect("otel config map should apply");
+                fakeserver
+                    .handle_apply(self.otel_service)
+                    .await
+                    .expect("otel service should apply");
+                fakeserver
+                    .handle_apply(self.otel_stateful_set)
+                    .await
+                    .expect("otel stateful set should apply");
+            }
+            for g in self.grafana {
+                fakeserver
+                    .handle_apply(g)
+                    .await
+                    .expect("grafana resources should apply");
+            }
+            if let Some(grafana_dashboard) = self.grafana_dashboard {
+                fakeserver
+                    .handle_apply(grafana_dashboard)
+                    .await
+                    .expect("grafana dashboard config map should apply");
+            }
+
+            if !discover_collector {
+                // Next we handle a sequence of status calls for various services
+                fakeserver
+                    .handle_request_response(self.jaeger_status.0, Some(&self.jaeger_status.1))
+                    .await
+                    .expect("should report jaeger status");
+                fakeserver
+                    .handle_request_response(self.prom_status.0, Some(&self.prom_status.1))
+                    .await
+                    .expect("should report jaeger status");
+                fakeserver
+                    .handle_request_response(self.otel_status.0, Some(&self.otel_status.1))
+                    .await
+                    .expect("should report jaeger status");
+                if let Some(grafana_status) = self.grafana_status {
+                    fakeserver
+                        .handle_request_response(grafana_status.0, Some(&grafana_status.1))
+                        .await
+                        .expect("should report grafana status");
+                }
+            }
 
             fakeserver
                 .handle_apply(self.redis_service)
@@ -293,20 +412,49 @@ impl Stub {
                 .await
                 .expect("should report redis status");
 
+            if let Some((request, networks)) = self.networks {
+                fakeserver
+                    .handle_request_response(
+                        request,
+                        Some(&serde_json::json!({
+                            "apiVersion": "keramik.3box.io/v1alpha1",
+                            "kind": "NetworkList",
+                            "metadata": {},
+                            "items": networks,
+                        })),
+                    )
+                    .await
+                    .expect("should list networks");
+                if !self.mesh_ready {
+                    return;
+                }
+            }
+
             // Next we handle creating the jobs
             fakeserver
                 .handle_apply(self.goose_service)
                 .await
                 .expect("goose service should apply");
-            fakeserver
-                .handle_apply(self.manager_job)
-                .await
-                .expect("manager job should apply");
+            if let Some((deployment, status, response)) = self.manager_deployment {
+                fakeserver
+                    .handle_apply(deployment)
+                    .await
+                    .expect("manager deployment should apply");
+                fakeserver
+                    .handle_request_response(status, Some(&response))
+                    .await
+                    .expect("manager deployment should report status");
+            } else {
+                fakeserver
+                    .handle_apply(self.manager_job)
+                    .await
+                    .expect("manager job should apply");
 
-            fakeserver
-                .handle_request_response(self.manager_status.0, Some(&self.manager_status.1))
-                .await
-                .expect("manager should report status");
+                fakeserver
+                    .handle_request_response(self.manager_status.0, Some(&self.manager_status.1))
+                    .await
+                    .expect("manager should report status");
+            }
 
             for w in self.worker_jobs {
                 fakeserver
@@ -314,6 +462,16 @@ impl Stub {
                     .await
                     .expect("should be next request");
             }
+            fakeserver
+                .handle_request_response(self.worker_jobs_list.0, Some(&self.worker_jobs_list.1))
+                .await
+                .expect("should list worker jobs");
+            for d in self.worker_job_deletes {
+                fakeserver
+                    .handle_request_response(d, Some(&Job::default()))
+                    .await
+                    .expect("job should delete");
+            }
 
             // Finally we handle the patch status call
             fakeserver
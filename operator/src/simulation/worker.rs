@@ -3,31 +3,243 @@ use std::collections::BTreeMap;
 use k8s_openapi::api::{
     batch::v1::JobSpec,
     core::v1::{
-        ConfigMapVolumeSource, Container, EnvVar, PodSpec, PodTemplateSpec, Volume, VolumeMount,
+        ConfigMapVolumeSource, Container, EnvVar, PodSpec, PodTemplateSpec, ResourceRequirements,
+        Volume, VolumeMount,
     },
 };
 
 use kube::core::ObjectMeta;
 
-use crate::{network::PEERS_CONFIG_MAP_NAME, simulation::job::JobImageConfig};
+use crate::{
+    labels::insert_revision_label,
+    network::{peers::peers_volume, resource_limits::ResourceLimitsConfig},
+    simulation::{
+        job::{
+            did_env_vars, JobDeadlineConfig, JobImageConfig, JobRetentionConfig,
+            DEFAULT_OTLP_ENDPOINT,
+        },
+        DidSpec,
+    },
+};
 
 // WorkerConfig defines which properties of the JobSpec can be customized.
 pub struct WorkerConfig {
     pub scenario: String,
     pub target_peer: u32,
+    /// Number of users of this worker.
+    pub users: u32,
     pub nonce: u32,
+    /// Fully qualified host name of the goose manager of the simulation.
+    pub manager_host: String,
     pub job_image_config: JobImageConfig,
+    pub job_retention_config: JobRetentionConfig,
+    pub job_deadline_config: JobDeadlineConfig,
+    pub resource_limits: ResourceLimitsConfig,
+    pub seed_documents: Option<u32>,
+    pub seed_models: Option<Vec<String>>,
+    pub target_spec: Option<String>,
+    pub catalog_size: Option<u32>,
+    /// Config map with the catalog definition, mounted into the worker when set.
+    pub catalog_config_map: Option<String>,
+    pub large_model_size: Option<u32>,
+    pub read_ratio: Option<u32>,
+    pub did: Option<DidSpec>,
+    pub seed: Option<u64>,
+    pub wait_time_min: Option<u64>,
+    pub wait_time_max: Option<u64>,
+    pub think_time: Option<String>,
+    pub think_time_shape: Option<f64>,
+    /// Tag the requests of the worker with its target peer.
+    pub tag_peer: bool,
+    pub revision: Option<String>,
+    pub otlp_endpoint: Option<String>,
 }
 
 pub fn worker_job_spec(config: WorkerConfig) -> JobSpec {
+    let mut env_vars = vec![
+        EnvVar {
+            name: "REDIS_ENDPOINT".to_owned(),
+            value: Some("http://redis:6379".to_owned()),
+            ..Default::default()
+        },
+        EnvVar {
+            name: "RUNNER_OTLP_ENDPOINT".to_owned(),
+            value: Some(
+                config
+                    .otlp_endpoint
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_OTLP_ENDPOINT.to_owned()),
+            ),
+            ..Default::default()
+        },
+        EnvVar {
+            name: "RUST_LOG".to_owned(),
+            value: Some("info,keramik_runner=trace".to_owned()),
+            ..Default::default()
+        },
+        EnvVar {
+            name: "RUST_BACKTRACE".to_owned(),
+            value: Some("1".to_owned()),
+            ..Default::default()
+        },
+        EnvVar {
+            name: "SIMULATE_SCENARIO".to_owned(),
+            value: Some(config.scenario.to_owned()),
+            ..Default::default()
+        },
+        EnvVar {
+            name: "SIMULATE_TARGET_PEER".to_owned(),
+            value: Some(config.target_peer.to_string()),
+            ..Default::default()
+        },
+        EnvVar {
+            name: "SIMULATE_USERS".to_owned(),
+            value: Some(config.users.to_string()),
+            ..Default::default()
+        },
+        EnvVar {
+            name: "SIMULATE_PEERS_PATH".to_owned(),
+            value: Some("/keramik-peers/peers.json".to_owned()),
+            ..Default::default()
+        },
+        EnvVar {
+            name: "SIMULATE_NONCE".to_owned(),
+            value: Some(config.nonce.to_string()),
+            ..Default::default()
+        },
+        EnvVar {
+            name: "SIMULATE_MANAGER_HOST".to_owned(),
+            value: Some(config.manager_host),
+            ..Default::default()
+        },
+    ];
+    env_vars.extend(did_env_vars(config.did.as_ref()));
+    if let Some(seed_documents) = config.seed_documents {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_SEED_DOCUMENTS".to_owned(),
+            value: Some(seed_documents.to_string()),
+            ..Default::default()
+        })
+    }
+    if let Some(seed_models) = config.seed_models {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_SEED_MODELS".to_owned(),
+            value: Some(seed_models.join(",")),
+            ..Default::default()
+        })
+    }
+    if let Some(target_spec) = config.target_spec {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_TARGET_SPEC".to_owned(),
+            value: Some(target_spec),
+            ..Default::default()
+        })
+    }
+    if let Some(catalog_size) = config.catalog_size {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_CATALOG_SIZE".to_owned(),
+            value: Some(catalog_size.to_string()),
+            ..Default::default()
+        })
+    }
+    if let Some(large_model_size) = config.large_model_size {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_LARGE_MODEL_SIZE".to_owned(),
+            value: Some(large_model_size.to_string()),
+            ..Default::default()
+        })
+    }
+    if let Some(wait_time_min) = config.wait_time_min {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_WAIT_TIME_MIN".to_owned(),
+            value: Some(wait_time_min.to_string()),
+            ..Default::default()
+        })
+    }
+    if let Some(wait_time_max) = config.wait_time_max {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_WAIT_TIME_MAX".to_owned(),
+            value: Some(wait_time_max.to_string()),
+            ..Default::default()
+        })
+    }
+    if let Some(think_time) = config.think_time {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_THINK_TIME".to_owned(),
+            value: Some(think_time),
+            ..Default::default()
+        })
+    }
+    if let Some(think_time_shape) = config.think_time_shape {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_THINK_TIME_SHAPE".to_owned(),
+            value: Some(think_time_shape.to_string()),
+            ..Default::default()
+        })
+    }
+    if let Some(seed) = config.seed {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_SEED".to_owned(),
+            value: Some(seed.to_string()),
+            ..Default::default()
+        })
+    }
+    if let Some(read_ratio) = config.read_ratio {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_READ_RATIO".to_owned(),
+            value: Some(read_ratio.to_string()),
+            ..Default::default()
+        })
+    }
+    if config.tag_peer {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_TAG_PEER".to_owned(),
+            value: Some("true".to_owned()),
+            ..Default::default()
+        })
+    }
+    if let Some(revision) = &config.revision {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_REVISION".to_owned(),
+            value: Some(revision.clone()),
+            ..Default::default()
+        })
+    }
+    let mut volume_mounts = vec![VolumeMount {
+        mount_path: "/keramik-peers".to_owned(),
+        name: "keramik-peers".to_owned(),
+        ..Default::default()
+    }];
+    let mut volumes = vec![peers_volume()];
+    if let Some(catalog_config_map) = config.catalog_config_map {
+        env_vars.push(EnvVar {
+            name: "SIMULATE_CATALOG_PATH".to_owned(),
+            value: Some("/catalog/catalog.json".to_owned()),
+            ..Default::default()
+        });
+        volume_mounts.push(VolumeMount {
+            mount_path: "/catalog".to_owned(),
+            name: "catalog".to_owned(),
+            read_only: Some(true),
+            ..Default::default()
+        });
+        volumes.push(Volume {
+            config_map: Some(ConfigMapVolumeSource {
+                name: Some(catalog_config_map),
+                ..Default::default()
+            }),
+            name: "catalog".to_owned(),
+            ..Default::default()
+        });
+    }
+    let mut labels = BTreeMap::from_iter(vec![("name".to_owned(), "goose".to_owned())]);
+    insert_revision_label(&mut labels, config.revision.as_deref());
     JobSpec {
-        backoff_limit: Some(4),
+        active_deadline_seconds: Some(config.job_deadline_config.active_deadline_seconds()),
+        backoff_limit: Some(config.job_retention_config.backoff_limit()),
         template: PodTemplateSpec {
             metadata: Some(ObjectMeta {
-                labels: Some(BTreeMap::from_iter(vec![(
-                    "name".to_owned(),
-                    "goose".to_owned(),
-                )])),
+                labels: Some(labels),
                 ..Default::default()
             }),
             spec: Some(PodSpec {
@@ -39,80 +251,16 @@ pub fn worker_job_spec(config: WorkerConfig) -> JobSpec {
                         "/usr/bin/keramik-runner".to_owned(),
                         "simulate".to_owned(),
                     ]),
-                    env: Some(vec![
-                        EnvVar {
-                            name: "REDIS_ENDPOINT".to_owned(),
-                            value: Some("http://redis:6379".to_owned()),
-                            ..Default::default()
-                        },
-                        EnvVar {
-                            name: "RUNNER_OTLP_ENDPOINT".to_owned(),
-                            value: Some("http://otel:4317".to_owned()),
-                            ..Default::default()
-                        },
-                        EnvVar {
-                            name: "RUST_LOG".to_owned(),
-                            value: Some("info,keramik_runner=trace".to_owned()),
-                            ..Default::default()
-                        },
-                        EnvVar {
-                            name: "RUST_BACKTRACE".to_owned(),
-                            value: Some("1".to_owned()),
-                            ..Default::default()
-                        },
-                        EnvVar {
-                            name: "SIMULATE_SCENARIO".to_owned(),
-                            value: Some(config.scenario.to_owned()),
-                            ..Default::default()
-                        },
-                        EnvVar {
-                            name: "SIMULATE_TARGET_PEER".to_owned(),
-                            value: Some(config.target_peer.to_string()),
-                            ..Default::default()
-                        },
-                        EnvVar {
-                            name: "SIMULATE_PEERS_PATH".to_owned(),
-                            value: Some("/keramik-peers/peers.json".to_owned()),
-                            ..Default::default()
-                        },
-                        EnvVar {
-                            name: "SIMULATE_NONCE".to_owned(),
-                            value: Some(config.nonce.to_string()),
-                            ..Default::default()
-                        },
-                        EnvVar {
-                            name: "DID_KEY".to_owned(),
-                            value: Some(
-                                "did:key:z6Mkqn5jbycThHcBtakJZ8fHBQ2oVRQhXQEdQk5ZK2NDtNZA"
-                                    .to_owned(),
-                            ),
-                            ..Default::default()
-                        },
-                        EnvVar {
-                            name: "DID_PRIVATE_KEY".to_owned(),
-                            value: Some(
-                                "86dce513cf0a37d4acd6d2c2e00fe4b95e0e655ca51e1a890808f5fa6f4fe65a"
-                                    .to_owned(),
-                            ),
-                            ..Default::default()
-                        },
-                    ]),
-                    volume_mounts: Some(vec![VolumeMount {
-                        mount_path: "/keramik-peers".to_owned(),
-                        name: "keramik-peers".to_owned(),
-                        ..Default::default()
-                    }]),
-                    ..Default::default()
-                }],
-                volumes: Some(vec![Volume {
-                    config_map: Some(ConfigMapVolumeSource {
-                        default_mode: Some(0o755),
-                        name: Some(PEERS_CONFIG_MAP_NAME.to_owned()),
+                    env: Some(env_vars),
+                    resources: Some(ResourceRequirements {
+                        limits: Some(config.resource_limits.clone().into()),
+                        requests: Some(config.resource_limits.into()),
                         ..Default::default()
                     }),
-                    name: "keramik-peers".to_owned(),
+                    volume_mounts: Some(volume_mounts),
                     ..Default::default()
-                }]),
+                }],
+                volumes: Some(volumes),
                 restart_policy: Some("Never".to_owned()),
                 ..Default::default()
             }),
@@ -8,10 +8,13 @@ use std::{collections::BTreeMap, sync::Arc};
 
 use k8s_openapi::{
     api::{
-        apps::v1::{StatefulSet, StatefulSetSpec, StatefulSetStatus},
+        apps::v1::{
+            Deployment, DeploymentSpec, DeploymentStatus, StatefulSet, StatefulSetSpec,
+            StatefulSetStatus,
+        },
         batch::v1::{Job, JobSpec, JobStatus},
         core::v1::{ConfigMap, Service, ServiceAccount, ServiceSpec, ServiceStatus},
-        rbac::v1::{ClusterRole, ClusterRoleBinding},
+        rbac::v1::{ClusterRole, ClusterRoleBinding, Role, RoleBinding},
     },
     apimachinery::pkg::apis::meta::v1::OwnerReference,
     chrono::{DateTime, Utc},
@@ -70,6 +73,27 @@ impl Clock for UtcClock {
         Utc::now()
     }
 }
+
+/// Error converting a spec into its config, reported in the status of the resource.
+#[derive(Debug, PartialEq, thiserror::Error)]
+#[error("invalid {field}: {reason}")]
+pub struct ConfigError {
+    /// Name of the invalid spec field
+    pub field: &'static str,
+    /// Why the value of the field is invalid
+    pub reason: String,
+}
+
+impl ConfigError {
+    /// Create an error for the named spec field.
+    pub fn new(field: &'static str, reason: impl Into<String>) -> Self {
+        Self {
+            field,
+            reason: reason.into(),
+        }
+    }
+}
+
 /// Apply a Service
 pub async fn apply_service(
     cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
@@ -138,6 +162,22 @@ pub async fn apply_job(
     Ok(job.status)
 }
 
+/// Delete a job in namespace
+pub async fn delete_job(
+    cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
+    ns: &str,
+    name: &str,
+) -> Result<(), kube::error::Error> {
+    let jobs: Api<Job> = Api::namespaced(cx.k_client.clone(), ns);
+
+    // Delete pods along with the job so they do not linger.
+    match jobs.delete(name, &DeleteParams::background()).await {
+        Ok(_) => Ok(()),
+        Err(kube::Error::Api(err)) if err.reason == "NotFound" => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
 /// Apply a stateful set in namespace
 pub async fn apply_stateful_set(
     cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
@@ -166,6 +206,34 @@ pub async fn apply_stateful_set(
     Ok(stateful_set.status)
 }
 
+/// Apply a deployment in namespace
+pub async fn apply_deployment(
+    cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
+    ns: &str,
+    orefs: Vec<OwnerReference>,
+    name: &str,
+    spec: DeploymentSpec,
+) -> Result<Option<DeploymentStatus>, kube::error::Error> {
+    let serverside = PatchParams::apply(CONTROLLER_NAME);
+    let deployments: Api<Deployment> = Api::namespaced(cx.k_client.clone(), ns);
+
+    // Server-side apply deployment
+    let deployment: Deployment = Deployment {
+        metadata: ObjectMeta {
+            name: Some(name.to_owned()),
+            owner_references: Some(orefs),
+            labels: managed_labels(),
+            ..ObjectMeta::default()
+        },
+        spec: Some(spec),
+        ..Default::default()
+    };
+    let deployment = deployments
+        .patch(name, &serverside, &Patch::Apply(deployment))
+        .await?;
+    Ok(deployment.status)
+}
+
 /// Delete a stateful set in namespace
 pub async fn delete_stateful_set(
     cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
@@ -258,6 +326,58 @@ pub async fn apply_cluster_role_binding(
     Ok(role_binding)
 }
 
+/// Apply role in namespace
+pub async fn apply_role(
+    cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
+    ns: &str,
+    orefs: Vec<OwnerReference>,
+    name: &str,
+    role: Role,
+) -> Result<Role, kube::error::Error> {
+    let serverside = PatchParams::apply(CONTROLLER_NAME);
+    let roles: Api<Role> = Api::namespaced(cx.k_client.clone(), ns);
+
+    // Server-side apply role
+    let role: Role = Role {
+        metadata: ObjectMeta {
+            name: Some(name.to_owned()),
+            owner_references: Some(orefs),
+            labels: managed_labels(),
+            ..role.metadata
+        },
+        ..role
+    };
+    let role = roles.patch(name, &serverside, &Patch::Apply(role)).await?;
+    Ok(role)
+}
+
+/// Apply role binding in namespace
+pub async fn apply_role_binding(
+    cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
+    ns: &str,
+    orefs: Vec<OwnerReference>,
+    name: &str,
+    rb: RoleBinding,
+) -> Result<RoleBinding, kube::error::Error> {
+    let serverside = PatchParams::apply(CONTROLLER_NAME);
+    let role_bindings: Api<RoleBinding> = Api::namespaced(cx.k_client.clone(), ns);
+
+    // Server-side apply role binding
+    let role_binding: RoleBinding = RoleBinding {
+        metadata: ObjectMeta {
+            name: Some(name.to_owned()),
+            owner_references: Some(orefs),
+            labels: managed_labels(),
+            ..rb.metadata
+        },
+        ..rb
+    };
+    let role_binding = role_bindings
+        .patch(name, &serverside, &Patch::Apply(role_binding))
+        .await?;
+    Ok(role_binding)
+}
+
 /// Apply a config map
 pub async fn apply_config_map(
     cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
@@ -266,6 +386,35 @@ pub async fn apply_config_map(
     name: &str,
     data: BTreeMap<String, String>,
 ) -> Result<(), kube::error::Error> {
+    apply_labeled_config_map(cx, ns, orefs, name, BTreeMap::new(), data).await
+}
+
+/// Delete a config map in namespace
+pub async fn delete_config_map(
+    cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
+    ns: &str,
+    name: &str,
+) -> Result<(), kube::error::Error> {
+    let config_maps: Api<ConfigMap> = Api::namespaced(cx.k_client.clone(), ns);
+
+    match config_maps.delete(name, &DeleteParams::default()).await {
+        Ok(_) => Ok(()),
+        Err(kube::Error::Api(err)) if err.reason == "NotFound" => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Apply a config map with labels in addition to the managed labels
+pub async fn apply_labeled_config_map(
+    cx: Arc<Context<impl IpfsRpcClient, impl RngCore, impl Clock>>,
+    ns: &str,
+    orefs: Vec<OwnerReference>,
+    name: &str,
+    labels: BTreeMap<String, String>,
+    data: BTreeMap<String, String>,
+) -> Result<(), kube::error::Error> {
+    let mut map_labels = managed_labels().unwrap_or_default();
+    map_labels.extend(labels);
     let serverside = PatchParams::apply(CONTROLLER_NAME);
     let config_maps: Api<ConfigMap> = Api::namespaced(cx.k_client.clone(), ns);
     // Apply config map
@@ -273,7 +422,7 @@ pub async fn apply_config_map(
         metadata: ObjectMeta {
             name: Some(name.to_owned()),
             owner_references: Some(orefs),
-            labels: managed_labels(),
+            labels: Some(map_labels),
             ..ObjectMeta::default()
         },
         data: Some(data),
@@ -128,20 +128,39 @@ impl ApiServerVerifier {
                 .body(Body::from(serde_json::to_vec(response).unwrap()))
                 .unwrap()
         } else {
-            let error = ErrorResponse {
-                status: "stub status".to_owned(),
-                code: 0,
-                message: "stub message".to_owned(),
-                reason: "NotFound".to_owned(),
-            };
-            http::Response::builder()
-                .status(404)
-                .body(Body::from(serde_json::to_vec(&error).unwrap()))
-                .unwrap()
+            error_response(404, "NotFound")
         };
         send.send_response(response);
         Ok(())
     }
+
+    /// Respond to the expected request with an error status, e.g. 409 AlreadyExists.
+    pub async fn handle_request_error(
+        &mut self,
+        expected_request: impl Expectation,
+        code: u16,
+        reason: &str,
+    ) -> Result<()> {
+        let (request, send) = self.0.next_request().await.expect("service not called");
+        let request = Request::from_request(request).await?;
+        expected_request.assert_debug_eq(&request);
+
+        send.send_response(error_response(code, reason));
+        Ok(())
+    }
+}
+
+fn error_response(code: u16, reason: &str) -> http::Response<Body> {
+    let error = ErrorResponse {
+        status: "stub status".to_owned(),
+        code,
+        message: "stub message".to_owned(),
+        reason: reason.to_owned(),
+    };
+    http::Response::builder()
+        .status(code)
+        .body(Body::from(serde_json::to_vec(&error).unwrap()))
+        .unwrap()
 }
 
 /// Helper struct to assert the contents of a mock Request.
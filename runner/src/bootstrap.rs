@@ -1,6 +1,6 @@
 use std::{cmp::min, path::PathBuf};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use clap::{Args, ValueEnum};
 use keramik_common::peer_info::Peer;
 use rand::seq::IteratorRandom;
@@ -19,10 +19,41 @@ pub struct Opts {
     #[arg(long, env = "BOOTSTRAP_N")]
     n: usize,
 
+    /// Index of the peer all other peers connect to with the star method.
+    #[arg(long, default_value_t = 0, env = "BOOTSTRAP_CENTER")]
+    center: usize,
+
     /// Path to file containing the list of peers.
     /// File should contian JSON encoding of Vec<Peer>.
     #[arg(long, env = "BOOTSTRAP_PEERS_PATH")]
     peers: PathBuf,
+
+    /// Types of the peers to connect, peers of other types are left out.
+    /// Connects peers of all types when unset.
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        env = "BOOTSTRAP_TARGET_PEER_TYPES"
+    )]
+    target_peer_types: Vec<PeerType>,
+}
+
+#[derive(Clone, Debug, PartialEq, ValueEnum)]
+enum PeerType {
+    /// Peers participating via Ceramic protocols.
+    Ceramic,
+    /// Peers only participating using IPFS protocols.
+    Ipfs,
+}
+
+impl PeerType {
+    fn of(peer: &Peer) -> Self {
+        match peer {
+            Peer::Ceramic(_) => Self::Ceramic,
+            Peer::Ipfs(_) => Self::Ipfs,
+        }
+    }
 }
 
 #[derive(Clone, Debug, ValueEnum)]
@@ -33,6 +64,8 @@ enum Method {
     Random,
     /// Connects each peer to the first N peers.
     Sentinel,
+    /// Connects each peer to a single center peer.
+    Star,
 }
 impl Default for Method {
     fn default() -> Self {
@@ -42,17 +75,29 @@ impl Default for Method {
 
 #[tracing::instrument]
 pub async fn bootstrap(opts: Opts) -> Result<()> {
-    let peers = parse_peers_info(opts.peers).await?;
+    let peers = target_peers(parse_peers_info(opts.peers).await?, &opts.target_peer_types);
     // Bootstrap peers according to the given method.
     // Methods should not assume that peer indexes are consecutive nor that they start at zero.
     match opts.method {
         Method::Ring => ring(opts.n, &peers).await?,
         Method::Random => random(opts.n, &peers).await?,
         Method::Sentinel => sentinel(opts.n, &peers).await?,
+        Method::Star => star(opts.center, &peers).await?,
     }
     Ok(())
 }
 
+/// Keep only the peers of the target types, all peers are kept without target types.
+fn target_peers(peers: Vec<Peer>, target_peer_types: &[PeerType]) -> Vec<Peer> {
+    if target_peer_types.is_empty() {
+        return peers;
+    }
+    peers
+        .into_iter()
+        .filter(|peer| target_peer_types.contains(&PeerType::of(peer)))
+        .collect()
+}
+
 #[tracing::instrument(skip(peers), fields(peers.len = peers.len()))]
 async fn ring(n: usize, peers: &[Peer]) -> Result<()> {
     for (i, peer) in peers.iter().enumerate() {
@@ -135,3 +180,33 @@ async fn sentinel(n: usize, peers: &[Peer]) -> Result<()> {
     }
     Ok(())
 }
+
+#[tracing::instrument(skip(peers), fields(peers.len = peers.len()))]
+async fn star(center: usize, peers: &[Peer]) -> Result<()> {
+    let Some(center_peer) = peers.get(center) else {
+        bail!(
+            "star center {center} is out of range, only {} peers are ready",
+            peers.len()
+        );
+    };
+    // Connect every other peer to the center peer.
+    for (i, peer) in peers.iter().enumerate() {
+        if i == center {
+            continue;
+        }
+        debug!(
+            peer = peer.id(),
+            center = center_peer.id(),
+            "star peer connection"
+        );
+        if let Err(err) = connect_peers(peer, center_peer).await {
+            error!(
+                peer = peer.id(),
+                center = center_peer.id(),
+                ?err,
+                "failed to bootstrap star peer"
+            );
+        }
+    }
+    Ok(())
+}
@@ -68,18 +68,19 @@ async fn main() -> Result<()> {
     runs.add(&cx, 1, &[KeyValue::new("command", args.command.name())]);
 
     info!(?args.command, ?args.otlp_endpoint, "starting runner");
-    match args.command {
-        Command::Bootstrap(opts) => bootstrap(opts).await?,
-        Command::Simulate(opts) => simulate(opts).await?,
-        Command::Noop => {}
-    }
+    let result = match args.command {
+        Command::Bootstrap(opts) => bootstrap(opts).await,
+        Command::Simulate(opts) => simulate(opts).await,
+        Command::Noop => Ok(()),
+    };
 
-    // Flush traces and metrics before shutdown
+    // Flush traces and metrics before shutdown, even when the command failed,
+    // so the metrics of a simulation that failed its thresholds are still reported.
     shutdown_tracer_provider();
     metrics_controller.stop(&cx)?;
 
     // This fixes lost metrics not sure why :(
     // Seems to be related to the inflight gRPC request getting cancelled
     tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-    Ok(())
+    result
 }
@@ -0,0 +1,310 @@
+use crate::scenario::ceramic::models::{LargeModel, NestedModel, RandomModelInstance, SmallModel};
+use crate::scenario::ceramic::util::{goose_error, index_model, setup_model, setup_model_instance};
+use crate::scenario::ceramic::{CeramicClient, Credentials};
+use crate::scenario::request_builder;
+use crate::scenario::think_time::set_think_time;
+use anyhow::{anyhow, Context};
+use ceramic_http_client::api::{self, Pagination};
+use ceramic_http_client::ceramic_event::StreamId;
+use ceramic_http_client::{
+    CeramicHttpClient, GetRootSchema, ModelAccountRelation, ModelDefinition,
+};
+use goose::prelude::*;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::{sync::Arc, time::Duration};
+use tracing::instrument;
+
+/// Number of models in the generated catalog when neither a size nor a definition is given.
+const DEFAULT_CATALOG_SIZE: usize = 10;
+
+/// Schema of the models of a catalog entry.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CatalogModel {
+    Small,
+    Large,
+    Nested,
+}
+
+impl CatalogModel {
+    /// Definition of a model with this schema and the given name.
+    fn definition(&self, name: &str) -> ModelDefinition {
+        match self {
+            Self::Small => definition::<SmallModel>(name),
+            Self::Large => definition::<LargeModel>(name),
+            Self::Nested => definition::<NestedModel>(name),
+        }
+    }
+}
+
+fn definition<T: GetRootSchema>(name: &str) -> ModelDefinition {
+    ModelDefinition::new::<T>(name, ModelAccountRelation::List).unwrap()
+}
+
+/// Model registered as part of the catalog.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct CatalogEntry {
+    pub name: String,
+    pub model: CatalogModel,
+}
+
+/// Fixed set of models registered before instances of them are created and queried.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Catalog {
+    entries: Vec<CatalogEntry>,
+}
+
+impl Catalog {
+    /// Read the catalog definition from the file at SIMULATE_CATALOG_PATH, a JSON array of
+    /// entries. Without a definition a catalog of alternating small and large models is
+    /// generated. SIMULATE_CATALOG_SIZE limits the number of models of either catalog.
+    pub fn from_env() -> Result<Self, anyhow::Error> {
+        let size = match std::env::var("SIMULATE_CATALOG_SIZE") {
+            Ok(value) => Some(
+                value
+                    .parse::<usize>()
+                    .map_err(|e| anyhow!("invalid SIMULATE_CATALOG_SIZE {value}: {e}"))?,
+            ),
+            Err(_) => None,
+        };
+        match std::env::var("SIMULATE_CATALOG_PATH") {
+            Ok(path) => {
+                let definition = std::fs::read_to_string(&path)
+                    .with_context(|| format!("failed to read catalog definition {path}"))?;
+                Self::parse(&definition, size)
+            }
+            Err(_) => Self::generate(size.unwrap_or(DEFAULT_CATALOG_SIZE)),
+        }
+    }
+
+    /// Parse a catalog definition, keeping at most size entries when set.
+    fn parse(definition: &str, size: Option<usize>) -> Result<Self, anyhow::Error> {
+        let mut entries: Vec<CatalogEntry> = serde_json::from_str(definition)?;
+        if let Some(size) = size {
+            entries.truncate(size);
+        }
+        let mut names = HashSet::new();
+        if let Some(entry) = entries.iter().find(|entry| !names.insert(&entry.name)) {
+            return Err(anyhow!("duplicate catalog model {}", entry.name));
+        }
+        Self::new(entries)
+    }
+
+    /// Generate a catalog of size models alternating between the small and large schemas.
+    fn generate(size: usize) -> Result<Self, anyhow::Error> {
+        Self::new(
+            (0..size)
+                .map(|i| CatalogEntry {
+                    name: format!("load_test_catalog_model_{i}"),
+                    model: if i % 2 == 0 {
+                        CatalogModel::Small
+                    } else {
+                        CatalogModel::Large
+                    },
+                })
+                .collect(),
+        )
+    }
+
+    fn new(entries: Vec<CatalogEntry>) -> Result<Self, anyhow::Error> {
+        if entries.is_empty() {
+            return Err(anyhow!("catalog must contain at least one model"));
+        }
+        Ok(Self { entries })
+    }
+
+    /// Index of the catalog model a user works on during the given iteration,
+    /// so the users cycle through the whole catalog.
+    fn entry_index(&self, user_index: usize, iteration: usize) -> usize {
+        (user_index + iteration) % self.entries.len()
+    }
+}
+
+#[derive(Clone)]
+pub struct CatalogUserData {
+    cli: CeramicClient,
+    catalog: Catalog,
+    model_ids: Vec<StreamId>,
+}
+
+impl CatalogUserData {
+    /// Catalog model and its id the user works on during its current iteration.
+    fn model_for_user(&self, user: &GooseUser) -> (CatalogModel, StreamId) {
+        let i = self
+            .catalog
+            .entry_index(user.weighted_users_index, user.get_iterations());
+        (self.catalog.entries[i].model, self.model_ids[i].clone())
+    }
+}
+
+pub async fn scenario() -> Result<Scenario, GooseError> {
+    let creds = Credentials::from_env().await.map_err(goose_error)?;
+    let cli = CeramicHttpClient::new(creds.signer);
+    let catalog = Catalog::from_env().map_err(goose_error)?;
+
+    // Registration is its own transaction so schema registration and
+    // the instance operations are measured separately.
+    let register_catalog = Transaction::new(Arc::new(move |user| {
+        Box::pin(register_catalog(user, cli.clone(), catalog.clone()))
+    }))
+    .set_name("register_catalog")
+    .set_on_start();
+
+    let create_instance = transaction!(create_instance).set_name("create_catalog_instance");
+    let query_instances = transaction!(query_instances).set_name("query_catalog_instances");
+
+    let scenario = scenario!("CeramicCatalogScenario")
+        .register_transaction(register_catalog)
+        .register_transaction(create_instance)
+        .register_transaction(query_instances);
+    // After each transactions runs, think from 1 to 5 seconds.
+    set_think_time(scenario, Duration::from_secs(1), Duration::from_secs(5))
+}
+
+#[instrument(skip_all, fields(user.index = user.weighted_users_index), ret)]
+async fn register_catalog(
+    user: &mut GooseUser,
+    cli: CeramicClient,
+    catalog: Catalog,
+) -> TransactionResult {
+    let mut model_ids = Vec::with_capacity(catalog.entries.len());
+    for entry in &catalog.entries {
+        let model_id = setup_model(user, &cli, entry.model.definition(&entry.name)).await?;
+        index_model(user, &cli, &model_id).await?;
+        model_ids.push(model_id);
+    }
+
+    user.set_session_data(CatalogUserData {
+        cli,
+        catalog,
+        model_ids,
+    });
+
+    Ok(())
+}
+
+async fn create_instance(user: &mut GooseUser) -> TransactionResult {
+    let user_data = {
+        let data: &CatalogUserData = user.get_session_data_unchecked();
+        data.clone()
+    };
+    let (model, model_id) = user_data.model_for_user(user);
+    let cli = &user_data.cli;
+    match model {
+        CatalogModel::Small => {
+            setup_model_instance(user, cli, &model_id, &SmallModel::random()).await?
+        }
+        CatalogModel::Large => {
+            setup_model_instance(user, cli, &model_id, &LargeModel::random()).await?
+        }
+        CatalogModel::Nested => {
+            setup_model_instance(user, cli, &model_id, &NestedModel::random()).await?
+        }
+    };
+    Ok(())
+}
+
+async fn query_instances(user: &mut GooseUser) -> TransactionResult {
+    let user_data: &CatalogUserData = user.get_session_data_unchecked();
+    let (_, model_id) = user_data.model_for_user(user);
+    let cli = &user_data.cli;
+    let req = cli
+        .create_query_request(&model_id, None, Pagination::default())
+        .await
+        .unwrap();
+    let goose = user
+        .request(
+            request_builder("query_catalog_instances")
+                .method(GooseMethod::Post)
+                .set_request_builder(
+                    user.client
+                        .post(user.build_url(cli.collection_endpoint())?)
+                        .json(&req),
+                )
+                .expect_status_code(200)
+                .build(),
+        )
+        .await?;
+    // Instances are created by the other transaction, so the first queries may find none
+    let _resp: api::QueryResponse = goose.response?.json().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_catalog() {
+        let catalog = Catalog::parse(
+            r#"[{"name": "profile", "model": "small"}, {"name": "post", "model": "large"}, {"name": "place", "model": "nested"}]"#,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                CatalogEntry {
+                    name: "profile".to_owned(),
+                    model: CatalogModel::Small,
+                },
+                CatalogEntry {
+                    name: "post".to_owned(),
+                    model: CatalogModel::Large,
+                },
+                CatalogEntry {
+                    name: "place".to_owned(),
+                    model: CatalogModel::Nested,
+                },
+            ],
+            catalog.entries
+        );
+    }
+
+    #[test]
+    fn parse_catalog_limited_to_size() {
+        let catalog = Catalog::parse(
+            r#"[{"name": "profile", "model": "small"}, {"name": "post", "model": "large"}]"#,
+            Some(1),
+        )
+        .unwrap();
+        assert_eq!(1, catalog.entries.len());
+        assert_eq!("profile", catalog.entries[0].name);
+    }
+
+    #[test]
+    fn invalid_catalogs() {
+        assert!(Catalog::parse("[]", None).is_err());
+        assert!(Catalog::parse(r#"[{"name": "profile", "model": "medium"}]"#, None).is_err());
+        assert!(Catalog::parse(
+            r#"[{"name": "profile", "model": "small"}, {"name": "profile", "model": "large"}]"#,
+            None
+        )
+        .is_err());
+        assert!(Catalog::generate(0).is_err());
+    }
+
+    #[test]
+    fn generate_catalog() {
+        let catalog = Catalog::generate(3).unwrap();
+        assert_eq!(
+            vec![
+                ("load_test_catalog_model_0", CatalogModel::Small),
+                ("load_test_catalog_model_1", CatalogModel::Large),
+                ("load_test_catalog_model_2", CatalogModel::Small),
+            ],
+            catalog
+                .entries
+                .iter()
+                .map(|entry| (entry.name.as_str(), entry.model))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn users_cycle_through_catalog() {
+        let catalog = Catalog::generate(3).unwrap();
+        let indexes: Vec<usize> = (0..6).map(|i| catalog.entry_index(1, i)).collect();
+        assert_eq!(vec![1, 2, 0, 1, 2, 0], indexes);
+    }
+}
@@ -0,0 +1,373 @@
+use crate::goose_try;
+use crate::scenario::ceramic::models::{RandomModelInstance, SmallModel};
+use crate::scenario::ceramic::util::{goose_error, setup_model, setup_model_instance};
+use crate::scenario::ceramic::{CeramicClient, Credentials};
+use crate::scenario::think_time::set_think_time;
+use crate::scenario::{request_builder, request_name};
+use crate::simulate::Topology;
+use ceramic_http_client::api::StreamsResponseOrError;
+use ceramic_http_client::ceramic_event::StreamId;
+use ceramic_http_client::{CeramicHttpClient, ModelAccountRelation, ModelDefinition};
+use goose::prelude::*;
+use keramik_common::peer_info::Peer;
+use opentelemetry::{global, metrics::Histogram, Context, KeyValue};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tracing::instrument;
+
+/// Default time to wait between polls of the other peers.
+const DEFAULT_POLL_INTERVAL_MS: u64 = 500;
+/// Default time to wait for a write to be visible on every other peer.
+const DEFAULT_PROPAGATION_TIMEOUT_MS: u64 = 5_000;
+
+pub struct LoadTestUserData {
+    cli: CeramicClient,
+    model_id: StreamId,
+    model_instance_id: StreamId,
+    other_peers: Vec<String>,
+    poll: PollConfig,
+    propagation_delay: Histogram<u64>,
+}
+
+/// How often and for how long to poll the other peers for a write.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct PollConfig {
+    interval: Duration,
+    timeout: Duration,
+}
+
+impl PollConfig {
+    fn from_env() -> Result<Self, GooseError> {
+        Ok(Self {
+            interval: millis_from_env(
+                "SIMULATE_PROPAGATION_POLL_INTERVAL_MS",
+                DEFAULT_POLL_INTERVAL_MS,
+            )?,
+            timeout: millis_from_env(
+                "SIMULATE_PROPAGATION_TIMEOUT_MS",
+                DEFAULT_PROPAGATION_TIMEOUT_MS,
+            )?,
+        })
+    }
+
+    /// Time to sleep before the next poll given the time elapsed since the write.
+    /// Returns None once the timeout has been reached.
+    fn next_delay(&self, elapsed: Duration) -> Option<Duration> {
+        let remaining = self.timeout.checked_sub(elapsed)?;
+        if remaining.is_zero() {
+            None
+        } else {
+            Some(self.interval.min(remaining))
+        }
+    }
+}
+
+pub async fn scenario(topo: Topology, peers: &[Peer]) -> Result<Scenario, GooseError> {
+    let creds = Credentials::from_env().await.map_err(goose_error)?;
+    let cli = CeramicHttpClient::new(creds.signer);
+    let poll = PollConfig::from_env()?;
+    let other_peers = other_ceramic_addrs(topo.target_worker, peers);
+    let propagation_delay = global::meter("simulate")
+        .u64_histogram("goose_consistency_propagation_delay_ms")
+        .with_description("Time in milliseconds until a write is visible on a peer")
+        .init();
+
+    let setup = Transaction::new(Arc::new(move |user| {
+        Box::pin(setup(
+            user,
+            cli.clone(),
+            other_peers.clone(),
+            poll,
+            propagation_delay.clone(),
+        ))
+    }))
+    .set_name("setup")
+    .set_on_start();
+
+    let check_consistency = transaction!(check_consistency).set_name("check_consistency");
+
+    let scenario = scenario!("CeramicConsistency")
+        .register_transaction(setup)
+        .register_transaction(check_consistency);
+    set_think_time(scenario, Duration::from_secs(1), Duration::from_secs(5))
+}
+
+fn millis_from_env(name: &str, default: u64) -> Result<Duration, GooseError> {
+    let millis = match std::env::var(name) {
+        Ok(value) => value
+            .parse::<u64>()
+            .map_err(|e| goose_error(anyhow::anyhow!("invalid {name} {value}: {e}")))?,
+        Err(_) => default,
+    };
+    Ok(Duration::from_millis(millis))
+}
+
+/// Ceramic API addresses of every peer other than the target peer.
+fn other_ceramic_addrs(target_peer: usize, peers: &[Peer]) -> Vec<String> {
+    peers
+        .iter()
+        .enumerate()
+        .filter(|(idx, _)| *idx != target_peer)
+        .filter_map(|(_, peer)| match peer {
+            Peer::Ceramic(peer) => Some(peer.ceramic_addr.clone()),
+            Peer::Ipfs(_) => None,
+        })
+        .collect()
+}
+
+/// Report the peers whose observed content does not match the expected content.
+/// A peer that could not produce any content is considered inconsistent.
+fn inconsistent_peers<'a>(
+    expected: &serde_json::Value,
+    observed: &'a [(String, Option<serde_json::Value>)],
+) -> Vec<&'a str> {
+    observed
+        .iter()
+        .filter(|(_, content)| content.as_ref() != Some(expected))
+        .map(|(peer, _)| peer.as_str())
+        .collect()
+}
+
+#[instrument(skip_all, fields(user.index = user.weighted_users_index), ret)]
+async fn setup(
+    user: &mut GooseUser,
+    cli: CeramicClient,
+    other_peers: Vec<String>,
+    poll: PollConfig,
+    propagation_delay: Histogram<u64>,
+) -> TransactionResult {
+    let model = ModelDefinition::new::<SmallModel>(
+        "load_test_consistency_model",
+        ModelAccountRelation::List,
+    )
+    .unwrap();
+    let model_id = setup_model(user, &cli, model).await?;
+    let model_instance_id =
+        setup_model_instance(user, &cli, &model_id, &SmallModel::random()).await?;
+
+    user.set_session_data(LoadTestUserData {
+        cli,
+        model_id,
+        model_instance_id,
+        other_peers,
+        poll,
+        propagation_delay,
+    });
+
+    Ok(())
+}
+
+/// Write a new value to the target peer and poll every other peer until
+/// it reports the same content, recording how long each peer took.
+async fn check_consistency(user: &mut GooseUser) -> TransactionResult {
+    let (model, url, req) = {
+        let user_data: &LoadTestUserData = user.get_session_data_unchecked();
+        let cli = &user_data.cli;
+        let streams_url = user.build_url(&format!(
+            "{}/{}",
+            cli.streams_endpoint(),
+            user_data.model_instance_id
+        ))?;
+        let req = request_builder("consistency_get")
+            .method(GooseMethod::Get)
+            .set_request_builder(user.client.get(streams_url))
+            .expect_status_code(200)
+            .build();
+        let commits_url = user.build_url(cli.commits_endpoint())?;
+        (user_data.model_id.clone(), commits_url, req)
+    };
+    let mut goose = user.request(req).await?;
+    let resp: StreamsResponseOrError = goose.response?.json().await?;
+    let resp = goose_try!(user, "consistency_get", &mut goose.request, {
+        resp.resolve("consistency_get")
+    })?;
+
+    let data = SmallModel::random();
+    let expected = serde_json::to_value(&data).unwrap();
+    let req = {
+        let user_data: &LoadTestUserData = user.get_session_data_unchecked();
+        user_data
+            .cli
+            .create_replace_request(&model, &resp, &data)
+            .await
+            .unwrap()
+    };
+    let req = user.client.post(url).json(&req);
+    let mut goose = user
+        .request(
+            request_builder("consistency_write")
+                .method(GooseMethod::Post)
+                .set_request_builder(req)
+                .expect_status_code(200)
+                .build(),
+        )
+        .await?;
+    let resp: StreamsResponseOrError = goose.response?.json().await?;
+    goose_try!(
+        user,
+        "consistency_write",
+        &mut goose.request,
+        resp.resolve("consistency_write")
+    )?;
+
+    let (poll, propagation_delay, mut pending) = {
+        let user_data: &LoadTestUserData = user.get_session_data_unchecked();
+        let reads: Vec<(String, String)> = user_data
+            .other_peers
+            .iter()
+            .map(|peer| {
+                (
+                    peer.clone(),
+                    format!(
+                        "{}{}/{}",
+                        peer,
+                        user_data.cli.streams_endpoint(),
+                        user_data.model_instance_id
+                    ),
+                )
+            })
+            .collect();
+        (user_data.poll, user_data.propagation_delay.clone(), reads)
+    };
+    let written = Instant::now();
+    let cx = Context::current();
+
+    let mut stale = Vec::new();
+    let mut last_request = None;
+    while !pending.is_empty() {
+        let mut observed = Vec::with_capacity(pending.len());
+        // Time from the write until each peer was read, so the delay of a peer does not include
+        // the reads of the peers polled after it.
+        let mut read_after = Vec::with_capacity(pending.len());
+        for (peer, url) in &pending {
+            let req = GooseRequest::builder()
+                .method(GooseMethod::Get)
+                .name(request_name("consistency_read"))
+                .set_request_builder(user.client.get(url))
+                .expect_status_code(200)
+                .build();
+            let goose = user.request(req).await?;
+            let content = match goose.response {
+                Ok(resp) => match resp.json::<StreamsResponseOrError>().await {
+                    Ok(resp) => resp
+                        .resolve("consistency_read")
+                        .ok()
+                        .and_then(|resp| resp.state)
+                        .map(|state| state.content),
+                    Err(_) => None,
+                },
+                Err(_) => None,
+            };
+            read_after.push(written.elapsed());
+            observed.push((peer.clone(), content));
+            last_request = Some(goose.request);
+        }
+
+        stale = inconsistent_peers(&expected, &observed)
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+        for ((peer, _), delay) in observed
+            .iter()
+            .zip(&read_after)
+            .filter(|((peer, _), _)| !stale.contains(peer))
+        {
+            propagation_delay.record(
+                &cx,
+                delay.as_millis() as u64,
+                &[KeyValue::new("peer", peer.clone())],
+            );
+        }
+        pending.retain(|(peer, _)| stale.contains(peer));
+
+        match poll.next_delay(written.elapsed()) {
+            Some(delay) if !pending.is_empty() => tokio::time::sleep(delay).await,
+            _ => break,
+        }
+    }
+
+    if let Some(mut request) = last_request.filter(|_| !stale.is_empty()) {
+        goose_try!(user, "consistency_read", &mut request, {
+            Err(anyhow::anyhow!(
+                "write not visible on peers after {:?}: {}",
+                poll.timeout,
+                stale.join(", ")
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use keramik_common::peer_info::{CeramicPeerInfo, IpfsPeerInfo};
+    use serde_json::json;
+
+    #[test]
+    fn all_peers_consistent() {
+        let expected = json!({"creator": "keramik", "radius": 1});
+        let observed = vec![
+            ("http://peer-1".to_owned(), Some(expected.clone())),
+            ("http://peer-2".to_owned(), Some(expected.clone())),
+        ];
+        assert!(inconsistent_peers(&expected, &observed).is_empty());
+    }
+
+    #[test]
+    fn stale_and_missing_peers_inconsistent() {
+        let expected = json!({"creator": "keramik", "radius": 1});
+        let observed = vec![
+            ("http://peer-1".to_owned(), Some(expected.clone())),
+            (
+                "http://peer-2".to_owned(),
+                Some(json!({"creator": "keramik", "radius": 2})),
+            ),
+            ("http://peer-3".to_owned(), None),
+        ];
+        assert_eq!(
+            vec!["http://peer-2", "http://peer-3"],
+            inconsistent_peers(&expected, &observed)
+        );
+    }
+
+    #[test]
+    fn other_addrs_skip_target_and_ipfs_peers() {
+        let peers = vec![
+            Peer::Ceramic(CeramicPeerInfo {
+                ceramic_addr: "http://peer-0".to_owned(),
+                ..Default::default()
+            }),
+            Peer::Ipfs(IpfsPeerInfo::default()),
+            Peer::Ceramic(CeramicPeerInfo {
+                ceramic_addr: "http://peer-2".to_owned(),
+                ..Default::default()
+            }),
+        ];
+        assert_eq!(vec!["http://peer-2"], other_ceramic_addrs(0, &peers));
+    }
+
+    #[test]
+    fn poll_delay_bounded_by_timeout() {
+        let poll = PollConfig {
+            interval: Duration::from_millis(500),
+            timeout: Duration::from_millis(1_200),
+        };
+        assert_eq!(
+            Some(Duration::from_millis(500)),
+            poll.next_delay(Duration::ZERO)
+        );
+        assert_eq!(
+            Some(Duration::from_millis(500)),
+            poll.next_delay(Duration::from_millis(500))
+        );
+        assert_eq!(
+            Some(Duration::from_millis(200)),
+            poll.next_delay(Duration::from_millis(1_000))
+        );
+        assert_eq!(None, poll.next_delay(Duration::from_millis(1_200)));
+        assert_eq!(None, poll.next_delay(Duration::from_millis(5_000)));
+    }
+}
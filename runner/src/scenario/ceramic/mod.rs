@@ -1,12 +1,19 @@
+pub mod catalog;
+pub mod consistency;
 pub mod model_reuse;
-mod models;
+pub mod models;
 pub mod new_streams;
 pub mod query;
+pub mod read_only;
+pub mod read_write;
+pub mod seed;
 pub mod util;
 pub mod write_only;
 
 use crate::goose_try;
 use crate::scenario::ceramic::util::{goose_error, setup_model, setup_model_instance};
+use crate::scenario::request_builder;
+use crate::scenario::think_time::set_think_time;
 use ceramic_http_client::api::StreamsResponseOrError;
 use ceramic_http_client::ceramic_event::{DidDocument, JwkSigner, StreamId};
 use ceramic_http_client::{CeramicHttpClient, ModelAccountRelation, ModelDefinition};
@@ -35,6 +42,8 @@ pub struct LoadTestUserData {
     cli: CeramicClient,
     small_model_id: StreamId,
     small_model_instance_id: StreamId,
+    medium_model_id: StreamId,
+    medium_model_instance_id: StreamId,
     large_model_id: StreamId,
     large_model_instance_id: StreamId,
 }
@@ -54,18 +63,21 @@ pub async fn scenario() -> Result<Scenario, GooseError> {
 
     let get_small_model = transaction!(get_small_model).set_name("get_small_model");
 
+    let update_medium_model = transaction!(update_medium_model).set_name("update_medium_model");
+
     let update_large_model = transaction!(update_large_model).set_name("update_large_model");
 
     let get_large_model = transaction!(get_large_model).set_name("get_large_model");
 
-    Ok(scenario!("CeramicSimpleScenario")
-        // After each transactions runs, sleep randomly from 1 to 5 seconds.
-        .set_wait_time(Duration::from_secs(1), Duration::from_secs(5))?
+    let scenario = scenario!("CeramicSimpleScenario")
         .register_transaction(test_start)
         .register_transaction(update_small_model)
         .register_transaction(get_small_model)
+        .register_transaction(update_medium_model)
         .register_transaction(update_large_model)
-        .register_transaction(get_large_model))
+        .register_transaction(get_large_model);
+    // After each transactions runs, think from 1 to 5 seconds.
+    set_think_time(scenario, Duration::from_secs(1), Duration::from_secs(5))
 }
 
 #[instrument(skip_all, fields(user.index = user.weighted_users_index), ret)]
@@ -78,6 +90,14 @@ async fn setup(user: &mut GooseUser, cli: CeramicClient) -> TransactionResult {
     let small_model_id = setup_model(user, &cli, small_model).await?;
     let small_model_instance_id =
         setup_model_instance(user, &cli, &small_model_id, &models::SmallModel::random()).await?;
+    let medium_model = ModelDefinition::new::<models::MediumModel>(
+        "load_test_medium_model",
+        ModelAccountRelation::List,
+    )
+    .unwrap();
+    let medium_model_id = setup_model(user, &cli, medium_model).await?;
+    let medium_model_instance_id =
+        setup_model_instance(user, &cli, &medium_model_id, &models::MediumModel::random()).await?;
     let large_model = ModelDefinition::new::<models::LargeModel>(
         "load_test_large_model",
         ModelAccountRelation::List,
@@ -91,6 +111,8 @@ async fn setup(user: &mut GooseUser, cli: CeramicClient) -> TransactionResult {
         cli,
         small_model_id,
         small_model_instance_id,
+        medium_model_id,
+        medium_model_instance_id,
         large_model_id,
         large_model_instance_id,
     };
@@ -110,7 +132,7 @@ async fn update_small_model(user: &mut GooseUser) -> TransactionResult {
             cli.streams_endpoint(),
             user_data.small_model_instance_id
         ))?;
-        let req = GooseRequest::builder()
+        let req = request_builder("update_small_model_get")
             .method(GooseMethod::Get)
             .set_request_builder(user.client.get(streams_url))
             .expect_status_code(200)
@@ -133,7 +155,7 @@ async fn update_small_model(user: &mut GooseUser) -> TransactionResult {
     let req = user.client.post(url).json(&req);
     let mut goose = user
         .request(
-            GooseRequest::builder()
+            request_builder("update_small_model")
                 .method(GooseMethod::Post)
                 .set_request_builder(req)
                 .expect_status_code(200)
@@ -150,6 +172,56 @@ async fn update_small_model(user: &mut GooseUser) -> TransactionResult {
     Ok(())
 }
 
+async fn update_medium_model(user: &mut GooseUser) -> TransactionResult {
+    let (model, url, req) = {
+        let user_data: &LoadTestUserData = user.get_session_data_unchecked();
+        let model = user_data.medium_model_id.clone();
+        let cli = &user_data.cli;
+        let streams_url = user.build_url(&format!(
+            "{}/{}",
+            cli.streams_endpoint(),
+            user_data.medium_model_instance_id
+        ))?;
+        let req = request_builder("update_medium_model_get")
+            .method(GooseMethod::Get)
+            .set_request_builder(user.client.get(streams_url))
+            .expect_status_code(200)
+            .build();
+        let commits_url = user.build_url(cli.commits_endpoint())?;
+        (model, commits_url, req)
+    };
+    let resp = user.request(req).await?;
+    let resp: StreamsResponseOrError = resp.response?.json().await?;
+    let resp = resp.resolve("update_medium_model_get").unwrap();
+
+    let req = {
+        let user_data: &LoadTestUserData = user.get_session_data_unchecked();
+        user_data
+            .cli
+            .create_replace_request(&model, &resp, &models::MediumModel::random())
+            .await
+            .unwrap()
+    };
+    let req = user.client.post(url).json(&req);
+    let mut goose = user
+        .request(
+            request_builder("update_medium_model")
+                .method(GooseMethod::Post)
+                .set_request_builder(req)
+                .expect_status_code(200)
+                .build(),
+        )
+        .await?;
+    let resp: StreamsResponseOrError = goose.response?.json().await?;
+    goose_try!(
+        user,
+        "update",
+        &mut goose.request,
+        resp.resolve("update_medium_model")
+    )?;
+    Ok(())
+}
+
 async fn get_small_model(user: &mut GooseUser) -> TransactionResult {
     let user_data: &LoadTestUserData = user.get_session_data_unchecked();
     let cli: &CeramicClient = &user_data.cli;
@@ -158,7 +230,11 @@ async fn get_small_model(user: &mut GooseUser) -> TransactionResult {
         cli.streams_endpoint(),
         user_data.small_model_instance_id
     ))?;
-    let mut goose = user.get(&url).await?;
+    let req = request_builder("get_small_model")
+        .method(GooseMethod::Get)
+        .set_request_builder(user.client.get(url))
+        .build();
+    let mut goose = user.request(req).await?;
     let resp: StreamsResponseOrError = goose.response?.json().await?;
     goose_try!(
         user,
@@ -179,7 +255,7 @@ async fn update_large_model(user: &mut GooseUser) -> TransactionResult {
             cli.streams_endpoint(),
             user_data.large_model_instance_id
         ))?;
-        let req = GooseRequest::builder()
+        let req = request_builder("update_large_model_get")
             .method(GooseMethod::Get)
             .set_request_builder(user.client.get(streams_url))
             .expect_status_code(200)
@@ -202,7 +278,7 @@ async fn update_large_model(user: &mut GooseUser) -> TransactionResult {
             .unwrap()
     };
     let req = user.client.post(url).json(&req);
-    let req = GooseRequest::builder()
+    let req = request_builder("update_large_model")
         .method(GooseMethod::Post)
         .set_request_builder(req)
         .expect_status_code(200)
@@ -226,7 +302,11 @@ async fn get_large_model(user: &mut GooseUser) -> TransactionResult {
         cli.streams_endpoint(),
         user_data.large_model_instance_id
     ))?;
-    let mut goose = user.get(&url).await?;
+    let req = request_builder("get_large_model")
+        .method(GooseMethod::Get)
+        .set_request_builder(user.client.get(url))
+        .build();
+    let mut goose = user.request(req).await?;
     let resp: StreamsResponseOrError = goose.response?.json().await?;
     goose_try!(
         user,
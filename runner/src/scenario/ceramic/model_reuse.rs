@@ -3,6 +3,7 @@ use crate::scenario::ceramic::models::LargeModel;
 use crate::scenario::ceramic::util::{goose_error, index_model, setup_model, setup_model_instance};
 use crate::scenario::ceramic::{CeramicClient, Credentials};
 use crate::scenario::get_redis_client;
+use crate::scenario::think_time::set_think_time;
 use ceramic_http_client::api::StreamsResponseOrError;
 use ceramic_http_client::ceramic_event::{JwkSigner, StreamId};
 use ceramic_http_client::{CeramicHttpClient, ModelAccountRelation, ModelDefinition};
@@ -36,12 +37,12 @@ pub async fn scenario() -> Result<Scenario, GooseError> {
     let create_instance_tx = transaction!(create_instance).set_name("create_instance");
     let get_instance_tx = transaction!(get_instance).set_name("get_instance");
 
-    Ok(scenario!("CeramicModelReuseScenario")
-        // After each transactions runs, sleep randomly from 1 to 5 seconds.
-        .set_wait_time(Duration::from_secs(1), Duration::from_secs(5))?
+    let scenario = scenario!("CeramicModelReuseScenario")
         .register_transaction(test_start)
         .register_transaction(create_instance_tx)
-        .register_transaction(get_instance_tx))
+        .register_transaction(get_instance_tx);
+    // After each transactions runs, think from 1 to 5 seconds.
+    set_think_time(scenario, Duration::from_secs(1), Duration::from_secs(5))
 }
 
 async fn get_model_id(conn: &mut redis::aio::Connection) -> StreamId {
@@ -1,12 +1,22 @@
+use crate::scenario::rng;
 use ceramic_http_client::GetRootSchema;
-use rand::prelude::*;
+use rand::{distributions::Alphanumeric, prelude::*};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
 
 pub trait RandomModelInstance {
     fn random() -> Self;
 }
 
+/// Random string of printable alphanumeric ASCII characters with the given length.
+fn random_string(rng: &mut impl Rng, length: usize) -> String {
+    rng.sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
 #[derive(Deserialize, JsonSchema, Serialize)]
 #[schemars(rename_all = "camelCase", deny_unknown_fields)]
 pub struct SmallModel {
@@ -21,7 +31,7 @@ impl GetRootSchema for SmallModel {}
 
 impl RandomModelInstance for SmallModel {
     fn random() -> Self {
-        let mut rng = thread_rng();
+        let mut rng = rng();
         Self {
             creator: "keramik".to_string(),
             radius: rng.gen_range(0..100),
@@ -32,6 +42,48 @@ impl RandomModelInstance for SmallModel {
     }
 }
 
+/// Length of the description of a random MediumModel.
+const MEDIUM_MODEL_DESCRIPTION_LENGTH: usize = 200;
+
+#[derive(Deserialize, JsonSchema, Serialize)]
+#[schemars(rename_all = "camelCase", deny_unknown_fields)]
+pub struct MediumModel {
+    creator: String,
+    name: String,
+    description: String,
+    radius: i32,
+    tpe: i64,
+}
+
+impl GetRootSchema for MediumModel {}
+
+impl RandomModelInstance for MediumModel {
+    fn random() -> Self {
+        let mut rng = rng();
+        Self {
+            creator: "keramik".to_string(),
+            name: format!("keramik-medium-model-{}", rng.gen_range(0..1_000)),
+            description: random_string(&mut rng, MEDIUM_MODEL_DESCRIPTION_LENGTH),
+            radius: rng.gen_range(0..100),
+            tpe: rng.gen_range(0..100),
+        }
+    }
+}
+
+/// Length of the random suffix of the name of a random LargeModel.
+const LARGE_MODEL_NAME_LENGTH: usize = 100;
+/// Length of the description of a random LargeModel.
+const LARGE_MODEL_DESCRIPTION_LENGTH: usize = 1_000;
+
+/// Length of the description of random LargeModels, set from SIMULATE_LARGE_MODEL_SIZE.
+static LARGE_MODEL_SIZE: OnceLock<usize> = OnceLock::new();
+
+/// Set the length of the description of random LargeModels, e.g. to probe the block size limits
+/// of IPFS. Only the first size takes effect.
+pub fn set_large_model_size(size: usize) {
+    let _ = LARGE_MODEL_SIZE.set(size);
+}
+
 #[derive(Deserialize, JsonSchema, Serialize)]
 #[schemars(rename_all = "camelCase", deny_unknown_fields)]
 pub struct LargeModel {
@@ -45,13 +97,134 @@ impl GetRootSchema for LargeModel {}
 
 impl RandomModelInstance for LargeModel {
     fn random() -> Self {
-        let mut rng = thread_rng();
-        let name: String = (1..100).map(|_| rng.gen::<char>()).collect();
+        Self::random_sized(
+            LARGE_MODEL_SIZE
+                .get()
+                .copied()
+                .unwrap_or(LARGE_MODEL_DESCRIPTION_LENGTH),
+        )
+    }
+}
+
+impl LargeModel {
+    /// Random instance whose description has the given length.
+    pub fn random_sized(description_length: usize) -> Self {
+        Self::random_with_lengths(LARGE_MODEL_NAME_LENGTH, description_length)
+    }
+
+    /// Random instance whose random name suffix and description have the given lengths.
+    pub fn random_with_lengths(name_length: usize, description_length: usize) -> Self {
+        let mut rng = rng();
         Self {
             creator: "keramik".to_string(),
-            name: format!("keramik-large-model-{}", name),
-            description: (1..1_000).map(|_| rng.gen::<char>()).collect(),
+            name: format!(
+                "keramik-large-model-{}",
+                random_string(&mut rng, name_length)
+            ),
+            description: random_string(&mut rng, description_length),
             tpe: rng.gen_range(0..100),
         }
     }
 }
+
+/// Maximum number of tags of a random NestedModel.
+const NESTED_MODEL_MAX_TAGS: usize = 10;
+/// Length of each tag of a random NestedModel.
+const NESTED_MODEL_TAG_LENGTH: usize = 20;
+
+/// Model with a nested object and an array, shapes the flat models do not exercise when
+/// ComposeDB indexes their instances.
+#[derive(Deserialize, JsonSchema, Serialize)]
+#[schemars(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NestedModel {
+    creator: String,
+    position: Position,
+    tags: Vec<String>,
+}
+
+#[derive(Deserialize, JsonSchema, Serialize)]
+#[schemars(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Position {
+    x: i32,
+    y: i32,
+    label: String,
+}
+
+impl GetRootSchema for NestedModel {}
+
+impl RandomModelInstance for NestedModel {
+    fn random() -> Self {
+        let mut rng = rng();
+        let tags = rng.gen_range(1..=NESTED_MODEL_MAX_TAGS);
+        Self {
+            creator: "keramik".to_string(),
+            position: Position {
+                x: rng.gen_range(-1_000..1_000),
+                y: rng.gen_range(-1_000..1_000),
+                label: format!("keramik-position-{}", rng.gen_range(0..1_000)),
+            },
+            tags: (0..tags)
+                .map(|_| random_string(&mut rng, NESTED_MODEL_TAG_LENGTH))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn medium_model_description_length() {
+        let model = MediumModel::random();
+        assert_eq!(MEDIUM_MODEL_DESCRIPTION_LENGTH, model.description.len());
+        assert!(model.description.chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    #[test]
+    fn large_model_printable() {
+        let model = LargeModel::random_with_lengths(10, 50);
+        assert_eq!("keramik-large-model-".len() + 10, model.name.len());
+        assert_eq!(50, model.description.len());
+        assert!(model
+            .name
+            .chars()
+            .chain(model.description.chars())
+            .all(|c| c.is_ascii_graphic()));
+    }
+
+    #[test]
+    fn large_model_default_lengths() {
+        let model = LargeModel::random();
+        assert_eq!("keramik", model.creator);
+        assert_eq!(
+            "keramik-large-model-".len() + LARGE_MODEL_NAME_LENGTH,
+            model.name.len()
+        );
+        assert_eq!(LARGE_MODEL_DESCRIPTION_LENGTH, model.description.len());
+        assert!(model.description.is_ascii());
+    }
+
+    #[test]
+    fn large_model_sized() {
+        for size in [0, 4_096, 1_048_576] {
+            let model = LargeModel::random_sized(size);
+            assert_eq!(size, model.description.len());
+            assert_eq!(
+                "keramik-large-model-".len() + LARGE_MODEL_NAME_LENGTH,
+                model.name.len()
+            );
+        }
+    }
+
+    #[test]
+    fn nested_model_has_tags() {
+        let model = NestedModel::random();
+        assert!((1..=NESTED_MODEL_MAX_TAGS).contains(&model.tags.len()));
+        assert!(model
+            .tags
+            .iter()
+            .all(|tag| tag.len() == NESTED_MODEL_TAG_LENGTH
+                && tag.chars().all(|c| c.is_ascii_alphanumeric())));
+    }
+}
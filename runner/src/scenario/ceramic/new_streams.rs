@@ -7,6 +7,8 @@ use crate::scenario::ceramic::util::goose_error;
 use crate::scenario::ceramic::{
     models, setup, Credentials, LoadTestUserData, RandomModelInstance, StreamsResponseOrError,
 };
+use crate::scenario::request_builder;
+use crate::scenario::think_time::set_think_time;
 
 pub async fn scenario() -> Result<Scenario, GooseError> {
     let creds = Credentials::from_env().await.map_err(goose_error)?;
@@ -24,11 +26,15 @@ pub async fn scenario() -> Result<Scenario, GooseError> {
     let instantiate_large_model =
         transaction!(instantiate_large_model).set_name("instantiate_large_model");
 
-    Ok(scenario!("CeramicNewStreams")
-        .set_wait_time(Duration::from_millis(10), Duration::from_millis(100))?
+    let scenario = scenario!("CeramicNewStreams")
         .register_transaction(test_start)
         .register_transaction(instantiate_small_model)
-        .register_transaction(instantiate_large_model))
+        .register_transaction(instantiate_large_model);
+    set_think_time(
+        scenario,
+        Duration::from_millis(10),
+        Duration::from_millis(100),
+    )
 }
 
 async fn instantiate_small_model(user: &mut GooseUser) -> TransactionResult {
@@ -39,7 +45,7 @@ async fn instantiate_small_model(user: &mut GooseUser) -> TransactionResult {
         .create_list_instance_request(&model, &models::SmallModel::random())
         .await
         .unwrap();
-    let req = GooseRequest::builder()
+    let req = request_builder("instantiate_small_model")
         .method(GooseMethod::Post)
         .set_request_builder(
             user.client
@@ -69,7 +75,7 @@ async fn instantiate_large_model(user: &mut GooseUser) -> TransactionResult {
         .await
         .unwrap();
     let req = user.client.post(url).json(&req);
-    let req = GooseRequest::builder()
+    let req = request_builder("instantiate_large_model")
         .method(GooseMethod::Post)
         .set_request_builder(req)
         .expect_status_code(200)
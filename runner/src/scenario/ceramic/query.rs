@@ -1,7 +1,9 @@
 use crate::goose_try;
 use crate::scenario::ceramic::models::LargeModel;
 use crate::scenario::ceramic::util::{goose_error, index_model, setup_model, setup_model_instance};
-use crate::scenario::ceramic::{CeramicClient, Credentials};
+use crate::scenario::ceramic::{seed, CeramicClient, Credentials};
+use crate::scenario::request_builder;
+use crate::scenario::think_time::set_think_time;
 use ceramic_http_client::api::{Pagination, StreamsResponse, StreamsResponseOrError};
 use ceramic_http_client::ceramic_event::{JwkSigner, StreamId};
 use ceramic_http_client::{
@@ -53,7 +55,7 @@ pub async fn scenario() -> Result<Scenario, GooseError> {
     let creds = Credentials::from_env().await.map_err(goose_error)?;
     let cli = CeramicHttpClient::new(creds.signer);
 
-    let setup_cli = cli;
+    let setup_cli = cli.clone();
     let test_start = Transaction::new(Arc::new(move |user| {
         Box::pin(setup(user, setup_cli.clone()))
     }))
@@ -66,13 +68,14 @@ pub async fn scenario() -> Result<Scenario, GooseError> {
     let post_query_models =
         transaction!(query_models_post_update).set_name("post_update_query_models");
 
-    Ok(scenario!("CeramicQueryScenario")
-        // After each transactions runs, sleep randomly from 1 to 5 seconds.
-        .set_wait_time(Duration::from_secs(1), Duration::from_secs(5))?
+    let scenario = scenario!("CeramicQueryScenario")
         .register_transaction(test_start)
         .register_transaction(pre_query_models)
         .register_transaction(update_models)
-        .register_transaction(post_query_models))
+        .register_transaction(post_query_models);
+    // After each transactions runs, think from 1 to 5 seconds.
+    let scenario = set_think_time(scenario, Duration::from_secs(1), Duration::from_secs(5))?;
+    seed::register(scenario, cli)
 }
 
 #[instrument(skip_all, fields(user.index = user.weighted_users_index), ret)]
@@ -149,7 +152,7 @@ async fn query_models_pre_update(user: &mut GooseUser) -> TransactionResult {
     let cli = &user_data.cli;
     let mut goose = user
         .request(
-            GooseRequest::builder()
+            request_builder("pre_update_query_models")
                 .method(GooseMethod::Post)
                 .set_request_builder(
                     user.client
@@ -198,7 +201,7 @@ async fn update_models(user: &mut GooseUser) -> TransactionResult {
     let model_id = user_data.model_id_for_user(user);
     let cli = &user_data.cli;
     let streams_url = user.build_url(&format!("{}/{}", cli.streams_endpoint(), model_id))?;
-    let req = GooseRequest::builder()
+    let req = request_builder("update_models_get")
         .method(GooseMethod::Get)
         .set_request_builder(user.client.get(streams_url))
         .expect_status_code(200)
@@ -212,7 +215,7 @@ async fn update_models(user: &mut GooseUser) -> TransactionResult {
 
     let mut goose = user
         .request(
-            GooseRequest::builder()
+            request_builder("update_models")
                 .method(GooseMethod::Post)
                 .set_request_builder(
                     user.client
@@ -253,7 +256,7 @@ async fn query_models_post_update(user: &mut GooseUser) -> TransactionResult {
     let cli = &user_data.cli;
     let mut goose = user
         .request(
-            GooseRequest::builder()
+            request_builder("post_update_query_models")
                 .method(GooseMethod::Post)
                 .set_request_builder(
                     user.client
@@ -0,0 +1,31 @@
+use ceramic_http_client::CeramicHttpClient;
+use goose::prelude::*;
+use std::{sync::Arc, time::Duration};
+
+use crate::scenario::ceramic::util::goose_error;
+use crate::scenario::ceramic::{get_large_model, get_small_model, seed, setup, Credentials};
+use crate::scenario::think_time::set_think_time;
+
+pub async fn scenario() -> Result<Scenario, GooseError> {
+    let creds = Credentials::from_env().await.map_err(goose_error)?;
+    let cli = CeramicHttpClient::new(creds.signer);
+
+    let setup_cli = cli.clone();
+    let setup = Transaction::new(Arc::new(move |user| {
+        Box::pin(setup(user, setup_cli.clone()))
+    }))
+    .set_name("setup")
+    .set_on_start();
+
+    let get_small_model = transaction!(get_small_model).set_name("get_small_model");
+
+    let get_large_model = transaction!(get_large_model).set_name("get_large_model");
+
+    let scenario = scenario!("CeramicReadOnly")
+        .register_transaction(setup)
+        .register_transaction(get_small_model)
+        .register_transaction(get_large_model);
+    // After each transactions runs, think from 1 to 5 seconds.
+    let scenario = set_think_time(scenario, Duration::from_secs(1), Duration::from_secs(5))?;
+    seed::register(scenario, cli)
+}
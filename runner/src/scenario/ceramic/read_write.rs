@@ -0,0 +1,112 @@
+use anyhow::anyhow;
+use ceramic_http_client::CeramicHttpClient;
+use goose::prelude::*;
+use std::{sync::Arc, time::Duration};
+
+use crate::scenario::ceramic::util::goose_error;
+use crate::scenario::ceramic::{
+    get_large_model, get_small_model, seed, setup, update_large_model, update_small_model,
+    Credentials,
+};
+use crate::scenario::think_time::set_think_time;
+
+/// Percentage of the transactions reading model instances when SIMULATE_READ_RATIO is unset.
+const DEFAULT_READ_RATIO: usize = 80;
+
+pub async fn scenario() -> Result<Scenario, GooseError> {
+    let (read_weight, write_weight) = weights(read_ratio().map_err(goose_error)?);
+    let creds = Credentials::from_env().await.map_err(goose_error)?;
+    let cli = CeramicHttpClient::new(creds.signer);
+
+    let setup_cli = cli.clone();
+    let setup = Transaction::new(Arc::new(move |user| {
+        Box::pin(setup(user, setup_cli.clone()))
+    }))
+    .set_name("setup")
+    .set_on_start();
+
+    let mut scenario = scenario!("CeramicReadWrite").register_transaction(setup);
+    // Goose rejects transactions with a weight of zero, leave them out instead
+    if read_weight > 0 {
+        scenario = scenario
+            .register_transaction(
+                transaction!(get_small_model)
+                    .set_name("get_small_model")
+                    .set_weight(read_weight)?,
+            )
+            .register_transaction(
+                transaction!(get_large_model)
+                    .set_name("get_large_model")
+                    .set_weight(read_weight)?,
+            );
+    }
+    if write_weight > 0 {
+        scenario = scenario
+            .register_transaction(
+                transaction!(update_small_model)
+                    .set_name("update_small_model")
+                    .set_weight(write_weight)?,
+            )
+            .register_transaction(
+                transaction!(update_large_model)
+                    .set_name("update_large_model")
+                    .set_weight(write_weight)?,
+            );
+    }
+    // After each transactions runs, think from 1 to 5 seconds.
+    let scenario = set_think_time(scenario, Duration::from_secs(1), Duration::from_secs(5))?;
+    seed::register(scenario, cli)
+}
+
+/// Read the percentage of transactions reading model instances from SIMULATE_READ_RATIO.
+fn read_ratio() -> Result<usize, anyhow::Error> {
+    match std::env::var("SIMULATE_READ_RATIO") {
+        Ok(value) => parse_read_ratio(&value),
+        Err(_) => Ok(DEFAULT_READ_RATIO),
+    }
+}
+
+fn parse_read_ratio(value: &str) -> Result<usize, anyhow::Error> {
+    let ratio = value
+        .parse::<usize>()
+        .map_err(|e| anyhow!("invalid SIMULATE_READ_RATIO {value}: {e}"))?;
+    if ratio > 100 {
+        return Err(anyhow!(
+            "invalid SIMULATE_READ_RATIO {value}, expected a percentage from 0 to 100"
+        ));
+    }
+    Ok(ratio)
+}
+
+/// Weights of the read and write transactions, reduced so Goose schedules as few as possible.
+fn weights(read_ratio: usize) -> (usize, usize) {
+    let write_ratio = 100 - read_ratio;
+    let (mut a, mut b) = (read_ratio, write_ratio);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    (read_ratio / a, write_ratio / a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_read_ratios() {
+        assert_eq!(0, parse_read_ratio("0").unwrap());
+        assert_eq!(100, parse_read_ratio("100").unwrap());
+        assert!(parse_read_ratio("101").is_err());
+        assert!(parse_read_ratio("-1").is_err());
+        assert!(parse_read_ratio("half").is_err());
+    }
+
+    #[test]
+    fn reduced_weights() {
+        assert_eq!((4, 1), weights(DEFAULT_READ_RATIO));
+        assert_eq!((1, 1), weights(50));
+        assert_eq!((33, 67), weights(33));
+        assert_eq!((1, 0), weights(100));
+        assert_eq!((0, 1), weights(0));
+    }
+}
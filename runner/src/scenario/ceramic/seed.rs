@@ -0,0 +1,163 @@
+use crate::scenario::ceramic::models::{LargeModel, RandomModelInstance, SmallModel};
+use crate::scenario::ceramic::util::{goose_error, setup_model, setup_model_instance};
+use crate::scenario::ceramic::CeramicClient;
+use ceramic_http_client::{ModelAccountRelation, ModelDefinition};
+use goose::prelude::*;
+use std::{str::FromStr, sync::Arc};
+use tracing::instrument;
+
+/// Model of the documents created while seeding the network.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SeedModel {
+    Small,
+    Large,
+}
+
+impl FromStr for SeedModel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "small" => Ok(Self::Small),
+            "large" => Ok(Self::Large),
+            _ => Err(anyhow::anyhow!(
+                "unknown seed model {s}, expected small or large"
+            )),
+        }
+    }
+}
+
+/// How many documents of which models each user creates before the measured phase.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeedConfig {
+    documents: usize,
+    models: Vec<SeedModel>,
+}
+
+impl SeedConfig {
+    /// Read the seed config, returns None when the network should not be seeded.
+    pub fn from_env() -> Result<Option<Self>, GooseError> {
+        let documents = match std::env::var("SIMULATE_SEED_DOCUMENTS") {
+            Ok(value) => value.parse::<usize>().map_err(|e| {
+                goose_error(anyhow::anyhow!(
+                    "invalid SIMULATE_SEED_DOCUMENTS {value}: {e}"
+                ))
+            })?,
+            Err(_) => return Ok(None),
+        };
+        let models = match std::env::var("SIMULATE_SEED_MODELS") {
+            Ok(value) => value
+                .split(',')
+                .map(SeedModel::from_str)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(goose_error)?,
+            Err(_) => vec![SeedModel::Small],
+        };
+        Ok(Self::new(documents, models))
+    }
+
+    fn new(documents: usize, models: Vec<SeedModel>) -> Option<Self> {
+        (documents > 0 && !models.is_empty()).then_some(Self { documents, models })
+    }
+
+    /// Number of documents to create for each model, spreading the documents evenly across the
+    /// models in order.
+    fn plan(&self) -> Vec<(SeedModel, usize)> {
+        let per_model = self.documents / self.models.len();
+        let remainder = self.documents % self.models.len();
+        self.models
+            .iter()
+            .enumerate()
+            .map(|(i, model)| (*model, per_model + usize::from(i < remainder)))
+            .collect()
+    }
+}
+
+/// Register a transaction seeding the network when the users start, if seeding is configured.
+/// The transaction runs after any previously registered start transactions.
+pub fn register(scenario: Scenario, cli: CeramicClient) -> Result<Scenario, GooseError> {
+    let Some(config) = SeedConfig::from_env()? else {
+        return Ok(scenario);
+    };
+    let seed = Transaction::new(Arc::new(move |user| {
+        Box::pin(seed(user, cli.clone(), config.clone()))
+    }))
+    .set_name("seed")
+    .set_on_start();
+    Ok(scenario.register_transaction(seed))
+}
+
+#[instrument(skip_all, fields(user.index = user.weighted_users_index), ret)]
+async fn seed(user: &mut GooseUser, cli: CeramicClient, config: SeedConfig) -> TransactionResult {
+    for (model, documents) in config.plan() {
+        if documents == 0 {
+            continue;
+        }
+        match model {
+            SeedModel::Small => {
+                let definition = ModelDefinition::new::<SmallModel>(
+                    "load_test_seed_small_model",
+                    ModelAccountRelation::List,
+                )
+                .unwrap();
+                let model_id = setup_model(user, &cli, definition).await?;
+                for _ in 0..documents {
+                    setup_model_instance(user, &cli, &model_id, &SmallModel::random()).await?;
+                }
+            }
+            SeedModel::Large => {
+                let definition = ModelDefinition::new::<LargeModel>(
+                    "load_test_seed_large_model",
+                    ModelAccountRelation::List,
+                )
+                .unwrap();
+                let model_id = setup_model(user, &cli, definition).await?;
+                for _ in 0..documents {
+                    setup_model_instance(user, &cli, &model_id, &LargeModel::random()).await?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_seed_models() {
+        assert_eq!(SeedModel::Small, "small".parse().unwrap());
+        assert_eq!(SeedModel::Large, " large".parse().unwrap());
+        assert!("medium".parse::<SeedModel>().is_err());
+    }
+
+    #[test]
+    fn no_seed_without_documents_or_models() {
+        assert_eq!(None, SeedConfig::new(0, vec![SeedModel::Small]));
+        assert_eq!(None, SeedConfig::new(10, vec![]));
+    }
+
+    #[test]
+    fn plan_spreads_documents_across_models() {
+        let config = SeedConfig::new(5, vec![SeedModel::Small, SeedModel::Large]).unwrap();
+        assert_eq!(
+            vec![(SeedModel::Small, 3), (SeedModel::Large, 2)],
+            config.plan()
+        );
+    }
+
+    #[test]
+    fn plan_accounts_for_every_document() {
+        let models = vec![SeedModel::Small, SeedModel::Large, SeedModel::Small];
+        for documents in 1..20 {
+            let config = SeedConfig::new(documents, models.clone()).unwrap();
+            let plan = config.plan();
+            assert_eq!(models.len(), plan.len());
+            assert_eq!(documents, plan.iter().map(|(_, n)| n).sum::<usize>());
+            let max = plan.iter().map(|(_, n)| *n).max().unwrap();
+            let min = plan.iter().map(|(_, n)| *n).min().unwrap();
+            assert!(max - min <= 1);
+        }
+    }
+}
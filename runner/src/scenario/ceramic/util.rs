@@ -1,13 +1,55 @@
 use crate::scenario::ceramic::CeramicClient;
+use crate::scenario::request_builder;
 use ceramic_http_client::{api, ceramic_event::StreamId, ModelDefinition};
-use goose::goose::{GooseMethod, GooseRequest, GooseUser};
+use goose::goose::{GooseMethod, GooseUser};
 use goose::prelude::TransactionError;
 use goose::GooseError;
+use std::time::Duration;
 
 pub fn goose_error(err: anyhow::Error) -> GooseError {
     GooseError::Io(std::io::Error::new(std::io::ErrorKind::Other, err))
 }
 
+/// Range users wait for after each transaction, read in milliseconds from
+/// SIMULATE_WAIT_TIME_MIN and SIMULATE_WAIT_TIME_MAX. Unset bounds fall back to the defaults.
+pub fn wait_time(
+    default_min: Duration,
+    default_max: Duration,
+) -> Result<(Duration, Duration), GooseError> {
+    parse_wait_time(
+        std::env::var("SIMULATE_WAIT_TIME_MIN").ok().as_deref(),
+        std::env::var("SIMULATE_WAIT_TIME_MAX").ok().as_deref(),
+        default_min,
+        default_max,
+    )
+    .map_err(goose_error)
+}
+
+fn parse_wait_time(
+    min: Option<&str>,
+    max: Option<&str>,
+    default_min: Duration,
+    default_max: Duration,
+) -> Result<(Duration, Duration), anyhow::Error> {
+    let parse = |name: &str, value: Option<&str>, default: Duration| match value {
+        Some(value) => value
+            .parse::<u64>()
+            .map(Duration::from_millis)
+            .map_err(|e| anyhow::anyhow!("invalid {name} {value}: {e}")),
+        None => Ok(default),
+    };
+    let min = parse("SIMULATE_WAIT_TIME_MIN", min, default_min)?;
+    let max = parse("SIMULATE_WAIT_TIME_MAX", max, default_max)?;
+    if min > max {
+        return Err(anyhow::anyhow!(
+            "invalid wait time, SIMULATE_WAIT_TIME_MIN {}ms is greater than SIMULATE_WAIT_TIME_MAX {}ms",
+            min.as_millis(),
+            max.as_millis()
+        ));
+    }
+    Ok((min, max))
+}
+
 /// Macro to transform errors from an expression to a goose transaction failiure
 #[macro_export]
 macro_rules! goose_try {
@@ -34,7 +76,7 @@ pub async fn setup_model(
     let url = user.build_url(cli.streams_endpoint())?;
     let req = cli.create_model_request(&model).await.unwrap();
     let req = user.client.post(url).json(&req);
-    let req = GooseRequest::builder()
+    let req = request_builder("setup_model")
         .method(GooseMethod::Post)
         .set_request_builder(req)
         .expect_status_code(200)
@@ -56,7 +98,7 @@ pub async fn setup_model_instance<T: serde::Serialize>(
     let url = user.build_url(cli.streams_endpoint())?;
     let req = cli.create_list_instance_request(model, data).await.unwrap();
     let req = user.client.post(url).json(&req);
-    let req = GooseRequest::builder()
+    let req = request_builder("setup_model_instance")
         .method(GooseMethod::Post)
         .set_request_builder(req)
         .expect_status_code(200)
@@ -76,7 +118,7 @@ pub async fn index_model(
 ) -> Result<(), TransactionError> {
     let result = user
         .request(
-            GooseRequest::builder()
+            request_builder("admin_code")
                 .method(GooseMethod::Get)
                 .set_request_builder(user.client.get(user.build_url(cli.admin_code_endpoint())?))
                 .expect_status_code(200)
@@ -90,7 +132,7 @@ pub async fn index_model(
         .unwrap();
     let mut goose = user
         .request(
-            GooseRequest::builder()
+            request_builder("index_model")
                 .method(GooseMethod::Post)
                 .set_request_builder(
                     user.client
@@ -113,3 +155,36 @@ pub async fn index_model(
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN: Duration = Duration::from_millis(9000);
+    const MAX: Duration = Duration::from_millis(11000);
+
+    #[test]
+    fn default_wait_time() {
+        assert_eq!((MIN, MAX), parse_wait_time(None, None, MIN, MAX).unwrap());
+    }
+
+    #[test]
+    fn configured_wait_time() {
+        assert_eq!(
+            (Duration::ZERO, Duration::from_millis(10)),
+            parse_wait_time(Some("0"), Some("10"), MIN, MAX).unwrap()
+        );
+        assert_eq!(
+            (MIN, Duration::from_secs(60)),
+            parse_wait_time(None, Some("60000"), MIN, MAX).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_wait_time() {
+        assert!(parse_wait_time(Some("fast"), None, MIN, MAX).is_err());
+        assert!(parse_wait_time(None, Some("-1"), MIN, MAX).is_err());
+        // The minimum is above the default maximum
+        assert!(parse_wait_time(Some("20000"), None, MIN, MAX).is_err());
+    }
+}
@@ -2,14 +2,15 @@ use ceramic_http_client::CeramicHttpClient;
 use goose::prelude::*;
 use std::{sync::Arc, time::Duration};
 
-use crate::scenario::ceramic::util::goose_error;
-use crate::scenario::ceramic::{setup, update_large_model, update_small_model, Credentials};
+use crate::scenario::ceramic::util::{goose_error, wait_time};
+use crate::scenario::ceramic::{seed, setup, update_large_model, update_small_model, Credentials};
+use crate::scenario::think_time::set_think_time;
 
 pub async fn scenario() -> Result<Scenario, GooseError> {
     let creds = Credentials::from_env().await.map_err(goose_error)?;
     let cli = CeramicHttpClient::new(creds.signer);
 
-    let setup_cli = cli;
+    let setup_cli = cli.clone();
     let setup = Transaction::new(Arc::new(move |user| {
         Box::pin(setup(user, setup_cli.clone()))
     }))
@@ -20,9 +21,12 @@ pub async fn scenario() -> Result<Scenario, GooseError> {
 
     let update_large_model = transaction!(update_large_model).set_name("update_large_model");
 
-    Ok(scenario!("CeramicWriteOnly")
-        .set_wait_time(Duration::from_millis(9000), Duration::from_millis(11000))?
+    let (min_wait, max_wait) =
+        wait_time(Duration::from_millis(9000), Duration::from_millis(11000))?;
+    let scenario = scenario!("CeramicWriteOnly")
         .register_transaction(setup)
         .register_transaction(update_small_model)
-        .register_transaction(update_large_model))
+        .register_transaction(update_large_model);
+    let scenario = set_think_time(scenario, min_wait, max_wait)?;
+    seed::register(scenario, cli)
 }
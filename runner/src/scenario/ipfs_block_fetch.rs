@@ -6,6 +6,8 @@ use libipld::{ipld, json::DagJsonCodec};
 use multihash::{Code, MultihashDigest};
 use std::{sync::Arc, time::Duration};
 
+use crate::scenario::request_builder;
+use crate::scenario::think_time::set_think_time;
 use crate::simulate::Topology;
 
 pub fn scenario(topo: Topology) -> Result<Scenario> {
@@ -26,14 +28,14 @@ pub fn scenario(topo: Topology) -> Result<Scenario> {
     .set_name("check")
     .set_on_stop();
 
-    Ok(scenario!("IpfsRpc")
-        // After each transactions runs, sleep randomly from 1 to 5 seconds.
-        .set_wait_time(Duration::from_secs(1), Duration::from_secs(5))?
+    let scenario = scenario!("IpfsRpc")
         // This transaction only runs one time when the user first starts.
         .register_transaction(put)
         // These next two transactions run repeatedly as long as the load test is running.
         .register_transaction(get)
-        .register_transaction(check))
+        .register_transaction(check);
+    // After each transactions runs, think from 1 to 5 seconds.
+    set_think_time(scenario, Duration::from_secs(1), Duration::from_secs(5))
 }
 
 // Determine global unique id for user based on the worker id and total number of workers
@@ -73,7 +75,7 @@ async fn put(topo: Topology, user: &mut GooseUser) -> TransactionResult {
     let reqwest_request_builder = user.client.post(url).multipart(form);
 
     // POST request.
-    let goose_request = GooseRequest::builder()
+    let goose_request = request_builder("dag_put")
         .method(GooseMethod::Post)
         .path(path)
         .set_request_builder(reqwest_request_builder)
@@ -105,7 +107,7 @@ async fn get(mut topo: Topology, user: &mut GooseUser) -> TransactionResult {
         .timeout(Duration::from_secs(5));
 
     // Manually build a GooseRequest.
-    let goose_request = GooseRequest::builder()
+    let goose_request = request_builder("dag_get")
         // Manually add our custom RequestBuilder object.
         .set_request_builder(request_builder)
         .expect_status_code(200)
@@ -133,7 +135,7 @@ async fn check(topo: Topology, user: &mut GooseUser) -> TransactionResult {
         .timeout(Duration::from_secs(15));
 
     // Manually build a GooseRequest.
-    let goose_request = GooseRequest::builder()
+    let goose_request = request_builder("check")
         // Manually add our custom RequestBuilder object.
         .set_request_builder(request_builder)
         .expect_status_code(200) // Turn the GooseRequestBuilder object into a GooseRequest.
@@ -1,11 +1,121 @@
 use crate::scenario::ceramic::util::goose_error;
-use goose::GooseError;
+use goose::{
+    goose::{GooseRequest, GooseRequestBuilder},
+    GooseError,
+};
+use rand::{rngs::StdRng, thread_rng, SeedableRng};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
 
 pub mod ceramic;
 pub mod ipfs_block_fetch;
+pub mod think_time;
+
+/// Generator all scenario randomness derives from when the simulation is seeded.
+static SEEDED_RNG: OnceLock<Mutex<StdRng>> = OnceLock::new();
+
+/// Target peer the requests are tagged with, read once from the env.
+static PEER_TAG: OnceLock<Option<usize>> = OnceLock::new();
+
+/// Tagged request names, leaked once per name since goose borrows the names of requests.
+static TAGGED_NAMES: OnceLock<Mutex<HashMap<&'static str, &'static str>>> = OnceLock::new();
+
+/// Seed the randomness of the scenarios, only the first seed takes effect.
+pub fn seed_rng(seed: u64) {
+    let _ = SEEDED_RNG.set(Mutex::new(StdRng::seed_from_u64(seed)));
+}
+
+/// Random number generator for scenario data. Generators are derived from the seed when the
+/// simulation is seeded, so the generated data is reproducible for the same sequence of calls.
+pub fn rng() -> StdRng {
+    match SEEDED_RNG.get() {
+        Some(seeded) => {
+            let mut seeded = seeded
+                .lock()
+                .expect("seeded rng lock should not be poisoned");
+            StdRng::from_rng(&mut *seeded).expect("seeded rng should not fail")
+        }
+        None => StdRng::from_rng(thread_rng()).expect("thread rng should not fail"),
+    }
+}
+
+/// Builder of a scenario request. When SIMULATE_TAG_PEER is true the request is named after
+/// the given name and the target peer of the worker, so the report of a distributed simulation
+/// breaks the requests down per peer. Otherwise goose names the request after its path.
+pub fn request_builder<'a>(name: &'static str) -> GooseRequestBuilder<'a> {
+    let builder = GooseRequest::builder();
+    match peer_tag() {
+        Some(peer) => builder.name(tagged_name(name, peer)),
+        None => builder,
+    }
+}
+
+/// Name of a request that is always named, tagged with the target peer when SIMULATE_TAG_PEER
+/// is true.
+pub fn request_name(name: &'static str) -> &'static str {
+    match peer_tag() {
+        Some(peer) => tagged_name(name, peer),
+        None => name,
+    }
+}
+
+fn peer_tag() -> Option<usize> {
+    *PEER_TAG.get_or_init(|| {
+        parse_peer_tag(
+            std::env::var("SIMULATE_TAG_PEER").ok().as_deref(),
+            std::env::var("SIMULATE_TARGET_PEER").ok().as_deref(),
+        )
+    })
+}
+
+/// Target peer to tag requests with, None unless tagging is enabled for a valid target peer.
+fn parse_peer_tag(tag_peer: Option<&str>, target_peer: Option<&str>) -> Option<usize> {
+    let enabled = tag_peer.and_then(|value| value.parse::<bool>().ok());
+    if enabled != Some(true) {
+        return None;
+    }
+    target_peer?.parse().ok()
+}
+
+fn tagged_name(name: &'static str, peer: usize) -> &'static str {
+    let mut names = TAGGED_NAMES
+        .get_or_init(Default::default)
+        .lock()
+        .expect("tagged names lock should not be poisoned");
+    names
+        .entry(name)
+        .or_insert_with(|| Box::leak(format!("{name}_peer_{peer}").into_boxed_str()))
+}
 
 pub async fn get_redis_client() -> Result<redis::Client, GooseError> {
     let redis_host =
         std::env::var("REDIS_CONNECTION_STRING").unwrap_or("redis://redis:6379".to_string());
     redis::Client::open(redis_host).map_err(|e| goose_error(e.into()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peer_tag_from_env() {
+        assert_eq!(Some(3), parse_peer_tag(Some("true"), Some("3")));
+        assert_eq!(None, parse_peer_tag(None, Some("3")));
+        assert_eq!(None, parse_peer_tag(Some("false"), Some("3")));
+        assert_eq!(None, parse_peer_tag(Some("yes"), Some("3")));
+        assert_eq!(None, parse_peer_tag(Some("true"), None));
+        assert_eq!(None, parse_peer_tag(Some("true"), Some("first")));
+    }
+
+    #[test]
+    fn tagged_request_names() {
+        assert_eq!("setup_model_peer_2", tagged_name("setup_model", 2));
+        // Names are only tagged once per process
+        assert!(std::ptr::eq(
+            tagged_name("setup_model", 2),
+            tagged_name("setup_model", 2)
+        ));
+    }
+}
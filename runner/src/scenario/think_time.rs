@@ -0,0 +1,186 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::anyhow;
+use goose::prelude::*;
+use rand::Rng;
+
+use crate::scenario::{ceramic::util::goose_error, rng};
+
+/// Shape of the pareto distribution when SIMULATE_THINK_TIME_SHAPE is unset,
+/// following the 80-20 rule.
+const DEFAULT_PARETO_SHAPE: f64 = 1.16;
+
+/// Distribution of the time users think for after each transaction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ThinkTime {
+    /// Uniformly random between min and max.
+    Uniform { min: Duration, max: Duration },
+    /// Always the same time.
+    Constant(Duration),
+    /// Long tailed, starting at scale and capped at max.
+    Pareto {
+        scale: Duration,
+        shape: f64,
+        max: Duration,
+    },
+}
+
+impl ThinkTime {
+    /// Read the distribution from SIMULATE_THINK_TIME, one of `uniform` (default), `constant`
+    /// and `pareto`. Constant users think for min, pareto times start at min and are capped at
+    /// max with the shape read from SIMULATE_THINK_TIME_SHAPE.
+    pub fn from_env(min: Duration, max: Duration) -> Result<Self, anyhow::Error> {
+        Self::parse(
+            std::env::var("SIMULATE_THINK_TIME").ok().as_deref(),
+            std::env::var("SIMULATE_THINK_TIME_SHAPE").ok().as_deref(),
+            min,
+            max,
+        )
+    }
+
+    fn parse(
+        distribution: Option<&str>,
+        shape: Option<&str>,
+        min: Duration,
+        max: Duration,
+    ) -> Result<Self, anyhow::Error> {
+        match distribution.unwrap_or("uniform") {
+            "uniform" => Ok(Self::Uniform { min, max }),
+            "constant" => Ok(Self::Constant(min)),
+            "pareto" => {
+                let shape = match shape {
+                    Some(value) => value
+                        .parse::<f64>()
+                        .map_err(|e| anyhow!("invalid SIMULATE_THINK_TIME_SHAPE {value}: {e}"))?,
+                    None => DEFAULT_PARETO_SHAPE,
+                };
+                if shape.is_nan() || shape <= 0.0 {
+                    return Err(anyhow!(
+                        "invalid SIMULATE_THINK_TIME_SHAPE {shape}, must be positive"
+                    ));
+                }
+                Ok(Self::Pareto {
+                    scale: min,
+                    shape,
+                    max,
+                })
+            }
+            other => Err(anyhow!(
+                "unknown SIMULATE_THINK_TIME {other}, expected uniform, constant or pareto"
+            )),
+        }
+    }
+
+    /// Time a user thinks for after a transaction.
+    pub fn sample(&self, rng: &mut impl Rng) -> Duration {
+        match *self {
+            Self::Uniform { min, max } => rng.gen_range(min..=max),
+            Self::Constant(time) => time,
+            Self::Pareto { scale, shape, max } => {
+                // Inverse transform sampling, u is in (0, 1] so the time is at least the scale
+                let u = 1.0 - rng.gen::<f64>();
+                let time = scale.as_secs_f64() / u.powf(1.0 / shape);
+                Duration::try_from_secs_f64(time).unwrap_or(max).min(max)
+            }
+        }
+    }
+
+    /// Make the users of the scenario think after each of its transactions. Uniform think times
+    /// use the wait time of goose, other distributions sleep at the end of the transactions.
+    pub fn apply(self, mut scenario: Scenario) -> Result<Scenario, GooseError> {
+        if let Self::Uniform { min, max } = self {
+            return scenario.set_wait_time(min, max);
+        }
+        for transaction in scenario
+            .transactions
+            .iter_mut()
+            .filter(|transaction| !transaction.on_start && !transaction.on_stop)
+        {
+            let function = transaction.function.clone();
+            transaction.function = Arc::new(move |user| {
+                let function = function.clone();
+                Box::pin(async move {
+                    let result = function(user).await;
+                    let think_time = self.sample(&mut rng());
+                    tokio::time::sleep(think_time).await;
+                    result
+                })
+            });
+        }
+        Ok(scenario)
+    }
+}
+
+/// Make the users of the scenario think for between min and max after each transaction,
+/// drawn from the distribution configured by SIMULATE_THINK_TIME.
+pub fn set_think_time(
+    scenario: Scenario,
+    min: Duration,
+    max: Duration,
+) -> Result<Scenario, GooseError> {
+    ThinkTime::from_env(min, max)
+        .map_err(goose_error)?
+        .apply(scenario)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const MIN: Duration = Duration::from_secs(1);
+    const MAX: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn parse_think_time() {
+        assert_eq!(
+            ThinkTime::Uniform { min: MIN, max: MAX },
+            ThinkTime::parse(None, None, MIN, MAX).unwrap()
+        );
+        assert_eq!(
+            ThinkTime::Constant(MIN),
+            ThinkTime::parse(Some("constant"), None, MIN, MAX).unwrap()
+        );
+        assert_eq!(
+            ThinkTime::Pareto {
+                scale: MIN,
+                shape: 2.0,
+                max: MAX
+            },
+            ThinkTime::parse(Some("pareto"), Some("2"), MIN, MAX).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_think_time() {
+        assert!(ThinkTime::parse(Some("normal"), None, MIN, MAX).is_err());
+        assert!(ThinkTime::parse(Some("pareto"), Some("steep"), MIN, MAX).is_err());
+        assert!(ThinkTime::parse(Some("pareto"), Some("0"), MIN, MAX).is_err());
+        assert!(ThinkTime::parse(Some("pareto"), Some("NaN"), MIN, MAX).is_err());
+    }
+
+    #[test]
+    fn pareto_think_time_bounds() {
+        let mut rng = StdRng::seed_from_u64(1234);
+        let think_time = ThinkTime::Pareto {
+            scale: MIN,
+            shape: DEFAULT_PARETO_SHAPE,
+            max: MAX,
+        };
+        let samples: Vec<Duration> = (0..10_000).map(|_| think_time.sample(&mut rng)).collect();
+        assert!(samples.iter().all(|sample| (MIN..=MAX).contains(sample)));
+        // Long tailed, most users think briefly while some think for the maximum
+        let short = samples.iter().filter(|sample| **sample < 2 * MIN).count();
+        assert!(short > samples.len() / 2);
+        assert!(samples.contains(&MAX));
+    }
+
+    #[test]
+    fn constant_and_uniform_think_time_bounds() {
+        let mut rng = StdRng::seed_from_u64(1234);
+        assert_eq!(MIN, ThinkTime::Constant(MIN).sample(&mut rng));
+        let uniform = ThinkTime::Uniform { min: MIN, max: MAX };
+        assert!((0..1000).all(|_| (MIN..=MAX).contains(&uniform.sample(&mut rng))));
+    }
+}
@@ -11,7 +11,7 @@ use opentelemetry::{global, metrics::ObservableGauge, Context, KeyValue};
 use tracing::error;
 
 use crate::{
-    scenario::{ceramic, ipfs_block_fetch},
+    scenario::{self, ceramic, ipfs_block_fetch},
     utils::parse_peers_info,
 };
 
@@ -51,6 +51,37 @@ pub struct Opts {
     /// Option to throttle requests (per second) for load control
     #[arg(long, env = "SIMULATE_THROTTLE_REQUESTS")]
     throttle_requests: Option<usize>,
+
+    /// Minimum throughput (requests per second), the simulation fails when it is not reached.
+    #[arg(long, env = "SIMULATE_MIN_THROUGHPUT")]
+    min_throughput: Option<f64>,
+
+    /// Maximum ratio of failed requests (0.0 - 1.0), the simulation fails when it is exceeded.
+    #[arg(long, env = "SIMULATE_MAX_ERROR_RATE")]
+    max_error_rate: Option<f64>,
+
+    /// Only target the peers created from the ceramic spec with this name.
+    #[arg(long, env = "SIMULATE_TARGET_SPEC")]
+    target_spec: Option<String>,
+
+    /// Seed of the randomness of the scenario data, the data is random across runs when unset.
+    /// Each worker derives its own data from the seed and its target peer.
+    #[arg(long, env = "SIMULATE_SEED")]
+    seed: Option<u64>,
+
+    /// Host name of the manager, workers connect to it to receive their instructions.
+    #[arg(long, env = "SIMULATE_MANAGER_HOST", default_value = "manager.goose")]
+    manager_host: String,
+
+    /// Length of the description of the large model instances the scenarios write, e.g. to
+    /// probe the block size limits of IPFS. Defaults to 1000.
+    #[arg(long, env = "SIMULATE_LARGE_MODEL_SIZE")]
+    large_model_size: Option<usize>,
+
+    /// Revision of the code under test, added to the attributes of the metrics so they can be
+    /// sliced by build.
+    #[arg(long, env = "SIMULATE_REVISION")]
+    revision: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -68,12 +99,20 @@ pub enum Scenario {
     CeramicSimple,
     /// WriteOnly Ceramic Scenario
     CeramicWriteOnly,
+    /// ReadOnly Ceramic Scenario
+    CeramicReadOnly,
+    /// Mixed reads and writes Ceramic Scenario, reading SIMULATE_READ_RATIO percent of the time
+    CeramicReadWrite,
     /// New Streams Ceramic Scenario
     CeramicNewStreams,
     /// Simple Query Scenario
     CeramicQuery,
     /// Scenario to reuse the same model id and query instances across workers
     CeramicModelReuse,
+    /// Writes to the target peer and verifies the write is visible on all other peers
+    CeramicConsistency,
+    /// Registers a fixed catalog of models, then creates and queries instances of them
+    CeramicCatalog,
 }
 
 impl Scenario {
@@ -82,9 +121,13 @@ impl Scenario {
             Scenario::IpfsRpc => "ipfs_rpc",
             Scenario::CeramicSimple => "ceramic_simple",
             Scenario::CeramicWriteOnly => "ceramic_write_only",
+            Scenario::CeramicReadOnly => "ceramic_read_only",
+            Scenario::CeramicReadWrite => "ceramic_read_write",
             Scenario::CeramicNewStreams => "ceramic_new_streams",
             Scenario::CeramicQuery => "ceramic_query",
             Scenario::CeramicModelReuse => "ceramic_model_reuse",
+            Scenario::CeramicConsistency => "ceramic_consistency",
+            Scenario::CeramicCatalog => "ceramic_catalog",
         }
     }
 
@@ -93,9 +136,13 @@ impl Scenario {
             Self::IpfsRpc => Ok(peer.ipfs_rpc_addr().to_owned()),
             Self::CeramicSimple
             | Self::CeramicWriteOnly
+            | Self::CeramicReadOnly
+            | Self::CeramicReadWrite
             | Self::CeramicNewStreams
             | Self::CeramicQuery
-            | Self::CeramicModelReuse => match peer {
+            | Self::CeramicModelReuse
+            | Self::CeramicConsistency
+            | Self::CeramicCatalog => match peer {
                 Peer::Ceramic(peer) => Ok(peer.ceramic_addr.clone()),
                 Peer::Ipfs(_) => Err(anyhow!(
                     "cannot use non ceramic peer as target for simulation {}",
@@ -114,7 +161,15 @@ pub async fn simulate(opts: Opts) -> Result<()> {
         .await?
         .into_iter()
         .filter(|peer| matches!(peer, Peer::Ceramic(_)))
+        .filter(|peer| {
+            opts.target_spec
+                .as_deref()
+                .map_or(true, |target_spec| peer.spec_name() == Some(target_spec))
+        })
         .collect();
+    if peers.is_empty() {
+        bail!("no ceramic peers to target");
+    }
 
     if opts.manager && opts.users % peers.len() != 0 {
         bail!("number of users {} must be a multiple of the number of peers {}, this ensures we can deterministically identifiy each user", opts.users, peers.len())
@@ -127,13 +182,24 @@ pub async fn simulate(opts: Opts) -> Result<()> {
         nonce: opts.nonce,
     };
 
+    if let Some(seed) = opts.seed {
+        scenario::seed_rng(seed.wrapping_add(opts.target_peer as u64));
+    }
+    if let Some(size) = opts.large_model_size {
+        ceramic::models::set_large_model_size(size);
+    }
+
     let scenario = match opts.scenario {
         Scenario::IpfsRpc => ipfs_block_fetch::scenario(topo)?,
         Scenario::CeramicSimple => ceramic::scenario().await?,
         Scenario::CeramicWriteOnly => ceramic::write_only::scenario().await?,
+        Scenario::CeramicReadOnly => ceramic::read_only::scenario().await?,
+        Scenario::CeramicReadWrite => ceramic::read_write::scenario().await?,
         Scenario::CeramicNewStreams => ceramic::new_streams::scenario().await?,
         Scenario::CeramicQuery => ceramic::query::scenario().await?,
         Scenario::CeramicModelReuse => ceramic::model_reuse::scenario().await?,
+        Scenario::CeramicConsistency => ceramic::consistency::scenario(topo, &peers).await?,
+        Scenario::CeramicCatalog => ceramic::catalog::scenario().await?,
     };
     let config = if opts.manager {
         manager_config(peers.len(), opts.users, opts.run_time)
@@ -144,6 +210,7 @@ pub async fn simulate(opts: Opts) -> Result<()> {
                     .get(opts.target_peer)
                     .ok_or_else(|| anyhow!("target peer too large, not enough peers"))?,
             )?,
+            opts.manager_host,
             opts.throttle_requests,
         )
     };
@@ -160,9 +227,89 @@ pub async fn simulate(opts: Opts) -> Result<()> {
         }
     };
 
+    let summary = RequestSummary::from(&goose_metrics);
     metrics.record(goose_metrics);
 
-    Ok(())
+    Thresholds {
+        min_throughput: opts.min_throughput,
+        max_error_rate: opts.max_error_rate,
+    }
+    .check(&summary)
+}
+
+/// Totals of the requests made during a simulation.
+#[derive(Debug)]
+struct RequestSummary {
+    total: usize,
+    failed: usize,
+    /// Duration of the simulation in seconds
+    duration: usize,
+}
+
+impl RequestSummary {
+    fn throughput(&self) -> f64 {
+        self.total as f64 / self.duration.max(1) as f64
+    }
+    fn error_rate(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.failed as f64 / self.total as f64
+        }
+    }
+}
+
+impl From<&GooseMetrics> for RequestSummary {
+    fn from(metrics: &GooseMetrics) -> Self {
+        let mut total = 0;
+        let mut failed = 0;
+        for req_metrics in metrics.requests.values() {
+            total += req_metrics.success_count + req_metrics.fail_count;
+            failed += req_metrics.fail_count;
+        }
+        Self {
+            total,
+            failed,
+            duration: metrics.duration,
+        }
+    }
+}
+
+/// Performance thresholds a simulation must meet to pass, unset thresholds are not checked.
+#[derive(Debug, Default)]
+struct Thresholds {
+    min_throughput: Option<f64>,
+    max_error_rate: Option<f64>,
+}
+
+impl Thresholds {
+    fn check(&self, summary: &RequestSummary) -> Result<()> {
+        let mut violations = Vec::new();
+        if let Some(min_throughput) = self.min_throughput {
+            let throughput = summary.throughput();
+            if throughput < min_throughput {
+                violations.push(format!(
+                    "throughput {throughput:.2} requests/s is below the minimum {min_throughput}"
+                ));
+            }
+        }
+        if let Some(max_error_rate) = self.max_error_rate {
+            let error_rate = summary.error_rate();
+            if error_rate > max_error_rate {
+                violations.push(format!(
+                    "error rate {error_rate:.4} is above the maximum {max_error_rate}"
+                ));
+            }
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            bail!(
+                "simulation failed its thresholds: {}",
+                violations.join(", ")
+            )
+        }
+    }
 }
 
 fn manager_config(count: usize, users: usize, run_time: String) -> GooseConfiguration {
@@ -176,15 +323,19 @@ fn manager_config(count: usize, users: usize, run_time: String) -> GooseConfigur
     config.run_time = run_time;
     config
 }
-fn worker_config(target_peer_addr: String, throttle_requests: Option<usize>) -> GooseConfiguration {
+fn worker_config(
+    target_peer_addr: String,
+    manager_host: String,
+    throttle_requests: Option<usize>,
+) -> GooseConfiguration {
     let mut config = GooseConfiguration::default();
     config.request_log = "request.log".to_owned();
     config.log_level = 2;
     config.worker = true;
     config.host = target_peer_addr;
-    // We are leveraging k8s dns search path so we do not have to specify the fully qualified
-    // domain name explicitly.
-    config.manager_host = "manager.goose".to_owned();
+    // The operator sets the fully qualified domain name of the manager, by default we leverage
+    // the k8s dns search path.
+    config.manager_host = manager_host;
     config.manager_port = 5115;
     if let Some(throttle_requests) = throttle_requests {
         config.throttle_requests = throttle_requests
@@ -223,6 +374,9 @@ impl Metrics {
         if !opts.manager {
             attrs.push(KeyValue::new("worker_id", opts.target_peer.to_string()));
         }
+        if let Some(revision) = &opts.revision {
+            attrs.push(KeyValue::new("revision", revision.clone()));
+        }
 
         let meter = global::meter("simulate");
         let duration = meter
@@ -416,3 +570,59 @@ impl MetricsInner {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(total: usize, failed: usize, duration: usize) -> RequestSummary {
+        RequestSummary {
+            total,
+            failed,
+            duration,
+        }
+    }
+
+    #[test]
+    fn no_thresholds_pass() {
+        assert!(Thresholds::default().check(&summary(0, 0, 60)).is_ok());
+        assert!(Thresholds::default().check(&summary(100, 100, 60)).is_ok());
+    }
+
+    #[test]
+    fn min_throughput() {
+        let thresholds = Thresholds {
+            min_throughput: Some(10.0),
+            ..Default::default()
+        };
+        assert!(thresholds.check(&summary(600, 0, 60)).is_ok());
+        assert!(thresholds.check(&summary(599, 0, 60)).is_err());
+        assert!(thresholds.check(&summary(0, 0, 0)).is_err());
+    }
+
+    #[test]
+    fn max_error_rate() {
+        let thresholds = Thresholds {
+            max_error_rate: Some(0.01),
+            ..Default::default()
+        };
+        assert!(thresholds.check(&summary(1000, 10, 60)).is_ok());
+        assert!(thresholds.check(&summary(1000, 11, 60)).is_err());
+        // No requests means no errors
+        assert!(thresholds.check(&summary(0, 0, 60)).is_ok());
+    }
+
+    #[test]
+    fn reports_all_violations() {
+        let thresholds = Thresholds {
+            min_throughput: Some(10.0),
+            max_error_rate: Some(0.01),
+        };
+        let err = thresholds
+            .check(&summary(60, 30, 60))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("throughput"), "{err}");
+        assert!(err.contains("error rate"), "{err}");
+    }
+}
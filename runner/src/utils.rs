@@ -1,7 +1,7 @@
 use std::path::Path;
 
 use anyhow::{bail, Result};
-use keramik_common::peer_info::Peer;
+use keramik_common::peer_info::{decompress_peers, Peer};
 use tokio::{fs::File, io::AsyncReadExt};
 use tracing::debug;
 
@@ -46,9 +46,30 @@ pub async fn connect_peers(peer: &Peer, other: &Peer) -> Result<()> {
 }
 
 /// Parse the peers info file.
+/// When the peers are compressed or sharded they are read from the same directory instead.
 pub async fn parse_peers_info(path: impl AsRef<Path>) -> Result<Vec<Peer>> {
+    let path = path.as_ref();
+    let compressed_path = path.with_file_name("peers.json.gz");
+    if compressed_path.exists() {
+        return decompress_peers(&read_file(&compressed_path).await?);
+    }
+    let mut peers: Vec<Peer> = serde_json::from_str(&read_file(path).await?)?;
+    let shards_path = path.with_file_name("shards");
+    if shards_path.exists() {
+        let shards: usize = read_file(&shards_path).await?.trim().parse()?;
+        for shard in 1..shards {
+            let shard_path = path.with_file_name(format!("peers-{shard}.json"));
+            peers.extend(serde_json::from_str::<Vec<Peer>>(
+                &read_file(&shard_path).await?,
+            )?);
+        }
+    }
+    Ok(peers)
+}
+
+async fn read_file(path: &Path) -> Result<String> {
     let mut f = File::open(path).await?;
-    let mut peers_json = String::new();
-    f.read_to_string(&mut peers_json).await?;
-    Ok(serde_json::from_str(&peers_json)?)
+    let mut data = String::new();
+    f.read_to_string(&mut data).await?;
+    Ok(data)
 }